@@ -0,0 +1,66 @@
+//! Compares make and unmake with keeping a snapshot of the game for every action
+//!
+//! Run with `cargo bench`, it needs no extra crates.
+use bughouse_rs::logic::perft::position;
+use bughouse_rs::logic::rules::Rules;
+use bughouse_rs::logic::types::BoardId;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+///Returns the time per iteration of a function, the best of several rounds
+fn measure<F: FnMut()>(mut f: F) -> Duration {
+    for _ in 0..10 {
+        f();
+    }
+    let iterations = 200;
+    (0..10)
+        .map(|_| {
+            let start = Instant::now();
+            for _ in 0..iterations {
+                f();
+            }
+            start.elapsed() / iterations
+        })
+        .min()
+        .unwrap()
+}
+
+fn main() {
+    let board = BoardId::Board1;
+    for (name, bfen) in [
+        (
+            "kiwipete",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        ),
+        ("pools", "2k5/8/8/8/8/8/8/4K3[QRBNPqrbnp] w - - 0 1"),
+    ]
+    .iter()
+    {
+        let mut cl = position(bfen, Rules::classical()).unwrap();
+        let moves = cl.compact_moves(board);
+        //the state is kept in a snapshot and restored after the action
+        let snapshot = measure(|| {
+            for m in moves.iter() {
+                let before = cl.snapshot();
+                cl.make(board, *m, true);
+                black_box(cl.get_hash(board));
+                cl.restore(&before);
+            }
+        });
+        let make = measure(|| {
+            for m in moves.iter() {
+                let undo = cl.make(board, *m, true);
+                black_box(cl.get_hash(board));
+                cl.unmake(&undo);
+            }
+        });
+        println!(
+            "{:<10} {} actions  snapshot and restore {:>10?}  make and unmake {:>10?} ({:.1}x)",
+            name,
+            moves.len(),
+            snapshot,
+            make,
+            snapshot.as_secs_f64() / make.as_secs_f64()
+        );
+    }
+}
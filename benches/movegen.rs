@@ -0,0 +1,216 @@
+//! Times the bitboard move generation against an array scan of the same moves
+//!
+//! Run with `cargo bench`, it needs no extra crates.
+use bughouse_rs::logic::board::{ChessBoard, Piece};
+use bughouse_rs::logic::types::{BoardId, Square};
+use bughouse_rs::logic::ChessLogic;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+///Returns the time per iteration of a function, the best of several rounds
+fn measure<F: FnMut()>(mut f: F) -> Duration {
+    for _ in 0..100 {
+        f();
+    }
+    let iterations = 2000;
+    (0..10)
+        .map(|_| {
+            let start = Instant::now();
+            for _ in 0..iterations {
+                f();
+            }
+            start.elapsed() / iterations
+        })
+        .min()
+        .unwrap()
+}
+
+///Returns the start position and a middlegame with pieces on every part of the board
+///
+/// White is to move and cannot castle, capture en passant, promote or deploy in either, so the array scan below finds
+/// the same moves.
+fn positions() -> Vec<(&'static str, ChessLogic)> {
+    let mut middlegame = ChessLogic::new();
+    for m in [
+        "e4", "e5", "Nf3", "Nc6", "Bb5", "a6", "Ba4", "Nf6", "O-O", "Be7", "Re1", "b5", "Bb3", "d6", "c3", "Bg4",
+    ]
+    .iter()
+    {
+        middlegame.play_san(BoardId::Board1, m).unwrap();
+    }
+    vec![("start", ChessLogic::new()), ("middlegame", middlegame)]
+}
+
+///Steps of a piece as (row,col)
+type Offsets = &'static [(i32, i32)];
+
+const KNIGHT: [(i32, i32); 8] = [(-2, -1), (-2, 1), (2, -1), (2, 1), (-1, -2), (-1, 2), (1, -2), (1, 2)];
+const KING: [(i32, i32); 8] = [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)];
+const ROOK: [(i32, i32); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+const BISHOP: [(i32, i32); 4] = [(1, 1), (1, -1), (-1, 1), (-1, -1)];
+
+///Returns the piece on a square, None outside of the board
+fn at(b: &ChessBoard, i: i32, j: i32) -> Option<Piece> {
+    if (0..8).contains(&i) && (0..8).contains(&j) {
+        Some(b.board[i as usize][j as usize])
+    } else {
+        None
+    }
+}
+
+///Returns true if a piece of a colour attacks a square, by walking from the square like the code before the bitboards
+fn scan_attacked(b: &ChessBoard, i: usize, j: usize, by_white: bool) -> bool {
+    let (i, j) = (i as i32, j as i32);
+    let ours = |x: Piece| if by_white { x.is_white() } else { x.is_black() };
+    let is = |x: Piece, white: Piece, black: Piece| x == if by_white { white } else { black };
+    let step_hits = |offsets: &[(i32, i32)], hit: &dyn Fn(Piece) -> bool| {
+        offsets
+            .iter()
+            .any(|(di, dj)| matches!(at(b, i + di, j + dj), Some(x) if ours(x) && hit(x)))
+    };
+    let ray_hits = |dirs: &[(i32, i32)], hit: &dyn Fn(Piece) -> bool| {
+        dirs.iter().any(|(di, dj)| {
+            let (mut a, mut c) = (i + di, j + dj);
+            while let Some(x) = at(b, a, c) {
+                if x != Piece::E {
+                    return ours(x) && hit(x);
+                }
+                a += di;
+                c += dj;
+            }
+            false
+        })
+    };
+    let pawn_row = if by_white { 1 } else { -1 };
+    step_hits(&[(pawn_row, -1), (pawn_row, 1)], &|x| is(x, Piece::P, Piece::p))
+        || step_hits(&KNIGHT, &|x| matches!(x, Piece::N | Piece::UN | Piece::n | Piece::Un))
+        || step_hits(&KING, &|x| is(x, Piece::K, Piece::k))
+        || ray_hits(&ROOK, &|x| {
+            matches!(
+                x,
+                Piece::R | Piece::UR | Piece::r | Piece::Ur | Piece::Q | Piece::UQ | Piece::q | Piece::Uq
+            )
+        })
+        || ray_hits(&BISHOP, &|x| {
+            matches!(
+                x,
+                Piece::B | Piece::UB | Piece::b | Piece::Ub | Piece::Q | Piece::UQ | Piece::q | Piece::Uq
+            )
+        })
+}
+
+///Returns the moves of the white pieces without castling and en passant, by scanning the array
+fn scan_moves(b: &ChessBoard) -> Vec<((usize, usize), (usize, usize))> {
+    let mut vec = Vec::new();
+    for i in 0..8 {
+        for j in 0..8 {
+            let piece = b.board[i as usize][j as usize];
+            if !piece.is_white() {
+                continue;
+            }
+            let mut add = |a: i32, c: i32| vec.push(((i as usize, j as usize), (a as usize, c as usize)));
+            let free = |a: i32, c: i32| at(b, a, c) == Some(Piece::E);
+            let enemy = |a: i32, c: i32| matches!(at(b, a, c), Some(x) if x.is_black());
+            let (steps, rays): (Offsets, Offsets) = match piece {
+                Piece::P => {
+                    if free(i - 1, j) {
+                        add(i - 1, j);
+                        if i == 6 && free(i - 2, j) {
+                            add(i - 2, j);
+                        }
+                    }
+                    for c in [j - 1, j + 1].iter() {
+                        if enemy(i - 1, *c) {
+                            add(i - 1, *c);
+                        }
+                    }
+                    (&[], &[])
+                }
+                Piece::N | Piece::UN => (&KNIGHT, &[]),
+                Piece::K => (&KING, &[]),
+                Piece::R | Piece::UR => (&[], &ROOK),
+                Piece::B | Piece::UB => (&[], &BISHOP),
+                //a queen moves like a king but as far as it can
+                _ => (&[], &KING),
+            };
+            for (di, dj) in steps.iter() {
+                if free(i + di, j + dj) || enemy(i + di, j + dj) {
+                    add(i + di, j + dj);
+                }
+            }
+            for (di, dj) in rays.iter() {
+                let (mut a, mut c) = (i + di, j + dj);
+                while free(a, c) {
+                    add(a, c);
+                    a += di;
+                    c += dj;
+                }
+                if enemy(a, c) {
+                    add(a, c);
+                }
+            }
+        }
+    }
+    vec
+}
+
+///Returns the moves of the player to move on board1 with the bitboards, through compact_moves
+fn bitboard_moves(cl: &ChessLogic) -> Vec<((usize, usize), (usize, usize))> {
+    let square = |sq: Square| (sq.get_row(), sq.get_col());
+    cl.compact_moves(BoardId::Board1)
+        .into_iter()
+        .map(|m| (square(m.get_from().unwrap()), square(m.get_to())))
+        .collect()
+}
+
+fn main() {
+    for (name, cl) in positions().iter() {
+        let b = cl.get_board_n(true);
+        let (mut scanned, mut generated) = (scan_moves(b), bitboard_moves(cl));
+        scanned.sort_unstable();
+        generated.sort_unstable();
+        assert!(scanned == generated, "{} has different moves", name);
+        for (i, j) in (0..64).map(|sq| (sq / 8, sq % 8)) {
+            assert!(cl.is_attacked(true, true, i, j) == scan_attacked(b, i, j, false));
+            assert!(cl.is_attacked(true, false, i, j) == scan_attacked(b, i, j, true));
+        }
+
+        let bitboards = measure(|| {
+            black_box(cl.compact_moves(BoardId::Board1));
+        });
+        let scan = measure(|| {
+            black_box(scan_moves(b));
+        });
+        println!(
+            "{:<10} {:<22} bitboards {:>10?}  array scan {:>10?} ({:.1}x)",
+            name,
+            "moves of white",
+            bitboards,
+            scan,
+            scan.as_secs_f64() / bitboards.as_secs_f64()
+        );
+
+        let bitboards = measure(|| {
+            for i in 0..8 {
+                for j in 0..8 {
+                    black_box(cl.is_attacked(true, true, i, j));
+                }
+            }
+        });
+        let scan = measure(|| {
+            for i in 0..8 {
+                for j in 0..8 {
+                    black_box(scan_attacked(b, i, j, false));
+                }
+            }
+        });
+        println!(
+            "{:<10} {:<22} bitboards {:>10?}  array scan {:>10?} ({:.1}x)",
+            name,
+            "attacks of all squares",
+            bitboards,
+            scan,
+            scan.as_secs_f64() / bitboards.as_secs_f64()
+        );
+    }
+}
@@ -0,0 +1,46 @@
+//! Counts the positions after a number of actions on a board
+//!
+//! cargo run --release --example perft -- "<BFEN or FEN>" <depth> [divide]
+//!
+//! The moves are counted with the classical rules and pawns cannot be deployed on the first rank, like in crazyhouse.
+use bughouse_rs::logic::perft::{divide, perft, position};
+use bughouse_rs::logic::rules::Rules;
+use bughouse_rs::logic::types::BoardId;
+use std::env;
+use std::process;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 3 {
+        eprintln!("usage: perft \"<BFEN or FEN>\" <depth> [divide]");
+        process::exit(1);
+    }
+    let rules = Rules {
+        pawn_drop_first_rank: false,
+        ..Rules::classical()
+    };
+    let cl = match position(&args[1], rules) {
+        Some(cl) => cl,
+        None => {
+            eprintln!("not a legal BFEN: {}", args[1]);
+            process::exit(1);
+        }
+    };
+    let depth: usize = match args[2].parse() {
+        Ok(d) => d,
+        Err(_) => {
+            eprintln!("not a depth: {}", args[2]);
+            process::exit(1);
+        }
+    };
+    if args.get(3).map(|s| s.as_str()) == Some("divide") {
+        let mut total = 0;
+        for (action, n) in divide(&cl, BoardId::Board1, depth) {
+            println!("{}: {}", action, n);
+            total += n;
+        }
+        println!("\n{}", total);
+    } else {
+        println!("{}", perft(&cl, BoardId::Board1, depth));
+    }
+}
@@ -0,0 +1,717 @@
+///A module to read/write BPGN game records
+///
+/// BPGN is the PGN dialect for bughouse, the actions of both boards are written in the order they were played.
+/// Board A is board1 and board B is board2, 1A. is the first move of white on board A, 1a. of black,
+/// 1B. and 1b. the same for board B. The remaining time of a player can follow the move as a comment, e.g. {178.5}.
+pub mod bpgn {
+    use crate::infoCourier::infoCourier::{gen_bfen_combined, read_bfen_combined};
+    use crate::logic::clock::{Increment, TimeControl};
+    use crate::logic::history::Action;
+    use crate::logic::rules::Rules;
+    use crate::logic::types::{BoardId, Color, Seat, Team, TeamResult};
+    use crate::logic::ChessLogic;
+    use crate::parse::san;
+    use std::fmt;
+    use std::time::Duration;
+
+    #[derive(Clone, PartialEq, Debug)]
+    ///The tag pairs of a BPGN record, in the order they are written
+    pub struct Tags {
+        pairs: Vec<(String, String)>,
+    }
+
+    impl Tags {
+        ///Creates the tags every record has: Event, Site, Date, the four players and the result
+        pub fn new() -> Tags {
+            let mut tags = Tags { pairs: Vec::new() };
+            for (name, value) in [
+                ("Event", "?"),
+                ("Site", "?"),
+                ("Date", "????.??.??"),
+                ("WhiteA", "?"),
+                ("BlackA", "?"),
+                ("WhiteB", "?"),
+                ("BlackB", "?"),
+                ("Result", "*"),
+            ]
+            .iter()
+            {
+                tags.set(name, value);
+            }
+            tags
+        }
+
+        ///Returns the value of a tag, None if the tag is not set
+        /// # Arguments
+        /// * `name` - name of the tag, e.g. "Event"
+        pub fn get(&self, name: &str) -> Option<&str> {
+            self.pairs.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+        }
+
+        ///Sets the value of a tag, a new tag is written after the existing ones
+        /// # Arguments
+        /// * `name` - name of the tag, e.g. "Event"
+        /// * `value` - the value without quotes
+        pub fn set(&mut self, name: &str, value: &str) {
+            match self.pairs.iter_mut().find(|(n, _)| n == name) {
+                Some(pair) => pair.1 = value.to_string(),
+                None => self.pairs.push((name.to_string(), value.to_string())),
+            }
+        }
+
+        ///Returns every tag pair in the order they are written
+        pub fn get_pairs(&self) -> &Vec<(String, String)> {
+            &self.pairs
+        }
+
+        ///Sets the name of a player
+        /// # Arguments
+        /// * `seat` - the player
+        /// * `name` - the name of the player
+        pub fn set_player(&mut self, seat: Seat, name: &str) {
+            self.set(player_tag(seat), name);
+        }
+
+        ///Returns the name of a player, None if the tag is not set
+        /// # Arguments
+        /// * `seat` - the player
+        pub fn get_player(&self, seat: Seat) -> Option<&str> {
+            self.get(player_tag(seat))
+        }
+    }
+
+    impl Default for Tags {
+        fn default() -> Tags {
+            Tags::new()
+        }
+    }
+
+    ///Returns the name of the tag of a player
+    fn player_tag(seat: Seat) -> &'static str {
+        match (seat.board, seat.color) {
+            (BoardId::Board1, Color::White) => "WhiteA",
+            (BoardId::Board1, Color::Black) => "BlackA",
+            (BoardId::Board2, Color::White) => "WhiteB",
+            (BoardId::Board2, Color::Black) => "BlackB",
+        }
+    }
+
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    ///Enum class for the errors of a BPGN import
+    ///
+    ///MalformedTag -> a tag pair is not of the form [Name "Value"]
+    ///UnexpectedToken -> a token that is not allowed at its position, e.g. a variation or a tag after the moves
+    ///MissingMoveNumber -> a move without a move number such as 1A. in front of it
+    ///WrongTurn -> the move number does not belong to the player to move
+    ///IllegalMove -> the move is not a legal move in SAN
+    ///BadFen -> the FEN tag is not a legal BFEN
+    ///Unterminated -> a comment or a tag is not closed
+    pub enum BpgnErrorKind {
+        MalformedTag,
+        UnexpectedToken,
+        MissingMoveNumber,
+        WrongTurn,
+        IllegalMove,
+        BadFen,
+        Unterminated,
+    }
+
+    #[derive(Clone, PartialEq, Debug)]
+    ///An error of a BPGN import with the position of the token, lines and columns start at 1
+    pub struct BpgnError {
+        pub line: usize,
+        pub column: usize,
+        pub kind: BpgnErrorKind,
+        ///The text of the token that caused the error
+        pub token: String,
+    }
+
+    impl fmt::Display for BpgnError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let what = match self.kind {
+                BpgnErrorKind::MalformedTag => "malformed tag",
+                BpgnErrorKind::UnexpectedToken => "unexpected token",
+                BpgnErrorKind::MissingMoveNumber => "move without move number",
+                BpgnErrorKind::WrongTurn => "move number does not match the player to move",
+                BpgnErrorKind::IllegalMove => "illegal move",
+                BpgnErrorKind::BadFen => "illegal FEN",
+                BpgnErrorKind::Unterminated => "unterminated comment or tag",
+            };
+            write!(f, "{}:{}: {} '{}'", self.line, self.column, what, self.token)
+        }
+    }
+
+    impl std::error::Error for BpgnError {}
+
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    ///An error of a BPGN export, an action of the history that cannot be written in SAN
+    pub struct ExportError {
+        ///The index of the entry in the history
+        pub index: usize,
+        pub action: Action,
+    }
+
+    impl fmt::Display for ExportError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(
+                f,
+                "entry {} of the history cannot be written in SAN '{}'",
+                self.index, self.action
+            )
+        }
+    }
+
+    impl std::error::Error for ExportError {}
+
+    ///Returns the result of the game as it is written in the Result tag
+    ///
+    /// 1-0 if the team of white on board A wins, 0-1 if the team of black on board A wins
+    pub fn result_str(cl: &ChessLogic) -> &'static str {
+        match cl.get_team_result() {
+            TeamResult::Ongoing => "*",
+            TeamResult::Draw => "1/2-1/2",
+            TeamResult::Won(Team::Team1) => "1-0",
+            TeamResult::Won(Team::Team2) => "0-1",
+        }
+    }
+
+    ///Returns the value of the TimeControl tag, the times in seconds
+    ///
+    /// 180 is sudden death, 180+2 a fischer increment, 180d2 a delay and 180b2 a bronstein delay of 2 seconds
+    pub fn time_control_str(tc: TimeControl) -> String {
+        let base = tc.base.as_secs();
+        match tc.increment {
+            Increment::None => base.to_string(),
+            Increment::Fischer(inc) => format!("{}+{}", base, inc.as_secs()),
+            Increment::Delay(d) => format!("{}d{}", base, d.as_secs()),
+            Increment::Bronstein(d) => format!("{}b{}", base, d.as_secs()),
+        }
+    }
+
+    ///Reads the value of a TimeControl tag as it is written by time_control_str, None if it is not of that form
+    /// # Arguments
+    /// * `s` - the value of the tag, e.g. "180+2"
+    pub fn read_time_control(s: &str) -> Option<TimeControl> {
+        let secs = |x: &str| x.parse::<u64>().ok().map(Duration::from_secs);
+        let (base, increment) = match s.find(['+', 'd', 'b']) {
+            Some(i) => {
+                let d = secs(&s[i + 1..])?;
+                let increment = match &s[i..i + 1] {
+                    "+" => Increment::Fischer(d),
+                    "d" => Increment::Delay(d),
+                    _ => Increment::Bronstein(d),
+                };
+                (&s[..i], increment)
+            }
+            None => (s, Increment::None),
+        };
+        Some(TimeControl::new(secs(base)?, increment))
+    }
+
+    ///Generates the BPGN record of a game from its history
+    ///# Arguments
+    /// * `cl` - A pointer to a ChessLogic
+    /// * `tags` - the tags of the record, the Result tag is always taken from the game
+    ///
+    /// If the game did not start from the initial position, the start position is written in the FEN tag as BFEN.
+    /// If the game has a clock, the remaining times are written as comments after the moves.
+    /// An action of the history that is not legal in its position, e.g. of a history replayed with other rules, cannot
+    /// be written in SAN and returns an ExportError, since import would reject the record.
+    pub fn export(cl: &ChessLogic, tags: &Tags) -> Result<String, ExportError> {
+        let mut tags = tags.clone();
+        let result = result_str(cl);
+        tags.set("Result", result);
+
+        let history = cl.get_history();
+        let start = match history.first() {
+            Some(e) => *e.get_before(),
+            None => cl.snapshot(),
+        };
+        if start != ChessLogic::new().snapshot() {
+            tags.set("SetUp", "1");
+            tags.set("FEN", &gen_bfen_combined(&ChessLogic::from_snapshot(&start)));
+        }
+        if let Some(c) = cl.get_clock() {
+            tags.set("TimeControl", &time_control_str(c.get_clock(true).get_time_control()));
+        }
+
+        let mut out = String::new();
+        for (name, value) in tags.get_pairs() {
+            out.push_str(&format!(
+                "[{} \"{}\"]\n",
+                name,
+                value.replace('\\', "\\\\").replace('"', "\\\"")
+            ));
+        }
+        out.push('\n');
+
+        let mut tokens = Vec::new();
+        for (index, e) in history.iter().enumerate() {
+            let mut before = ChessLogic::from_snapshot(e.get_before());
+            before.set_rules(cl.get_rules());
+            let board1 = e.action.get_board().is_board1();
+            let white = before.get_white_active(board1);
+            let label = match (board1, white) {
+                (true, true) => 'A',
+                (true, false) => 'a',
+                (false, true) => 'B',
+                (false, false) => 'b',
+            };
+            //the move number is not separated from its move by a line break
+            let mv = san::to_san(&before, &e.action).ok_or(ExportError {
+                index,
+                action: e.action,
+            })?;
+            tokens.push(format!("{}{}. {}", before.get_movectr(board1), label, mv));
+            if let Some(d) = e.get_remaining() {
+                tokens.push(format!("{{{:.1}}}", d.as_secs_f64()));
+            }
+        }
+        tokens.push(result.to_string());
+
+        //lines of at most 80 characters
+        let mut line = String::new();
+        for t in tokens {
+            if !line.is_empty() && line.len() + 1 + t.len() > 80 {
+                out.push_str(&line);
+                out.push('\n');
+                line.clear();
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&t);
+        }
+        out.push_str(&line);
+        out.push('\n');
+        Ok(out)
+    }
+
+    ///Reads the input char by char and keeps track of the position
+    struct Reader {
+        chars: Vec<char>,
+        pos: usize,
+        line: usize,
+        column: usize,
+    }
+
+    impl Reader {
+        fn peek(&self) -> Option<char> {
+            self.chars.get(self.pos).copied()
+        }
+
+        fn next(&mut self) -> Option<char> {
+            let c = self.peek()?;
+            self.pos += 1;
+            if c == '\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
+            Some(c)
+        }
+
+        fn error(&self, line: usize, column: usize, kind: BpgnErrorKind, token: &str) -> BpgnError {
+            BpgnError {
+                line,
+                column,
+                kind,
+                token: token.to_string(),
+            }
+        }
+    }
+
+    ///Reads a tag pair after the opening bracket
+    fn read_tag(r: &mut Reader, line: usize, column: usize) -> Result<(String, String), BpgnError> {
+        let mut name = String::new();
+        while let Some(c) = r.peek() {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                name.push(c);
+                r.next();
+            } else {
+                break;
+            }
+        }
+        while r.peek() == Some(' ') || r.peek() == Some('\t') {
+            r.next();
+        }
+        if name.is_empty() || r.next() != Some('"') {
+            return Err(r.error(line, column, BpgnErrorKind::MalformedTag, &name));
+        }
+        let mut value = String::new();
+        loop {
+            match r.next() {
+                Some('\\') => match r.next() {
+                    Some(c) => value.push(c),
+                    None => return Err(r.error(line, column, BpgnErrorKind::Unterminated, &name)),
+                },
+                Some('"') => break,
+                Some('\n') | None => return Err(r.error(line, column, BpgnErrorKind::Unterminated, &name)),
+                Some(c) => value.push(c),
+            }
+        }
+        while r.peek() == Some(' ') || r.peek() == Some('\t') {
+            r.next();
+        }
+        if r.next() != Some(']') {
+            return Err(r.error(line, column, BpgnErrorKind::MalformedTag, &name));
+        }
+        Ok((name, value))
+    }
+
+    ///Reads the remaining time of a comment, either seconds such as 178.5 or [%clk 0:02:58.5]
+    fn read_clock(comment: &str) -> Option<Duration> {
+        let c = comment.trim();
+        let c = match c.strip_prefix("[%clk") {
+            Some(rest) => rest.strip_suffix(']')?.trim(),
+            None => c,
+        };
+        let mut secs = 0.0;
+        for part in c.split(':') {
+            let x: f64 = part.parse().ok()?;
+            if x.is_nan() || x < 0.0 {
+                return None;
+            }
+            secs = secs * 60.0 + x;
+        }
+        Some(Duration::from_secs_f64(secs))
+    }
+
+    ///Splits a move number such as "12a." into the number and the player, None if the word is not a move number
+    fn read_move_number(word: &str) -> Option<(usize, Seat, &str)> {
+        let digits = word.find(|c: char| !c.is_ascii_digit())?;
+        if digits == 0 {
+            return None;
+        }
+        let mut chars = word[digits..].chars();
+        let seat = match chars.next()? {
+            'A' => Seat::new(BoardId::Board1, Color::White),
+            'a' => Seat::new(BoardId::Board1, Color::Black),
+            'B' => Seat::new(BoardId::Board2, Color::White),
+            'b' => Seat::new(BoardId::Board2, Color::Black),
+            _ => return None,
+        };
+        let rest = chars.as_str().strip_prefix('.')?;
+        Some((word[..digits].parse().ok()?, seat, rest))
+    }
+
+    ///Creates the game at the start position of the record, the FEN tag or the initial position
+    fn start_position(tags: &Tags, fen_pos: (usize, usize)) -> Result<ChessLogic, BpgnError> {
+        match tags.get("FEN") {
+            Some(fen) => read_bfen_combined(fen).ok_or_else(|| BpgnError {
+                line: fen_pos.0,
+                column: fen_pos.1,
+                kind: BpgnErrorKind::BadFen,
+                token: fen.to_string(),
+            }),
+            None => Ok(ChessLogic::new()),
+        }
+    }
+
+    ///Reads a BPGN record and replays its moves, returns the tags and the game
+    ///# Arguments
+    /// * `s` - the BPGN record
+    ///
+    /// The game starts from the FEN tag if there is one, the remaining times of the comments are
+    /// stored in the history. Variations are not supported, NAGs such as $1 are skipped.
+    /// A result after the moves ends the game if the moves did not end it, 1-0 and 0-1 as a resignation of the other team.
+    pub fn import(s: &str) -> Result<(Tags, ChessLogic), BpgnError> {
+        import_with_rules(s, Rules::new())
+    }
+
+    ///Same as import but the moves are replayed with house rules
+    ///# Arguments
+    /// * `s` - the BPGN record
+    /// * `rules` - the rules the game was played with
+    pub fn import_with_rules(s: &str, rules: Rules) -> Result<(Tags, ChessLogic), BpgnError> {
+        let mut r = Reader {
+            chars: s.chars().collect(),
+            pos: 0,
+            line: 1,
+            column: 1,
+        };
+        let mut tags = Tags { pairs: Vec::new() };
+        let mut cl: Option<ChessLogic> = None;
+        let mut fen_pos = (1, 1);
+        //the player of the last move number and its position, None if no move number is pending
+        let mut pending: Option<(Seat, BpgnError)> = None;
+        let mut last_was_move = false;
+        let mut finished = false;
+
+        while let Some(c) = r.peek() {
+            let (line, column) = (r.line, r.column);
+            if c.is_whitespace() {
+                r.next();
+                continue;
+            }
+            match c {
+                '[' => {
+                    r.next();
+                    if cl.is_some() {
+                        return Err(r.error(line, column, BpgnErrorKind::UnexpectedToken, "["));
+                    }
+                    let (name, value) = read_tag(&mut r, line, column)?;
+                    if name == "FEN" {
+                        fen_pos = (line, column);
+                    }
+                    tags.set(&name, &value);
+                }
+                '{' => {
+                    r.next();
+                    let mut comment = String::new();
+                    loop {
+                        match r.next() {
+                            Some('}') => break,
+                            Some(c) => comment.push(c),
+                            None => return Err(r.error(line, column, BpgnErrorKind::Unterminated, "{")),
+                        }
+                    }
+                    if last_was_move {
+                        if let (Some(d), Some(game)) = (read_clock(&comment), cl.as_mut()) {
+                            game.set_last_remaining(Some(d));
+                        }
+                    }
+                }
+                ';' => {
+                    while let Some(c) = r.next() {
+                        if c == '\n' {
+                            break;
+                        }
+                    }
+                }
+                _ => {
+                    let mut word = String::new();
+                    while let Some(c) = r.peek() {
+                        if c.is_whitespace() || c == '{' || c == ';' || (c == '[' && !word.is_empty()) {
+                            break;
+                        }
+                        word.push(c);
+                        r.next();
+                    }
+                    if finished || word.starts_with('(') || word.starts_with(')') {
+                        return Err(r.error(line, column, BpgnErrorKind::UnexpectedToken, &word));
+                    }
+                    if cl.is_none() {
+                        let mut game = start_position(&tags, fen_pos)?;
+                        game.set_rules(rules);
+                        cl = Some(game);
+                    }
+                    let game = cl.as_mut().unwrap();
+                    if let "1-0" | "0-1" | "1/2-1/2" | "*" = word.as_str() {
+                        if pending.is_some() {
+                            return Err(r.error(line, column, BpgnErrorKind::UnexpectedToken, &word));
+                        }
+                        if game.get_team_result() == TeamResult::Ongoing {
+                            match word.as_str() {
+                                "1-0" => game.resign_seat(Seat::new(BoardId::Board1, Color::Black)),
+                                "0-1" => game.resign_seat(Seat::new(BoardId::Board1, Color::White)),
+                                "1/2-1/2" => game.set_draw(),
+                                _ => {}
+                            }
+                        }
+                        finished = true;
+                        continue;
+                    }
+                    if word.starts_with('$') {
+                        continue;
+                    }
+                    let mut mv = word.as_str();
+                    if let Some((n, seat, rest)) = read_move_number(&word) {
+                        if pending.is_some() {
+                            return Err(r.error(line, column, BpgnErrorKind::UnexpectedToken, &word));
+                        }
+                        if !game.is_turn(seat) || game.get_movectr(seat.board.is_board1()) != n {
+                            return Err(r.error(line, column, BpgnErrorKind::WrongTurn, &word));
+                        }
+                        let at = r.error(line, column, BpgnErrorKind::UnexpectedToken, &word);
+                        pending = Some((seat, at));
+                        last_was_move = false;
+                        if rest.is_empty() {
+                            continue;
+                        }
+                        mv = rest;
+                    }
+                    let seat = match pending.take() {
+                        Some((x, _)) => x,
+                        None => return Err(r.error(line, column, BpgnErrorKind::MissingMoveNumber, &word)),
+                    };
+                    if game.play_san(seat.board, mv).is_err() {
+                        return Err(r.error(line, column, BpgnErrorKind::IllegalMove, &word));
+                    }
+                    last_was_move = true;
+                }
+            }
+        }
+        //a move number without a move
+        if let Some((_, e)) = pending {
+            return Err(e);
+        }
+        let cl = match cl {
+            Some(x) => x,
+            None => {
+                let mut game = start_position(&tags, fen_pos)?;
+                game.set_rules(rules);
+                game
+            }
+        };
+        Ok((tags, cl))
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::infoCourier::infoCourier::read_bfen_combined;
+        use crate::logic::board::Piece;
+        use crate::logic::types::{BoardId, Color, Seat};
+        use crate::logic::ChessLogic;
+
+        #[test]
+        fn bpgn_export_import() {
+            use crate::logic::clock::*;
+            use std::rc::Rc;
+            use std::time::Duration;
+            let mut cl = ChessLogic::new();
+            let source = ManualClock::new();
+            cl.set_clock(
+                TimeControl::new(Duration::from_secs(180), Increment::Fischer(Duration::from_secs(2))),
+                Rc::new(source.clone()),
+            );
+            for (board, m) in [
+                (BoardId::Board1, "e4"),
+                (BoardId::Board2, "d4"),
+                (BoardId::Board1, "d5"),
+                (BoardId::Board1, "exd5"),
+                (BoardId::Board2, "e5"),
+                (BoardId::Board2, "dxe5"),
+                (BoardId::Board1, "Nf6"),
+                (BoardId::Board1, "Nc3"),
+                (BoardId::Board2, "P@e4"),
+            ]
+            .iter()
+            {
+                source.advance(Duration::from_millis(1500));
+                assert!(cl.play_san(*board, m) == Ok(true), "{}", m);
+            }
+            let mut tags = Tags::new();
+            tags.set("Event", "Club night");
+            tags.set_player(Seat::new(BoardId::Board2, Color::Black), "Partner \"P\"");
+            let record = export(&cl, &tags).unwrap();
+            assert!(record.starts_with("[Event \"Club night\"]\n"));
+            assert!(record.contains("[BlackB \"Partner \\\"P\\\"\"]\n"));
+            assert!(record.contains("[TimeControl \"180+2\"]\n"));
+            assert!(record.contains("1A. e4 {180.0} 1B. d4 {180.0} 1a. d5 {179.0} 2A. exd5 {180.5}"));
+            assert!(record.contains("2b. P@e4 {"));
+            assert!(record.trim_end().ends_with("*"));
+
+            let (read_tags, read) = import(&record).unwrap();
+            assert_eq!(
+                read_tags.get_player(Seat::new(BoardId::Board2, Color::Black)),
+                Some("Partner \"P\"")
+            );
+            assert!(read.snapshot() == cl.snapshot());
+            assert_eq!(read.get_history().len(), 9);
+            assert!(read.get_history()[3].get_remaining() == Some(Duration::from_millis(180500)));
+            assert_eq!(
+                export(&read, &read_tags)
+                    .unwrap()
+                    .lines()
+                    .filter(|l| !l.starts_with('['))
+                    .collect::<Vec<_>>(),
+                record.lines().filter(|l| !l.starts_with('[')).collect::<Vec<_>>()
+            );
+
+            //from a position with a result
+            let mut cl =
+                read_bfen_combined("4k3/8/8/8/8/8/8/4K2R[] w K - 0 1 | 4k3/p7/8/8/8/8/P7/4K3[] b - - 0 1").unwrap();
+            assert!(cl.play_san(BoardId::Board2, "Kd7") == Ok(true));
+            cl.resign_seat(Seat::new(BoardId::Board1, Color::Black));
+            let record = export(&cl, &Tags::new()).unwrap();
+            assert!(record.contains("[SetUp \"1\"]"));
+            assert!(record.contains("[Result \"1-0\"]"));
+            assert!(record.contains("1b. Kd7 1-0"));
+            let (_, read) = import(&record).unwrap();
+            assert!(read.get_piece(false, 1, 3) == Piece::k);
+        }
+
+        #[test]
+        fn time_control_round_trip() {
+            use crate::logic::clock::ManualClock;
+            use std::rc::Rc;
+            let secs = Duration::from_secs;
+            for (increment, value) in [
+                (Increment::None, "180"),
+                (Increment::Fischer(secs(2)), "180+2"),
+                (Increment::Delay(secs(2)), "180d2"),
+                (Increment::Bronstein(secs(2)), "180b2"),
+            ]
+            .iter()
+            {
+                let tc = TimeControl::new(secs(180), *increment);
+                let mut cl = ChessLogic::new();
+                cl.set_clock(tc, Rc::new(ManualClock::new()));
+                assert!(cl.play_san(BoardId::Board1, "e4") == Ok(true));
+                let record = export(&cl, &Tags::new()).unwrap();
+                assert!(record.contains(&format!("[TimeControl \"{}\"]\n", value)));
+                let (tags, _) = import(&record).unwrap();
+                assert!(read_time_control(tags.get("TimeControl").unwrap()) == Some(tc));
+            }
+            assert!(read_time_control("40/9000").is_none());
+            assert!(read_time_control("180+").is_none());
+        }
+
+        #[test]
+        fn result_round_trip() {
+            let mut won = ChessLogic::new();
+            won.resign_seat(Seat::new(BoardId::Board2, Color::White));
+            let mut lost = ChessLogic::new();
+            lost.resign_seat(Seat::new(BoardId::Board2, Color::Black));
+            let mut drawn = ChessLogic::new();
+            assert!(drawn.play_san(BoardId::Board2, "d4") == Ok(true));
+            drawn.set_draw();
+            for cl in [won, lost, drawn, ChessLogic::new()].iter() {
+                let (_, read) = import(&export(cl, &Tags::new()).unwrap()).unwrap();
+                assert!(read.get_team_result() == cl.get_team_result());
+            }
+            //the moves decide the result, not the token after them
+            let (_, read) = import("1A. f3 1a. e5 2A. g4 2a. Qh4 3A. a3 3a. Qxe1 1/2-1/2").unwrap();
+            assert!(read.get_team_result() == TeamResult::Won(Team::Team2));
+        }
+
+        #[test]
+        fn export_errors() {
+            //a move that ignores a check is legal with king capture, not after the rules change to checkmate
+            let mut cl = ChessLogic::new();
+            for m in ["e4", "d5", "Bb5+", "a5"].iter() {
+                assert!(cl.play_san(BoardId::Board1, m) == Ok(true), "{}", m);
+            }
+            assert!(export(&cl, &Tags::new()).is_ok());
+            cl.set_rules(Rules::classical());
+            let e = export(&cl, &Tags::new()).err().unwrap();
+            assert!(e.index == 3 && e.action == cl.get_history()[3].action);
+        }
+
+        #[test]
+        fn bpgn_errors() {
+            let err = |s: &str| import(s).err().unwrap();
+            let e = err("[Event \"x\"]\n\n1A. e4 1a. e5\n2A. Ke3 *");
+            assert!(e.kind == BpgnErrorKind::IllegalMove && e.line == 4 && e.column == 5 && e.token == "Ke3");
+            let e = err("1A. e4 1A. d4");
+            assert!(e.kind == BpgnErrorKind::WrongTurn && e.column == 8);
+            let e = err("1A. e4 e5");
+            assert!(e.kind == BpgnErrorKind::MissingMoveNumber && e.column == 8);
+            let e = err("[Event x]");
+            assert!(e.kind == BpgnErrorKind::MalformedTag && e.line == 1 && e.column == 1);
+            let e = err("1A. e4 {178");
+            assert!(e.kind == BpgnErrorKind::Unterminated && e.column == 8);
+            let e = err("1A. e4 (1A. d4) *");
+            assert!(e.kind == BpgnErrorKind::UnexpectedToken);
+            let e = err("[FEN \"8/8 w\"]\n1A. e4");
+            assert!(e.kind == BpgnErrorKind::BadFen && e.line == 1);
+            let e = err("1A. e4 1-0 1a. e5");
+            assert!(e.kind == BpgnErrorKind::UnexpectedToken && e.column == 12);
+            assert_eq!(format!("{}", err("1B.")), "1:1: unexpected token '1B.'");
+            assert!(import("1A.e4 {[%clk 0:02:58]} 1a. e5 $1 ; comment\n *").is_ok());
+        }
+    }
+}
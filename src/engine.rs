@@ -0,0 +1,937 @@
+///A module for a bughouse engine that searches the moves and deploys of a player on one board
+///
+/// The engine uses alpha-beta search with iterative deepening, a transposition table and move ordering,
+/// the evaluation counts the pieces on the board and in the pools, piece-square tables and the safety of the kings against deploys.
+/// The other board does not move. Alone the engine plays the board as if it were crazyhouse without the captured pieces,
+/// in team mode captured pieces are sent to the other board and the engine also evaluates the board of the partner.
+/// It also advises a player what to ask the partner for and whether to sit and wait for a piece.
+pub mod engine {
+    use crate::logic::bitboard::{self, Bitboards, Kind};
+    use crate::logic::board::{self, Piece};
+    use crate::logic::compact::CompactMove;
+    use crate::logic::history::{Action, Snapshot};
+    use crate::logic::rules::Termination;
+    use crate::logic::types::Seat;
+    use crate::logic::{ChessLogic, Winner};
+    use std::time::{Duration, Instant};
+
+    ///Score of a checkmate, a mate after n plies scores MATE - n
+    pub const MATE: i32 = 100_000;
+    ///Scores above this are mates
+    const MATE_BOUND: i32 = MATE - 1000;
+    ///Larger than every score
+    const INF: i32 = 1_000_000;
+    ///Number of entries of the transposition table
+    const TABLE_SIZE: usize = 1 << 16;
+    ///The deepest ply that has killer moves
+    const MAX_PLY: usize = 128;
+
+    ///Values of the pieces in the order of Kind, a promoted piece is worth the piece
+    pub const VALUES: [i32; 6] = [100, 400, 300, 300, 800, 0];
+    ///Values of the pieces in a pool in the order P-R-N-B-Q, a piece in the pool can be deployed on any empty square
+    pub const POOL_VALUES: [i32; 5] = [110, 420, 330, 330, 820];
+    ///Penalty for each square next to the king that an enemy piece attacks or a deployed enemy piece could take
+    const KING_ZONE: i32 = 6;
+    ///Additional penalty of such a square for each piece in the enemy pool
+    const KING_ZONE_DROP: i32 = 3;
+    ///A piece is requested from the partner if it improves the score of the player by this
+    const NEED: i32 = 200;
+    ///A more valuable piece is only requested if it is better than a cheaper one by this
+    const NEED_MARGIN: i32 = 50;
+    ///The player sits and waits for the piece if it improves the score by this, or if it mates
+    const SIT: i32 = 400;
+    ///The partner should not trade if a trade lowers the score of the player by this
+    const TRADE: i32 = 150;
+
+    ///Piece-square tables of white in the order of Kind, row 0 is the eighth rank, black uses the mirrored row
+    const TABLES: [[i32; 64]; 6] = [
+        [
+            0, 0, 0, 0, 0, 0, 0, 0, 50, 50, 50, 50, 50, 50, 50, 50, 10, 10, 20, 30, 30, 20, 10, 10, 5, 5, 10, 25, 25,
+            10, 5, 5, 0, 0, 0, 20, 20, 0, 0, 0, 5, -5, -10, 0, 0, -10, -5, 5, 5, 10, 10, -20, -20, 10, 10, 5, 0, 0, 0,
+            0, 0, 0, 0, 0,
+        ],
+        [
+            0, 0, 0, 0, 0, 0, 0, 0, 5, 10, 10, 10, 10, 10, 10, 5, -5, 0, 0, 0, 0, 0, 0, -5, -5, 0, 0, 0, 0, 0, 0, -5,
+            -5, 0, 0, 0, 0, 0, 0, -5, -5, 0, 0, 0, 0, 0, 0, -5, -5, 0, 0, 0, 0, 0, 0, -5, 0, 0, 0, 5, 5, 0, 0, 0,
+        ],
+        [
+            -50, -40, -30, -30, -30, -30, -40, -50, -40, -20, 0, 0, 0, 0, -20, -40, -30, 0, 10, 15, 15, 10, 0, -30,
+            -30, 5, 15, 20, 20, 15, 5, -30, -30, 0, 15, 20, 20, 15, 0, -30, -30, 5, 10, 15, 15, 10, 5, -30, -40, -20,
+            0, 5, 5, 0, -20, -40, -50, -40, -30, -30, -30, -30, -40, -50,
+        ],
+        [
+            -20, -10, -10, -10, -10, -10, -10, -20, -10, 0, 0, 0, 0, 0, 0, -10, -10, 0, 5, 10, 10, 5, 0, -10, -10, 5,
+            5, 10, 10, 5, 5, -10, -10, 0, 10, 10, 10, 10, 0, -10, -10, 10, 10, 10, 10, 10, 10, -10, -10, 5, 0, 0, 0, 0,
+            5, -10, -20, -10, -10, -10, -10, -10, -10, -20,
+        ],
+        [
+            -20, -10, -10, -5, -5, -10, -10, -20, -10, 0, 0, 0, 0, 0, 0, -10, -10, 0, 5, 5, 5, 5, 0, -10, -5, 0, 5, 5,
+            5, 5, 0, -5, 0, 0, 5, 5, 5, 5, 0, -5, -10, 5, 5, 5, 5, 5, 0, -10, -10, 0, 5, 0, 0, 0, 0, -10, -20, -10,
+            -10, -5, -5, -10, -10, -20,
+        ],
+        [
+            -30, -40, -40, -50, -50, -40, -40, -30, -30, -40, -40, -50, -50, -40, -40, -30, -30, -40, -40, -50, -50,
+            -40, -40, -30, -30, -40, -40, -50, -50, -40, -40, -30, -20, -30, -30, -40, -40, -30, -30, -20, -10, -20,
+            -20, -20, -20, -20, -20, -10, 20, 20, 0, 0, 0, 0, 20, 20, 20, 30, 10, 0, 0, 10, 30, 20,
+        ],
+    ];
+
+    const KINDS: [Kind; 6] = [
+        Kind::Pawn,
+        Kind::Rook,
+        Kind::Knight,
+        Kind::Bishop,
+        Kind::Queen,
+        Kind::King,
+    ];
+
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    ///How long the engine searches, it stops at the first limit that is reached
+    ///
+    /// The depth is counted in plies of the board, the time and the nodes are checked while searching.
+    pub struct Limits {
+        ///Deepest search, default is 4
+        pub depth: usize,
+        ///Time of a search, None (default) for no limit
+        pub time: Option<Duration>,
+        ///Number of positions of a search, None (default) for no limit
+        pub nodes: Option<u64>,
+    }
+
+    impl Limits {
+        ///Creates the default limits, a search of depth 4
+        pub fn new() -> Limits {
+            Limits {
+                depth: 4,
+                time: None,
+                nodes: None,
+            }
+        }
+    }
+
+    impl Default for Limits {
+        fn default() -> Limits {
+            Limits::new()
+        }
+    }
+
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    ///The result of a search
+    pub struct SearchResult {
+        ///The best move or deploy
+        pub action: Action,
+        ///Score for the player in centipawns, MATE - n if the player mates after n plies, -MATE + n if the player is mated
+        pub score: i32,
+        ///Depth of the last complete iteration, 0 if not even depth 1 was finished
+        pub depth: usize,
+        ///Number of positions searched
+        pub nodes: u64,
+    }
+
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    ///A request to the partner
+    pub enum Request {
+        ///The player needs the piece, in the colour of the player, the partner should capture one
+        Need(Piece),
+        ///The pieces the partner would lose in a trade help the opponent of the player more than the player
+        DontTrade,
+    }
+
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    ///Enum class for whether the player moves or waits for a piece of the partner
+    pub enum Decision {
+        Sit,
+        Go,
+    }
+
+    #[derive(Clone, PartialEq, Debug)]
+    ///The advice for a player of a team
+    pub struct Advice {
+        ///The best action if the player goes, None if the player has no legal action
+        pub result: Option<SearchResult>,
+        ///The requests to the partner, the best piece first
+        pub requests: Vec<Request>,
+        ///Whether the player moves or waits for the requested piece
+        pub decision: Decision,
+    }
+
+    #[derive(Clone, Copy, PartialEq)]
+    ///Enum class for the kind of a score of the transposition table
+    ///
+    ///Exact -> the score of the position
+    ///Lower -> the score is at least this, a move was too good for the other player
+    ///Upper -> the score is at most this, no move was better than alpha
+    enum Bound {
+        Exact,
+        Lower,
+        Upper,
+    }
+
+    #[derive(Clone, Copy)]
+    ///An entry of the transposition table
+    struct Entry {
+        hash: u64,
+        depth: usize,
+        score: i32,
+        bound: Bound,
+        best: Option<CompactMove>,
+    }
+
+    ///Returns the index of a square in the tables of a colour
+    fn table_index(i: usize, j: usize, white: bool) -> usize {
+        if white {
+            i * 8 + j
+        } else {
+            (7 - i) * 8 + j
+        }
+    }
+
+    ///Returns the score of the pieces of a player on a board, without the other player
+    fn side(cl: &ChessLogic, bb: &Bitboards, board1: bool, white: bool) -> i32 {
+        let mut score = 0;
+        for (k, kind) in KINDS.iter().enumerate() {
+            for (i, j) in bitboard::squares(bb.pieces(*kind, white)) {
+                score += VALUES[k] + TABLES[k][table_index(i, j, white)];
+            }
+        }
+        for (ind, value) in POOL_VALUES.iter().enumerate() {
+            score += *value * cl.get_captured_piece(board1, white, ind) as i32;
+        }
+        //the squares next to the king, a deployed enemy piece is dangerous there unless an own piece guards the square
+        let enemy_pool: i32 = (0..5)
+            .map(|ind| cl.get_captured_piece(board1, !white, ind) as i32)
+            .sum();
+        let king = bb.pieces(Kind::King, white);
+        let mut guards = *bb;
+        guards.kinds[Kind::King as usize] &= !king;
+        for (i, j) in bitboard::squares(king) {
+            for (a, b) in bitboard::squares(bitboard::king_attacks(i, j)) {
+                let danger = if bb.attacked(a, b, !white) {
+                    2
+                } else if bb.occupied & bitboard::bit(a, b) == 0 && !guards.attacked(a, b, white) {
+                    1
+                } else {
+                    0
+                };
+                score -= danger * (KING_ZONE + KING_ZONE_DROP * enemy_pool);
+            }
+        }
+        score
+    }
+
+    ///Returns the score of a board for a player in centipawns, positive if the player is better
+    /// # Arguments
+    /// * `cl` - the game
+    /// * `seat` - the player
+    ///
+    /// It counts the pieces on the board and in the pools of the board, piece-square tables and the squares next to the kings
+    /// where the enemy could deploy a piece. Checkmates are found by the search, not by the evaluation.
+    pub fn evaluate(cl: &ChessLogic, seat: Seat) -> i32 {
+        let board1 = seat.board.is_board1();
+        let white = seat.color.is_white();
+        let bb = cl.bitboards(board1);
+        side(cl, &bb, board1, white) - side(cl, &bb, board1, !white)
+    }
+
+    ///Returns the score of both boards for the team of a player in centipawns, positive if the team is better
+    /// # Arguments
+    /// * `cl` - the game
+    /// * `seat` - the player
+    ///
+    /// It is the evaluation of the player plus the one of the partner, so a piece in the pool of the opponent of the partner
+    /// counts against the team, even more if the king of the partner is open.
+    pub fn evaluate_team(cl: &ChessLogic, seat: Seat) -> i32 {
+        evaluate(cl, seat) + evaluate(cl, seat.partner())
+    }
+
+    ///Returns the value of the piece on a square, 0 if it is empty
+    fn value_at(cl: &ChessLogic, board1: bool, i: usize, j: usize) -> i32 {
+        match bitboard::kind(cl.get_piece(board1, i, j)) {
+            Some((k, _)) => VALUES[k as usize],
+            None => 0,
+        }
+    }
+
+    ///Returns true if the action that was just made is not legal, it left the king of the player in check
+    ///
+    /// With Termination::KingCapture every action is legal, the opponent can take the king next.
+    fn leaves_check(game: &ChessLogic, seat: Seat) -> bool {
+        game.get_rules().termination == Termination::Checkmate && game.is_in_check(seat)
+    }
+
+    ///Returns true if a move takes a piece or promotes a pawn, the moves of the quiescence search
+    fn is_tactical(cl: &ChessLogic, board1: bool, m: CompactMove, white: bool) -> bool {
+        if m.is_drop() {
+            return false;
+        }
+        let to = m.get_to();
+        m.get_piece(white).is_some() || cl.get_piece(board1, to.get_row(), to.get_col()) != Piece::E
+    }
+
+    ///A bughouse engine, the transposition table is kept between searches
+    pub struct Engine {
+        limits: Limits,
+        team: bool,
+        table: Vec<Option<Entry>>,
+        killers: Vec<[Option<CompactMove>; 2]>,
+        nodes: u64,
+        start: Instant,
+        stopped: bool,
+    }
+
+    impl Engine {
+        ///Creates an engine
+        /// # Arguments
+        /// * `limits` - how long a search takes
+        pub fn new(limits: Limits) -> Engine {
+            Engine {
+                limits,
+                team: false,
+                table: vec![None; TABLE_SIZE],
+                killers: vec![[None; 2]; MAX_PLY],
+                nodes: 0,
+                start: Instant::now(),
+                stopped: false,
+            }
+        }
+
+        ///Returns the limits of a search
+        pub fn get_limits(&self) -> Limits {
+            self.limits
+        }
+
+        ///Sets the limits of the next searches
+        /// # Arguments
+        /// * `limits` - how long a search takes
+        pub fn set_limits(&mut self, limits: Limits) {
+            self.limits = limits;
+        }
+
+        ///Returns true if the engine plays for the team, see set_team
+        pub fn get_team(&self) -> bool {
+            self.team
+        }
+
+        ///Sets whether the engine plays for the team
+        /// # Arguments
+        /// * `team` - true to send captured pieces to the other board and to evaluate both boards with evaluate_team,
+        ///   false (default) to search and evaluate the board of the player alone
+        pub fn set_team(&mut self, team: bool) {
+            if self.team != team {
+                self.clear();
+            }
+            self.team = team;
+        }
+
+        ///Removes everything the engine learned from earlier searches, e.g. for a new game
+        pub fn clear(&mut self) {
+            self.table.iter_mut().for_each(|e| *e = None);
+        }
+
+        ///Searches the best move or deploy of a player, None if the game has ended or the player cannot move
+        /// # Arguments
+        /// * `cl` - the game, it is not changed
+        /// * `seat` - the player, if it is not the turn of the player the search is done as if it were, e.g. for a premove
+        ///
+        /// The action is legal in the game, if the player is to move it can be played with try_play.
+        pub fn search(&mut self, cl: &ChessLogic, seat: Seat) -> Option<SearchResult> {
+            self.start = Instant::now();
+            self.nodes = 0;
+            self.stopped = false;
+            self.killers.iter_mut().for_each(|k| *k = [None; 2]);
+            if cl.get_winner(true) != Winner::N {
+                return None;
+            }
+            let board = seat.board;
+            let white = seat.color.is_white();
+            let mut game = to_move(cl, seat);
+            let mut root = self.root_moves(&mut game, seat);
+            if root.is_empty() {
+                return None;
+            }
+            let mut result = SearchResult {
+                action: root[0].to_action(board, white),
+                score: 0,
+                depth: 0,
+                nodes: 0,
+            };
+            for depth in 1..=self.limits.depth.max(1) {
+                let (best, score) = self.search_root(&mut game, seat, &mut root, depth);
+                if self.stopped {
+                    break;
+                }
+                result = SearchResult {
+                    action: best.to_action(board, white),
+                    score,
+                    depth,
+                    nodes: self.nodes,
+                };
+                //the best move is searched first in the next iteration
+                let pos = root.iter().position(|m| *m == best).unwrap();
+                root[..=pos].rotate_right(1);
+                if score.abs() >= MATE_BOUND || self.out_of_budget() {
+                    break;
+                }
+            }
+            result.nodes = self.nodes;
+            Some(result)
+        }
+
+        ///Advises a player of a team, None if the game has ended
+        /// # Arguments
+        /// * `cl` - the game, it is not changed
+        /// * `seat` - the player
+        ///
+        /// The game is searched as it is and with each piece in the pool of the player. It is also searched with the opponent to move,
+        /// as it is and with each piece in the pools of the player and the opponent, as after a trade on the other board while the
+        /// opponent thinks. This takes twelve searches with the limits of the engine.
+        /// The cheapest piece that improves the score enough is requested, don't trade is requested if a trade makes the score worse.
+        /// The player sits if the piece mates or improves the score a lot, unless the player has less time than the opponent.
+        pub fn advise(&mut self, cl: &ChessLogic, seat: Seat) -> Option<Advice> {
+            if cl.get_winner(true) != Winner::N {
+                return None;
+            }
+            let board1 = seat.board.is_board1();
+            let white = seat.color.is_white();
+            let result = self.search(cl, seat);
+            let base = result.map_or(-MATE, |r| r.score);
+            let score_with = |engine: &mut Engine, ind: usize, trade: bool| {
+                let mut s = cl.snapshot();
+                pool(&mut s, board1, white)[ind] += 1;
+                if trade {
+                    pool(&mut s, board1, !white)[ind] += 1;
+                }
+                let mut game = ChessLogic::from_snapshot(&s);
+                game.set_rules(cl.get_rules());
+                game.rehash();
+                //the partner trades while the opponent is thinking, so the trade is searched with the opponent to move
+                if trade {
+                    engine.search(&game, seat.opponent()).map_or(MATE, |r| -r.score)
+                } else {
+                    engine.search(&game, seat).map_or(-MATE, |r| r.score)
+                }
+            };
+            let mut requests = Vec::new();
+            //the cheap pieces first, a more valuable piece has to be better by a margin
+            let mut need: Option<(usize, i32)> = None;
+            for ind in [0, 2, 3, 1, 4].iter() {
+                let score = score_with(self, *ind, false);
+                if score - base >= NEED && need.map_or(true, |(_, best)| score > best + NEED_MARGIN) {
+                    need = Some((*ind, score));
+                }
+            }
+            if let Some((ind, _)) = need {
+                requests.push(Request::Need(board::pool_piece(ind, white)));
+            }
+            let waiting = self.search(cl, seat.opponent()).map_or(MATE, |r| -r.score);
+            if (0..5).any(|ind| waiting - score_with(self, ind, true) >= TRADE) {
+                requests.push(Request::DontTrade);
+            }
+            let time = match (
+                cl.get_remaining_time(board1, white),
+                cl.get_remaining_time(board1, !white),
+            ) {
+                (Some(own), Some(other)) => own >= other,
+                _ => true,
+            };
+            let worth =
+                need.is_some_and(|(_, score)| score - base >= SIT || (score >= MATE_BOUND && base < MATE_BOUND));
+            let decision = if result.is_none() || (worth && time) {
+                Decision::Sit
+            } else {
+                Decision::Go
+            };
+            Some(Advice {
+                result,
+                requests,
+                decision,
+            })
+        }
+
+        ///Returns the actions of the player that are legal under the rules of the game
+        ///
+        /// With Termination::KingCapture an action that leaves the king in check is legal, the search scores the capture of the king as a mate.
+        fn root_moves(&mut self, game: &mut ChessLogic, seat: Seat) -> Vec<CompactMove> {
+            let mut legal = Vec::new();
+            for m in game.compact_moves(seat.board) {
+                let undo = game.make(seat.board, m, self.team);
+                if !leaves_check(game, seat) {
+                    legal.push(m);
+                }
+                game.unmake(&undo);
+            }
+            legal
+        }
+
+        ///Returns the evaluation of the mode of the engine
+        fn evaluate(&self, game: &ChessLogic, seat: Seat) -> i32 {
+            if self.team {
+                evaluate_team(game, seat)
+            } else {
+                evaluate(game, seat)
+            }
+        }
+
+        ///Returns the key of a position in the table, in team mode the pools of the other board change and are part of it
+        fn key(&self, game: &ChessLogic, seat: Seat) -> u64 {
+            let hash = game.get_hash(seat.board);
+            if self.team {
+                hash ^ game.get_hash(seat.board.other()).rotate_left(32)
+            } else {
+                hash
+            }
+        }
+
+        ///Returns true if the time or the nodes of the limits are used up
+        fn out_of_budget(&self) -> bool {
+            self.limits.nodes.is_some_and(|n| self.nodes >= n)
+                || self.limits.time.is_some_and(|t| self.start.elapsed() >= t)
+        }
+
+        ///Counts a node and stops the search if the limits are reached, the time is only read every 1024 nodes
+        fn visit(&mut self) {
+            self.nodes += 1;
+            if self.limits.nodes.is_some_and(|n| self.nodes >= n) || (self.nodes % 1024 == 0 && self.out_of_budget()) {
+                self.stopped = true;
+            }
+        }
+
+        ///Searches the actions of the root, returns the best action and its score
+        fn search_root(
+            &mut self,
+            game: &mut ChessLogic,
+            seat: Seat,
+            root: &mut [CompactMove],
+            depth: usize,
+        ) -> (CompactMove, i32) {
+            let (mut alpha, beta) = (-INF, INF);
+            let mut best = (root[0], -INF);
+            for m in root.iter() {
+                let undo = game.make(seat.board, *m, self.team);
+                let score = -self.negamax(game, seat.opponent(), depth - 1, -beta, -alpha, 1);
+                game.unmake(&undo);
+                if self.stopped {
+                    break;
+                }
+                if score > best.1 {
+                    best = (*m, score);
+                }
+                alpha = alpha.max(score);
+            }
+            best
+        }
+
+        ///Returns the score of a position for the player to move
+        /// # Arguments
+        /// * `seat` - the player to move
+        /// * `ply` - the distance to the root
+        fn negamax(
+            &mut self,
+            game: &mut ChessLogic,
+            seat: Seat,
+            depth: usize,
+            mut alpha: i32,
+            beta: i32,
+            ply: usize,
+        ) -> i32 {
+            self.visit();
+            if self.stopped {
+                return 0;
+            }
+            //only the capture of a king ends a board in the search, the player to move has lost the king
+            if game.get_winner(true) != Winner::N {
+                return -MATE + ply as i32;
+            }
+            if depth == 0 {
+                return self.quiesce(game, seat, alpha, beta, ply);
+            }
+            let hash = self.key(game, seat);
+            let slot = (hash as usize) % TABLE_SIZE;
+            let mut tt_move = None;
+            if let Some(e) = self.table[slot].filter(|e| e.hash == hash) {
+                tt_move = e.best;
+                if e.depth >= depth {
+                    let score = from_table(e.score, ply);
+                    match e.bound {
+                        Bound::Exact => return score,
+                        Bound::Lower if score >= beta => return score,
+                        Bound::Upper if score <= alpha => return score,
+                        _ => {}
+                    }
+                }
+            }
+            let alpha_start = alpha;
+            let mut moves = game.compact_moves(seat.board);
+            self.order(game, seat, &mut moves, tt_move, ply);
+            let mut best = (None, -INF);
+            for m in moves {
+                let undo = game.make(seat.board, m, self.team);
+                if leaves_check(game, seat) {
+                    game.unmake(&undo);
+                    continue;
+                }
+                let score = -self.negamax(game, seat.opponent(), depth - 1, -beta, -alpha, ply + 1);
+                game.unmake(&undo);
+                if self.stopped {
+                    return 0;
+                }
+                if score > best.1 {
+                    best = (Some(m), score);
+                }
+                alpha = alpha.max(score);
+                if alpha >= beta {
+                    if !is_tactical(game, seat.board.is_board1(), m, seat.color.is_white()) && ply < MAX_PLY {
+                        let k = &mut self.killers[ply];
+                        if k[0] != Some(m) {
+                            k[1] = k[0];
+                            k[0] = Some(m);
+                        }
+                    }
+                    break;
+                }
+            }
+            if best.0.is_none() {
+                //no legal action: checkmate or stalemate
+                return if game.is_in_check(seat) { -MATE + ply as i32 } else { 0 };
+            }
+            let bound = if best.1 >= beta {
+                Bound::Lower
+            } else if best.1 > alpha_start {
+                Bound::Exact
+            } else {
+                Bound::Upper
+            };
+            self.table[slot] = Some(Entry {
+                hash,
+                depth,
+                score: to_table(best.1, ply),
+                bound,
+                best: best.0,
+            });
+            best.1
+        }
+
+        ///Searches the captures and promotions until the position is quiet, the player can also stop capturing
+        fn quiesce(&mut self, game: &mut ChessLogic, seat: Seat, mut alpha: i32, beta: i32, ply: usize) -> i32 {
+            if game.get_winner(true) != Winner::N {
+                return -MATE + ply as i32;
+            }
+            let stand = self.evaluate(game, seat);
+            if stand >= beta {
+                return stand;
+            }
+            alpha = alpha.max(stand);
+            let board1 = seat.board.is_board1();
+            let white = seat.color.is_white();
+            let mut moves: Vec<CompactMove> = game
+                .compact_moves(seat.board)
+                .into_iter()
+                .filter(|m| is_tactical(game, board1, *m, white))
+                .collect();
+            self.order(game, seat, &mut moves, None, MAX_PLY);
+            for m in moves {
+                self.visit();
+                if self.stopped {
+                    return 0;
+                }
+                let undo = game.make(seat.board, m, self.team);
+                if leaves_check(game, seat) {
+                    game.unmake(&undo);
+                    continue;
+                }
+                let score = -self.quiesce(game, seat.opponent(), -beta, -alpha, ply + 1);
+                game.unmake(&undo);
+                if score >= beta {
+                    return score;
+                }
+                alpha = alpha.max(score);
+            }
+            alpha
+        }
+
+        ///Sorts the actions, the best first: the move of the table, captures of valuable pieces by cheap pieces,
+        ///promotions, the killer moves, deploys next to the enemy king and the rest
+        fn order(
+            &self,
+            game: &ChessLogic,
+            seat: Seat,
+            moves: &mut [CompactMove],
+            tt_move: Option<CompactMove>,
+            ply: usize,
+        ) {
+            let board1 = seat.board.is_board1();
+            let white = seat.color.is_white();
+            let killers = if ply < MAX_PLY { self.killers[ply] } else { [None; 2] };
+            let bb = game.bitboards(board1);
+            let enemy_zone = bitboard::squares(bb.pieces(Kind::King, !white))
+                .map(|(i, j)| bitboard::king_attacks(i, j))
+                .fold(0, |a, b| a | b);
+            moves.sort_by_cached_key(|m| {
+                let to = m.get_to();
+                let score = if Some(*m) == tt_move {
+                    1_000_000
+                } else if let Some(from) = m.get_from() {
+                    let victim = value_at(game, board1, to.get_row(), to.get_col());
+                    let promotion = m
+                        .get_piece(white)
+                        .and_then(bitboard::kind)
+                        .map_or(0, |(k, _)| VALUES[k as usize]);
+                    if victim > 0 {
+                        100_000 + 10 * victim - value_at(game, board1, from.get_row(), from.get_col()) + promotion
+                    } else if promotion > 0 {
+                        90_000 + promotion
+                    } else if killers.contains(&Some(*m)) {
+                        80_000
+                    } else {
+                        0
+                    }
+                } else if enemy_zone & bitboard::bit(to.get_row(), to.get_col()) != 0 {
+                    50
+                } else {
+                    0
+                };
+                -score
+            });
+        }
+    }
+
+    ///Returns a copy of the game where it is the turn of the player, without history and clock
+    fn to_move(cl: &ChessLogic, seat: Seat) -> ChessLogic {
+        let mut s = cl.snapshot();
+        if seat.board.is_board1() {
+            s.white_active_1 = seat.color.is_white();
+        } else {
+            s.white_active_2 = seat.color.is_white();
+        }
+        let mut game = ChessLogic::from_snapshot(&s);
+        game.set_rules(cl.get_rules());
+        game.rehash();
+        game
+    }
+
+    ///Returns the pool of a player in a snapshot
+    fn pool(s: &mut Snapshot, board1: bool, white: bool) -> &mut [u8; 5] {
+        match (board1, white) {
+            (true, true) => &mut s.board1_white_capture,
+            (true, false) => &mut s.board1_black_capture,
+            (false, true) => &mut s.board2_white_capture,
+            (false, false) => &mut s.board2_black_capture,
+        }
+    }
+
+    ///Returns the score to store in the table, mates are stored as the distance from the position
+    fn to_table(score: i32, ply: usize) -> i32 {
+        if score >= MATE_BOUND {
+            score + ply as i32
+        } else if score <= -MATE_BOUND {
+            score - ply as i32
+        } else {
+            score
+        }
+    }
+
+    ///Returns the score of the table for a position at a ply
+    fn from_table(score: i32, ply: usize) -> i32 {
+        if score >= MATE_BOUND {
+            score - ply as i32
+        } else if score <= -MATE_BOUND {
+            score + ply as i32
+        } else {
+            score
+        }
+    }
+
+    ///Returns the best move or deploy of a player with the default engine, e.g. for a hint
+    /// # Arguments
+    /// * `cl` - the game
+    /// * `seat` - the player
+    /// * `limits` - how long the search takes
+    pub fn best_action(cl: &ChessLogic, seat: Seat, limits: Limits) -> Option<Action> {
+        Engine::new(limits).search(cl, seat).map(|r| r.action)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::infoCourier::infoCourier::read_bfen;
+        use crate::logic::perft;
+        use crate::logic::rules::Rules;
+        use crate::logic::types::{BoardId, Color, Move};
+
+        #[test]
+        fn engine() {
+            let b1 = BoardId::Board1;
+            let white = Seat::new(b1, Color::White);
+            let black = Seat::new(b1, Color::Black);
+            let mut engine = Engine::new(Limits::new());
+
+            //a hanging queen is taken
+            let cl = perft::position("4k3/8/8/3q4/8/8/8/3RK3 w - - 0 1", Rules::classical()).unwrap();
+            let result = engine.search(&cl, white).unwrap();
+            assert!(
+                result.action
+                    == Action::Move {
+                        board: b1,
+                        mv: Move::from_name("d1d5").unwrap()
+                    },
+                "{}",
+                result.action
+            );
+            assert!(evaluate(&cl, white) < 0 && evaluate(&cl, black) > 0);
+
+            //a deployed rook mates on the back rank, on f8 the king would take it
+            let cl = perft::position("6k1/5ppp/8/8/8/8/8/4K3[R] w - - 0 1", Rules::classical()).unwrap();
+            let result = engine.search(&cl, white).unwrap();
+            assert!(result.score == MATE - 1, "{:?}", result);
+            match result.action {
+                Action::Drop { drop, .. } => {
+                    assert!(drop.piece == Piece::R && drop.to.get_row() == 0 && drop.to.get_col() < 5)
+                }
+                _ => panic!("{}", result.action),
+            }
+            //a piece in the pool is worth more than on the board
+            assert!(evaluate(&cl, white) > 0);
+
+            //a mated player has no action
+            let cl = perft::position("R5k1/5ppp/8/8/8/8/8/4K3 b - - 0 1", Rules::classical()).unwrap();
+            assert!(engine.search(&cl, black).is_none());
+
+            //with king capture the king is taken like a mate, and a king in check is not left to be taken
+            let cl = perft::position("3k4/8/8/8/8/8/8/3RK3 w - - 0 1", Rules::new()).unwrap();
+            let result = engine.search(&cl, white).unwrap();
+            assert!(result.score == MATE - 1, "{:?}", result);
+            assert!(result.action.to_string() == "d1-d8", "{}", result.action);
+            let cl = perft::position("k7/8/8/8/8/8/6PP/R5K1 b - - 0 1", Rules::new()).unwrap();
+            let result = engine.search(&cl, black).unwrap();
+            assert!(result.score > -MATE_BOUND, "{:?}", result);
+            let mut classical = perft::position("k7/8/8/8/8/8/6PP/R5K1 b - - 0 1", Rules::classical()).unwrap();
+            assert!(classical.try_play(result.action) == Ok(true), "{}", result.action);
+
+            //the limits stop the search, the action is legal also for the player who is not to move
+            engine.set_limits(Limits {
+                depth: 64,
+                nodes: Some(5000),
+                time: None,
+            });
+            let mut cl = ChessLogic::new();
+            let result = engine.search(&cl, black).unwrap();
+            assert!(result.nodes <= 5000 && result.depth < 64);
+            engine.set_limits(Limits {
+                depth: 64,
+                nodes: Some(20000),
+                time: None,
+            });
+            let result = engine.search(&cl, white).unwrap();
+            assert!(result.nodes <= 20000 && result.depth >= 1 && result.depth < 64);
+            assert!(cl.try_play(result.action) == Ok(true));
+        }
+
+        #[test]
+        fn root_moves() {
+            //the actions of the root are the actions try_play accepts, also with the house rules
+            let mut no_drop_mate = Rules::classical();
+            no_drop_mate.drop_mate = false;
+            for (fen, rules) in [
+                (
+                    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+                    Rules::new(),
+                ),
+                (
+                    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+                    Rules::classical(),
+                ),
+                ("6k1/5ppp/8/8/8/8/8/4K3[R] w - - 0 1", no_drop_mate),
+                ("k7/8/8/8/8/8/6PP/R5K1[p] b - - 0 1", Rules::classical()),
+            ]
+            .iter()
+            {
+                let seat = Seat::new(BoardId::Board1, Color::from_white(fen.contains(" w ")));
+                let mut game = perft::position(fen, *rules).unwrap();
+                let mut root: Vec<Action> = Engine::new(Limits::new())
+                    .root_moves(&mut game, seat)
+                    .into_iter()
+                    .map(|m| m.to_action(seat.board, seat.color.is_white()))
+                    .collect();
+                let mut legal: Vec<Action> = game
+                    .compact_moves(seat.board)
+                    .into_iter()
+                    .map(|m| m.to_action(seat.board, seat.color.is_white()))
+                    .filter(|a| perft::position(fen, *rules).unwrap().try_play(*a).is_ok())
+                    .collect();
+                assert!(!root.is_empty());
+                root.sort_by_key(|a| a.to_string());
+                legal.sort_by_key(|a| a.to_string());
+                assert!(root == legal, "{}", fen);
+            }
+        }
+
+        #[test]
+        fn team_engine() {
+            use crate::logic::clock::*;
+            use std::rc::Rc;
+            use std::time::Duration;
+            let white = Seat::new(BoardId::Board1, Color::White);
+            let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string();
+            let load = |b1: &str, b2: &str| {
+                let mut cl = read_bfen(b1, b2).unwrap();
+                cl.set_rules(Rules::classical());
+                cl
+            };
+
+            //a knight for the opponent of the partner costs the team more than the knight is worth when the king of the partner is open
+            let board1 = "4k3/8/8/8/8/8/8/4K3 w - - 0 1";
+            let cl = load(board1, "4k3/8/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1");
+            let knight = load(board1, "4k3/8/8/8/8/8/PPPPPPPP/RNBQKBNR[N] w - - 0 1");
+            assert!(evaluate(&cl, white) == evaluate(&knight, white));
+            assert!(evaluate_team(&cl, white) - evaluate_team(&knight, white) > 330);
+
+            //in team mode a captured knight goes to the partner
+            let cl = load("4k3/8/8/3n4/8/8/8/3RK3 w - - 0 1", &start);
+            let mut engine = Engine::new(Limits::new());
+            let alone = engine.search(&cl, white).unwrap();
+            engine.set_team(true);
+            assert!(engine.get_team());
+            let team = engine.search(&cl, white).unwrap();
+            assert!(
+                alone.action == team.action
+                    && alone.action
+                        == Action::Move {
+                            board: BoardId::Board1,
+                            mv: Move::from_name("d1d5").unwrap()
+                        }
+            );
+            assert!(team.score - alone.score >= 300);
+
+            //a rook mates, so the player asks for it and sits, but goes with less time than the opponent
+            engine.set_team(false);
+            let mut cl = load("6k1/5ppp/8/8/8/8/5PPP/6K1 w - - 0 1", &start);
+            let advice = engine.advise(&cl, white).unwrap();
+            assert!(
+                advice.requests == vec![Request::Need(Piece::R)] && advice.decision == Decision::Sit,
+                "{:?}",
+                advice
+            );
+            assert!(cl.try_play(advice.result.unwrap().action) == Ok(true));
+            let mut cl = load("6k1/5ppp/8/8/8/8/5PPP/6K1 w - - 0 1", &start);
+            let src = ManualClock::new();
+            cl.set_clock(
+                TimeControl::new(Duration::from_secs(60), Increment::Fischer(Duration::from_secs(0))),
+                Rc::new(src.clone()),
+            );
+            cl.start_clock();
+            src.advance(Duration::from_secs(10));
+            assert!(engine.advise(&cl, white).unwrap().decision == Decision::Go);
+
+            //with a queen in the pool the opponent mates on g2, so the partner should not trade
+            let cl = load("6k1/1b3ppp/8/8/8/8/5P1P/6K1 w - - 0 1", &start);
+            let advice = engine.advise(&cl, white).unwrap();
+            assert!(advice.requests.contains(&Request::DontTrade), "{:?}", advice);
+            let cl = load("6k1/5ppp/8/8/8/8/5PPP/6K1 w - - 0 1", &start);
+            assert!(!engine
+                .advise(&cl, white)
+                .unwrap()
+                .requests
+                .contains(&Request::DontTrade));
+        }
+    }
+}
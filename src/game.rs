@@ -0,0 +1,570 @@
+///A module for the lifecycle of a game on top of ChessLogic
+///
+/// A Game keeps track of how the game ends: draw offers of the teams, aborts before the first move,
+/// adjournments and the reason of the final result.
+pub mod game {
+    use crate::bpgn::bpgn::{export, import_with_rules, BpgnError, ExportError, Tags};
+    use crate::logic::clock::{ClockSource, ClockState, GameClock};
+    use crate::logic::error::{ActionError, RejectCause};
+    use crate::logic::history::Action;
+    use crate::logic::rules::{Rules, Termination};
+    use crate::logic::types::{Color, Seat, Team};
+    use crate::logic::{ChessLogic, Winner};
+    use std::error::Error;
+    use std::fmt;
+    use std::rc::Rc;
+
+    #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+    ///Enum class for the reason a game ended
+    ///
+    ///KingCapture -> a king was captured
+    ///Checkmate -> a player was checkmated, only with the Checkmate termination of the rules
+    ///Resignation -> a player resigned
+    ///TimeForfeit -> the flag of a player has fallen
+    ///Stalemate -> a player was stalemated
+    ///Agreement -> the teams agreed to a draw
+    ///Abort -> the game was aborted before the first move
+    ///Adjudication -> the game was decided by an arbiter or drawn by repetition or the N-move rule
+    pub enum EndReason {
+        KingCapture,
+        Checkmate,
+        Resignation,
+        TimeForfeit,
+        Stalemate,
+        Agreement,
+        Abort,
+        Adjudication,
+    }
+
+    #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+    ///The final result of a game, winner is None for a draw or an aborted game
+    pub struct Outcome {
+        pub winner: Option<Team>,
+        pub reason: EndReason,
+    }
+
+    #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+    ///Enum class for the state of a game
+    pub enum Status {
+        Playing,
+        Adjourned,
+        Finished(Outcome),
+    }
+
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    ///Enum class for the errors of a game
+    ///
+    ///Action -> the action was rejected by ChessLogic
+    ///Finished -> the game has already ended
+    ///Adjourned -> the game is adjourned and has to be resumed first
+    ///NotAdjourned -> only an adjourned game can be resumed
+    ///NoDrawOffer -> the other team has not offered a draw
+    ///CannotAbort -> a move or a deploy has already been played on one of the boards
+    ///Record -> the record of the game cannot be written, see export
+    pub enum GameError {
+        Action(ActionError),
+        Finished,
+        Adjourned,
+        NotAdjourned,
+        NoDrawOffer,
+        CannotAbort,
+        Record(ExportError),
+    }
+
+    impl fmt::Display for GameError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                GameError::Action(e) => write!(f, "{}", e),
+                GameError::Finished => write!(f, "the game has ended"),
+                GameError::Adjourned => write!(f, "the game is adjourned"),
+                GameError::NotAdjourned => write!(f, "the game is not adjourned"),
+                GameError::NoDrawOffer => write!(f, "there is no draw offer of the other team"),
+                GameError::CannotAbort => write!(f, "the game has already started"),
+                GameError::Record(e) => write!(f, "{}", e),
+            }
+        }
+    }
+
+    impl Error for GameError {}
+
+    #[derive(Clone, PartialEq, Debug)]
+    ///The saved state of an adjourned game, everything load needs to go on with it
+    pub struct Adjournment {
+        ///The BPGN record of the game
+        pub record: String,
+        ///The rules the game is played with
+        pub rules: Rules,
+        ///The remaining times and the running sides when the game was adjourned, None for a game without a clock
+        pub clock: Option<ClockState>,
+    }
+
+    ///A bughouse game with its lifecycle
+    pub struct Game {
+        logic: ChessLogic,
+        status: Status,
+        ///The team that offered a draw, None if there is no offer
+        draw_offer: Option<Team>,
+        ///Tags of the record written by adjourn
+        tags: Tags,
+        ///The running sides of the clocks when the game was adjourned, resume starts them again
+        running: [Option<bool>; 2],
+    }
+
+    impl Game {
+        ///Creates a game
+        /// # Arguments
+        /// * `logic` - the game to play, e.g. ChessLogic::new() or a game with a clock
+        pub fn new(logic: ChessLogic) -> Game {
+            Game {
+                logic,
+                status: Status::Playing,
+                draw_offer: None,
+                tags: Tags::new(),
+                running: [None, None],
+            }
+        }
+
+        ///Returns the game
+        pub fn get_logic(&self) -> &ChessLogic {
+            &self.logic
+        }
+
+        ///Returns the state of the game
+        pub fn get_status(&self) -> Status {
+            self.status
+        }
+
+        ///Returns the final result, None if the game has not ended
+        pub fn get_outcome(&self) -> Option<Outcome> {
+            match self.status {
+                Status::Finished(o) => Some(o),
+                _ => None,
+            }
+        }
+
+        ///Returns the team that has offered a draw, None if there is no offer
+        pub fn get_draw_offer(&self) -> Option<Team> {
+            self.draw_offer
+        }
+
+        ///Returns the tags that are written by adjourn
+        pub fn get_tags(&self) -> &Tags {
+            &self.tags
+        }
+
+        ///Sets the tags that are written by adjourn, e.g. the names of the players
+        /// # Arguments
+        /// * `tags` - the tags of the record
+        pub fn set_tags(&mut self, tags: Tags) {
+            self.tags = tags;
+        }
+
+        ///Returns an error if the game cannot go on
+        fn check_playing(&self) -> Result<(), GameError> {
+            match self.status {
+                Status::Playing => Ok(()),
+                Status::Adjourned => Err(GameError::Adjourned),
+                Status::Finished(_) => Err(GameError::Finished),
+            }
+        }
+
+        ///Ends the game
+        fn finish(&mut self, winner: Option<Team>, reason: EndReason) {
+            self.status = Status::Finished(Outcome { winner, reason });
+            self.draw_offer = None;
+            self.logic.stop_clock();
+        }
+
+        ///Ends the game if ChessLogic has a winner
+        /// # Arguments
+        /// * `reason` - the reason if a player has won
+        fn finish_with_winner(&mut self, reason: EndReason) {
+            match self.logic.get_winner(true) {
+                Winner::N => {}
+                Winner::P => self.finish(None, EndReason::Stalemate),
+                Winner::D => self.finish(None, EndReason::Adjudication),
+                w => self.finish(w.team(), reason),
+            }
+        }
+
+        ///Plays a move or a deploy
+        /// # Arguments
+        /// * `action` - the action to play
+        ///
+        /// An action of the team that did not offer a draw declines the offer.
+        pub fn play(&mut self, action: Action) -> Result<bool, GameError> {
+            self.check_playing()?;
+            if self.check_flags() {
+                return Err(GameError::Finished);
+            }
+            let board = action.get_board();
+            let seat = Seat::new(board, Color::from_white(self.logic.get_white_active(board.is_board1())));
+            match self.logic.try_play(action) {
+                Ok(res) => {
+                    if self.draw_offer == Some(seat.team().other()) {
+                        self.draw_offer = None;
+                    }
+                    let reason = match self.logic.get_rules().termination {
+                        Termination::KingCapture => EndReason::KingCapture,
+                        Termination::Checkmate => EndReason::Checkmate,
+                    };
+                    self.finish_with_winner(reason);
+                    Ok(res)
+                }
+                Err(e) => {
+                    //a flag can fall between check_flags and the action
+                    if let RejectCause::GameOver(_) = e.cause {
+                        self.finish_with_winner(EndReason::TimeForfeit);
+                    }
+                    Err(GameError::Action(e))
+                }
+            }
+        }
+
+        ///Checks the flags of the players, returns true if the game has ended
+        ///
+        /// Should be called periodically since a flag falls without a move.
+        pub fn check_flags(&mut self) -> bool {
+            if self.status != Status::Playing {
+                return self.get_outcome().is_some();
+            }
+            if self.logic.check_flags() {
+                self.finish_with_winner(EndReason::TimeForfeit);
+            }
+            self.get_outcome().is_some()
+        }
+
+        ///A player resigns, the team of the player loses
+        /// # Arguments
+        /// * `seat` - the player that resigns
+        pub fn resign(&mut self, seat: Seat) -> Result<(), GameError> {
+            self.check_playing()?;
+            self.logic.resign_seat(seat);
+            self.finish(Some(seat.team().other()), EndReason::Resignation);
+            Ok(())
+        }
+
+        ///A player offers a draw for the team, if the other team has offered a draw it is accepted
+        /// # Arguments
+        /// * `seat` - the player that offers the draw
+        pub fn offer_draw(&mut self, seat: Seat) -> Result<(), GameError> {
+            self.check_playing()?;
+            if self.draw_offer == Some(seat.team().other()) {
+                return self.accept_draw(seat);
+            }
+            self.draw_offer = Some(seat.team());
+            Ok(())
+        }
+
+        ///A player accepts the draw offer of the other team for the team, the game is drawn
+        /// # Arguments
+        /// * `seat` - the player that accepts
+        pub fn accept_draw(&mut self, seat: Seat) -> Result<(), GameError> {
+            self.check_playing()?;
+            if self.draw_offer != Some(seat.team().other()) {
+                return Err(GameError::NoDrawOffer);
+            }
+            self.logic.set_draw();
+            self.finish(None, EndReason::Agreement);
+            Ok(())
+        }
+
+        ///A player declines the draw offer of the other team
+        /// # Arguments
+        /// * `seat` - the player that declines
+        pub fn decline_draw(&mut self, seat: Seat) -> Result<(), GameError> {
+            self.check_playing()?;
+            if self.draw_offer != Some(seat.team().other()) {
+                return Err(GameError::NoDrawOffer);
+            }
+            self.draw_offer = None;
+            Ok(())
+        }
+
+        ///Claims a draw by repetition or the N-move rule of the rules, see ChessLogic::claim_draw
+        /// # Arguments
+        /// * `seat` - the player that claims
+        pub fn claim_draw(&mut self, seat: Seat) -> Result<bool, GameError> {
+            self.check_playing()?;
+            if !self.logic.claim_draw(seat) {
+                return Ok(false);
+            }
+            self.finish(None, EndReason::Adjudication);
+            Ok(true)
+        }
+
+        ///Returns true if the player has made a move or a deploy
+        /// # Arguments
+        /// * `seat` - the player
+        pub fn has_moved(&self, seat: Seat) -> bool {
+            self.logic.get_history().iter().any(|e| {
+                let board1 = seat.board.is_board1();
+                let white = if board1 {
+                    e.get_before().white_active_1
+                } else {
+                    e.get_before().white_active_2
+                };
+                e.action.get_board() == seat.board && white == seat.color.is_white()
+            })
+        }
+
+        ///Aborts the game, a player can abort until the first move or deploy on either board
+        /// # Arguments
+        /// * `seat` - the player that aborts
+        ///
+        /// Both boards belong to one game, so once a board has started the game cannot be aborted from the other one.
+        pub fn abort(&mut self, _seat: Seat) -> Result<(), GameError> {
+            self.check_playing()?;
+            if !self.logic.get_history().is_empty() {
+                return Err(GameError::CannotAbort);
+            }
+            self.finish(None, EndReason::Abort);
+            Ok(())
+        }
+
+        ///Decides the game by an arbiter
+        /// # Arguments
+        /// * `winner` - the team that wins, None for a draw
+        pub fn adjudicate(&mut self, winner: Option<Team>) -> Result<(), GameError> {
+            if let Status::Finished(_) = self.status {
+                return Err(GameError::Finished);
+            }
+            match winner {
+                Some(team) => self.logic.resign_seat(team.other().seats()[0]),
+                None => self.logic.set_draw(),
+            }
+            self.finish(winner, EndReason::Adjudication);
+            Ok(())
+        }
+
+        ///Adjourns the game, the clocks are stopped, returns the saved state of the game
+        ///
+        /// The game goes on after resume or it can be loaded from the saved state by load.
+        pub fn adjourn(&mut self) -> Result<Adjournment, GameError> {
+            self.check_playing()?;
+            let record = export(&self.logic, &self.tags).map_err(GameError::Record)?;
+            let clock = self.logic.get_clock().map(|c| c.state());
+            self.running = clock.map_or([None, None], |c| c.running);
+            self.logic.stop_clock();
+            self.status = Status::Adjourned;
+            Ok(Adjournment {
+                record,
+                rules: self.logic.get_rules(),
+                clock,
+            })
+        }
+
+        ///Resumes an adjourned game, the clocks that were running when it was adjourned are started
+        pub fn resume(&mut self) -> Result<(), GameError> {
+            if self.status != Status::Adjourned {
+                return Err(GameError::NotAdjourned);
+            }
+            self.status = Status::Playing;
+            if let Some(c) = self.logic.get_clock_mut() {
+                for (board1, running) in [true, false].iter().zip(self.running.iter()) {
+                    if let Some(white) = running {
+                        c.start(*board1, *white);
+                    }
+                }
+            }
+            Ok(())
+        }
+
+        ///Loads an adjourned game from the saved state of adjourn, it has to be resumed to go on
+        /// # Arguments
+        /// * `saved` - the saved state
+        /// * `source` - where the clocks get their time from, not used for a game without a clock
+        ///
+        /// The game has the rules and the remaining times of the saved state.
+        pub fn load(saved: &Adjournment, source: Rc<dyn ClockSource>) -> Result<Game, BpgnError> {
+            let (tags, mut logic) = import_with_rules(&saved.record, saved.rules)?;
+            if let Some(state) = saved.clock.as_ref() {
+                logic.set_game_clock(GameClock::from_state(state, source));
+            }
+            Ok(Game {
+                logic,
+                status: Status::Adjourned,
+                draw_offer: None,
+                tags,
+                running: saved.clock.map_or([None, None], |c| c.running),
+            })
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::logic::types::{BoardId, TeamResult};
+        use crate::parse::san;
+
+        #[test]
+        fn game_lifecycle() {
+            use crate::logic::clock::*;
+            use std::rc::Rc;
+            use std::time::Duration;
+            let w1 = Seat::new(BoardId::Board1, Color::White);
+            let b1 = Seat::new(BoardId::Board1, Color::Black);
+            let b2 = Seat::new(BoardId::Board2, Color::Black);
+            let play = |g: &mut Game, board: BoardId, s: &str| {
+                let a = san::from_san(g.get_logic(), board, s).unwrap();
+                g.play(a)
+            };
+
+            //a player can abort until the first move on either board
+            let mut g = Game::new(ChessLogic::new());
+            assert!(play(&mut g, BoardId::Board1, "e4") == Ok(true));
+            assert!(g.abort(w1) == Err(GameError::CannotAbort));
+            assert!(g.abort(w1.partner()) == Err(GameError::CannotAbort));
+            let mut g = Game::new(ChessLogic::new());
+            assert!(g.abort(w1.partner()) == Ok(()));
+            assert!(
+                g.get_outcome()
+                    == Some(Outcome {
+                        winner: None,
+                        reason: EndReason::Abort
+                    })
+            );
+            assert!(play(&mut g, BoardId::Board1, "e4") == Err(GameError::Finished));
+
+            //a draw offer is team-wide, it is declined by a move of the other team
+            let mut g = Game::new(ChessLogic::new());
+            assert!(g.accept_draw(b1) == Err(GameError::NoDrawOffer));
+            assert!(g.offer_draw(w1) == Ok(()));
+            assert!(g.get_draw_offer() == Some(Team::Team1));
+            assert!(g.accept_draw(b2) == Err(GameError::NoDrawOffer));
+            assert!(play(&mut g, BoardId::Board1, "e4") == Ok(true));
+            assert!(g.get_draw_offer() == Some(Team::Team1));
+            assert!(play(&mut g, BoardId::Board1, "e5") == Ok(true));
+            assert!(g.get_draw_offer().is_none());
+            assert!(g.offer_draw(b1) == Ok(()));
+            assert!(g.decline_draw(w1) == Ok(()));
+            assert!(g.offer_draw(b1) == Ok(()));
+            assert!(g.offer_draw(w1.partner()) == Ok(()));
+            assert!(
+                g.get_outcome()
+                    == Some(Outcome {
+                        winner: None,
+                        reason: EndReason::Agreement
+                    })
+            );
+            assert!(g.get_logic().get_team_result() == TeamResult::Draw);
+
+            //resignation and adjudication
+            let mut g = Game::new(ChessLogic::new());
+            assert!(g.resign(b2) == Ok(()));
+            assert!(
+                g.get_outcome()
+                    == Some(Outcome {
+                        winner: Some(Team::Team2),
+                        reason: EndReason::Resignation
+                    })
+            );
+            assert!(g.resign(w1) == Err(GameError::Finished));
+            let mut g = Game::new(ChessLogic::new());
+            assert!(g.adjudicate(Some(Team::Team1)) == Ok(()));
+            assert!(g.get_logic().get_team_result() == TeamResult::Won(Team::Team1));
+
+            //king capture and time forfeit
+            let mut g = Game::new(ChessLogic::new());
+            for m in ["e4", "f5", "Qh5", "g6", "Qxg6", "a6", "Qe8"].iter() {
+                assert!(play(&mut g, BoardId::Board1, m).is_ok());
+            }
+            assert!(
+                g.get_outcome()
+                    == Some(Outcome {
+                        winner: Some(Team::Team1),
+                        reason: EndReason::KingCapture
+                    })
+            );
+            let mut cl = ChessLogic::new();
+            let src = ManualClock::new();
+            cl.set_clock(
+                TimeControl::new(Duration::from_secs(60), Increment::Fischer(Duration::from_secs(0))),
+                Rc::new(src.clone()),
+            );
+            cl.start_clock();
+            let mut g = Game::new(cl);
+            src.advance(Duration::from_secs(61));
+            assert!(g.check_flags());
+            assert!(
+                g.get_outcome()
+                    == Some(Outcome {
+                        winner: Some(Team::Team2),
+                        reason: EndReason::TimeForfeit
+                    })
+            );
+
+            //adjourn and load
+            let mut g = Game::new(ChessLogic::new());
+            assert!(play(&mut g, BoardId::Board1, "e4") == Ok(true));
+            assert!(play(&mut g, BoardId::Board2, "d4") == Ok(true));
+            let saved = g.adjourn().unwrap();
+            assert!(saved.clock.is_none());
+            assert!(g.get_status() == Status::Adjourned);
+            assert!(play(&mut g, BoardId::Board1, "e5") == Err(GameError::Adjourned));
+            let mut h = Game::load(&saved, Rc::new(ManualClock::new())).unwrap();
+            assert!(h.get_status() == Status::Adjourned);
+            assert!(h.get_logic().get_history().len() == 2);
+            assert!(h.resume() == Ok(()));
+            assert!(h.resume() == Err(GameError::NotAdjourned));
+            assert!(play(&mut h, BoardId::Board1, "e5") == Ok(true));
+        }
+
+        #[test]
+        fn adjourn_with_clock() {
+            use crate::logic::clock::*;
+            use std::time::Duration;
+            let secs = Duration::from_secs;
+            let rules = Rules {
+                move_rule: Some(50),
+                ..Rules::classical()
+            };
+            let mut cl = ChessLogic::with_rules(rules);
+            let src = ManualClock::new();
+            cl.set_clock(
+                TimeControl::new(secs(60), Increment::Bronstein(secs(2))),
+                Rc::new(src.clone()),
+            );
+            cl.start_clock();
+            let mut g = Game::new(cl);
+            for (board, m) in [
+                (BoardId::Board1, "e4"),
+                (BoardId::Board1, "e5"),
+                (BoardId::Board2, "d4"),
+            ]
+            .iter()
+            {
+                src.advance(secs(5));
+                let a = san::from_san(g.get_logic(), *board, m).unwrap();
+                assert!(g.play(a) == Ok(true));
+            }
+            src.advance(secs(4));
+            let saved = g.adjourn().unwrap();
+            let state = saved.clock.unwrap();
+            assert!(saved.rules == rules);
+            assert!(state.tc.increment == Increment::Bronstein(secs(2)));
+            //the delay gives back 2 seconds per move, white is running on board1 since e5 and black on board2 since d4
+            assert!(state.remaining == [[secs(48), secs(57)], [secs(47), secs(56)]]);
+            assert!(state.running == [Some(true), Some(false)]);
+
+            //a loaded game has the remaining times and the rules, the clocks run after resume
+            let src = ManualClock::new();
+            src.set(secs(1000));
+            let mut h = Game::load(&saved, Rc::new(src.clone())).unwrap();
+            assert!(h.get_logic().get_rules() == rules);
+            src.advance(secs(10));
+            assert!(
+                h.get_logic().get_clock().unwrap().state()
+                    == ClockState {
+                        running: [None, None],
+                        ..state
+                    }
+            );
+            assert!(h.resume() == Ok(()));
+            src.advance(secs(3));
+            let now = h.get_logic().get_clock().unwrap().state();
+            assert!(now.running == state.running);
+            assert!(now.remaining == [[secs(45), secs(57)], [secs(47), secs(53)]]);
+        }
+    }
+}
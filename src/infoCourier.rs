@@ -1,24 +1,20 @@
-
 ///A module to read/generate FEN Strings
-/// 
+///
 /// Bughousers has 2 Games running simultaneously
 /// Therefore it generates one FEN String for each Game
 pub mod infoCourier {
-    use crate::logic::ChessLogic;
-    use crate::logic::board;
     use crate::logic::board::ChessBoard;
     use crate::logic::board::Piece;
+    use crate::logic::ChessLogic;
+    use crate::logic::Winner;
     use crate::parse::parser;
-    use crate::parse::parser::line2line;
     use crate::parse::parser::char2ind;
-    use crate::util::contains;
-    use crate::logic::Winner;
-    use std;
+    use crate::parse::parser::line2line;
 
-    ///# Arguments
-    /// * `cl` - A pointer to a ChessLogic 
     ///Generates the FEN String from a given Bughouse game: a given Chesslogic module
-    pub fn gen_fen(cl:& ChessLogic) -> (String,String) {
+    ///# Arguments
+    /// * `cl` - A pointer to a ChessLogic
+    pub fn gen_fen(cl: &ChessLogic) -> (String, String) {
         //pieces
         //active color
         //castling rights KQkq
@@ -29,168 +25,162 @@ pub mod infoCourier {
         //create for board1
         let mut s1 = "".to_string();
         let mut s2 = "".to_string();
-        //get pieces 
-       
-        for board1 in [true,false].iter() {
+        //get pieces
 
+        for board1 in [true, false].iter() {
             let mut pieces = "".to_string();
             let mut emptyblock = 0;
-            let mut sum = 0;
 
             for i in 0..8 {
                 for j in 0..8 {
-                    let p = cl.get_piece(*board1,i,j);
-                    if p==Piece::E {
+                    let piece = cl.get_piece(*board1, i, j);
+                    if piece == Piece::E {
                         emptyblock += 1;
-                    }else{
-                        let a = emptyblock.to_string();
-                        if emptyblock!=0{
-                            pieces = format!("{}{}{}",pieces,emptyblock,p);
-                        }else{
-                            pieces = format!("{}{}",pieces,p);
+                    } else {
+                        if emptyblock != 0 {
+                            pieces = format!("{}{}{}", pieces, emptyblock, piece);
+                        } else {
+                            pieces = format!("{}{}", pieces, piece);
                         }
-                        sum += emptyblock;
-                        emptyblock=0;
+                        emptyblock = 0;
                     }
                 }
-                if emptyblock == 8{
-                    let a = emptyblock.to_string();
-                    pieces = format!("{}{}",pieces,emptyblock);
-                    emptyblock = 0;
-                }else if emptyblock !=0{
-                    let a = (8 - emptyblock).to_string();
-                    pieces = format!("{}{}",pieces,emptyblock);
-                    emptyblock = 0;
+                if emptyblock != 0 {
+                    pieces = format!("{}{}", pieces, emptyblock);
                 }
-                emptyblock=0;
+                emptyblock = 0;
 
-                if i!=7 {
-                    pieces = format!("{}{}",pieces,"/".to_string());
+                if i != 7 {
+                    pieces = format!("{}{}", pieces, "/");
                 }
             }
-            
-            
+
             //get active color
-            let mut activecol = 'w'.to_string();
-            match cl.get_white_active(*board1) {
-                true =>{ activecol = 'w'.to_string();},
-                false =>{ activecol = 'b'.to_string();},
-            }
+            let activecol = match cl.get_white_active(*board1) {
+                true => 'w'.to_string(),
+                false => 'b'.to_string(),
+            };
             //check for castling rights
 
             //check for en passant
             let mut enpassant = "".to_string();
             match cl.get_pawn_in_last_turn(*board1) {
-                None => {enpassant = "-".to_string();},
-                Some((a,b)) => {
+                None => {
+                    enpassant = "-".to_string();
+                }
+                Some((a, b)) => {
                     if a == 3 {
                         if let Some(col) = parser::ind2char(b) {
                             col.to_string();
-                            enpassant = format!("{}{}",enpassant,col);
+                            enpassant = format!("{}{}", enpassant, col);
                         }
-                        if let Some(row) = parser::ind2line(a-1) {
+                        if let Some(row) = parser::ind2line(a - 1) {
                             row.to_string();
-                            enpassant = format!("{}{}",enpassant,row);
+                            enpassant = format!("{}{}", enpassant, row);
                         }
-                    }else if a == 4 {
+                    } else if a == 4 {
                         if let Some(col) = parser::ind2char(b) {
                             col.to_string();
-                            enpassant = format!("{}{}",enpassant,col);
+                            enpassant = format!("{}{}", enpassant, col);
                         }
-                        if let Some(row) = parser::ind2line(a+1) {
+                        if let Some(row) = parser::ind2line(a + 1) {
                             row.to_string();
-                            enpassant = format!("{}{}",enpassant,row);
+                            enpassant = format!("{}{}", enpassant, row);
                         }
-                    } 
-                    else{
+                    } else {
                         enpassant = "-".to_string();
                     }
-                },
+                }
             }
 
-            //get if king has moved 
+            //get if king has moved
             let x1 = cl.get_castling_rights(*board1);
             let mut castling = "".to_string();
-                if x1[0] {
-                    castling = format!("{}{}", castling, "K".to_string());
-                }else{
-                    castling = format!("{}{}", castling, "-".to_string());
-                }
-
-                if x1[1] {
-                    castling = format!("{}{}", castling, "Q".to_string());
-                }else{
-                    castling = format!("{}{}", castling, "-".to_string());
-                }
+            if x1[0] {
+                castling = format!("{}{}", castling, "K");
+            } else {
+                castling = format!("{}{}", castling, "-");
+            }
 
-                if x1[2] {
-                    castling = format!("{}{}", castling, "k".to_string());
-                }else{
-                    castling = format!("{}{}", castling, "-".to_string());
-                }
+            if x1[1] {
+                castling = format!("{}{}", castling, "Q");
+            } else {
+                castling = format!("{}{}", castling, "-");
+            }
 
-                if x1[3] {
-                    castling = format!("{}{}", castling, "q".to_string());
-                }else{
-                    castling = format!("{}{}", castling, "-".to_string());
-                }
+            if x1[2] {
+                castling = format!("{}{}", castling, "k");
+            } else {
+                castling = format!("{}{}", castling, "-");
+            }
 
+            if x1[3] {
+                castling = format!("{}{}", castling, "q");
+            } else {
+                castling = format!("{}{}", castling, "-");
+            }
 
             //get halfturns
-            let mut halfturns = cl.get_half_moves(*board1).to_string();
+            let halfturns = cl.get_half_moves(*board1).to_string();
             //get fullturns
-            let mut fullturns = cl.get_movectr(*board1).to_string();
+            let fullturns = cl.get_movectr(*board1).to_string();
 
-            let mut x = format!("{} {} {} {} {} {}",
-            pieces, activecol,castling,enpassant,
-            halfturns,fullturns);
+            let x = format!(
+                "{} {} {} {} {} {}",
+                pieces, activecol, castling, enpassant, halfturns, fullturns
+            );
 
-            if *board1 { s1 = x; } else {s2 = x; }
+            if *board1 {
+                s1 = x;
+            } else {
+                s2 = x;
+            }
         }
 
-        (s1,s2)
+        (s1, s2)
     }
 
     /// Read from 2 FENs and 4 Deployable Piece Pools
     ///# Arguments
     /// * `s1` - FEN String board1
     /// * `s2` - FEN String board2
-    /// * `p1` - list of pieces for board1,white 
-    /// * `p2` - list of pieces for board1,black 
-    /// * `p3` - list of pieces for board2,white 
+    /// * `p1` - list of pieces for board1,white
+    /// * `p2` - list of pieces for board1,black
+    /// * `p3` - list of pieces for board2,white
     /// * `p4` - list of pieces for board2,black   
     ///
     /// Example fen: "rnbqkbnr/pppppppp/8/8/4P3/8/PPPPPPPP/RNBQKBNR b KQkq e3 0 1".to_string();
     /// A pool string has to be String consisting of P,R,Q,N,B,p,r,q,n,b -> capital case for white, lower case for black
     /// The ordering is not important but it must be consistent (only white or only black)
     /// In normal fen --kq becomes -kq or ---- -> -, to make parsing easier this string is not trimmed
-    pub fn read_fen(s1:& String,s2:& String,
-        p1:&String,p2:&String,p3:&String,p4:&String ) -> Option<ChessLogic> {
-       
-        let mut splt1: Vec<&str> = s1.split(" ").collect();
-        let mut splt2: Vec<&str> = s2.split(" ").collect();
+    pub fn read_fen(s1: &str, s2: &str, p1: &str, p2: &str, p3: &str, p4: &str) -> Option<ChessLogic> {
+        let splt1: Vec<&str> = s1.split(" ").collect();
+        let splt2: Vec<&str> = s2.split(" ").collect();
         //positions (split on /)
 
-        if splt1.len()!=6 || splt2.len()!=6{
-            return None
+        if splt1.len() != 6 || splt2.len() != 6 {
+            return None;
         }
-      
+
         //split the first string in "/"
         //and read the locations
         let mut ss = splt1[0];
-        let mut locs1: Vec<&str> = ss.split("/").collect();
+        let locs1: Vec<&str> = ss.split("/").collect();
         ss = splt2[0];
-        let mut locs2: Vec<&str> = ss.split("/").collect();
+        let locs2: Vec<&str> = ss.split("/").collect();
 
-        
         let mut board1 = ChessBoard::new();
         let mut board2 = ChessBoard::new();
 
-        for i in 0..7 {
-            if !read_line(&(locs1[i].to_string()),&mut board1,i){
+        if locs1.len() != 8 || locs2.len() != 8 {
+            return None;
+        }
+
+        for i in 0..8 {
+            if !read_line(locs1[i], &mut board1, i) {
                 return None;
             }
-            if !read_line(&(locs2[i].to_string()),&mut board2,i){
+            if !read_line(locs2[i], &mut board2, i) {
                 return None;
             }
         }
@@ -199,287 +189,520 @@ pub mod infoCourier {
         let turnstr1 = splt1[1].to_string();
         let mut turns: Vec<bool> = Vec::new();
         let turnstr2 = splt2[1].to_string();
-        for i in [turnstr1,turnstr2].iter(){
-            if *i=="b".to_string(){
+        for i in [turnstr1, turnstr2].iter() {
+            if *i == "b" {
                 turns.push(false);
-            }else if *i=="w".to_string(){
+            } else if *i == "w" {
                 turns.push(true);
-            }else{
+            } else {
                 return None;
             }
         }
 
-
         //castling rights (KQkq)
         //actually rooks can be assigned only once
-        //but the match statements prevents from 
+        //but the match statements prevents from
         //being it secure
-        //only applies with the fen precondition 
+        //only applies with the fen precondition
         //so they are mutable
-        let mut white_king_moved1:bool=false;
-        let mut white_king_moved2:bool=false;
-        let mut black_king_moved1:bool=false;
-        let mut black_king_moved2:bool=false;
-        let mut white_rook_k_moved1:bool=false;
-        let mut white_rook_q_moved1:bool=false;
-        let mut white_rook_k_moved2:bool=false;
-        let mut white_rook_q_moved2:bool=false;
-        let mut black_rook_k_moved1:bool=false;
-        let mut black_rook_q_moved1:bool=false;
-        let mut black_rook_k_moved2:bool=false;
-        let mut black_rook_q_moved2:bool=false;
-        println!("test1");
-            for c in splt1[2].chars() {
-                match c {
-                    'K' => {
-                        white_king_moved1=false;
-                        white_rook_k_moved1=false;
-                    },
-                    'Q' => {
-                        white_king_moved1=false;
-                        white_rook_q_moved1=false;
-                    },
-                    'k' => {
-                        black_king_moved1=false;
-                        black_rook_k_moved1=false;
-                    },
-                    'q' => {
-                        black_king_moved1=false;
-                        black_rook_q_moved1=false;
-                    },
-                    '-' => {
-                        if board1.board[7][7]==Piece::R{
-                            white_king_moved1=true;
-                        }else{
-                            white_rook_k_moved1=true;
-                        }
-                        if board1.board[7][0]==Piece::R{
-                            white_king_moved1=true;
-                        }else{
-                            white_rook_q_moved1=true;
-                        }
-                        if board1.board[0][7]==Piece::r{
-                            black_king_moved1=true;
-                        }else{
-                            black_rook_k_moved1=true;
-                        }
-                        if board1.board[0][0]==Piece::r{
-                            black_king_moved1=true;
-                        }else{
-                            black_rook_q_moved1=true;
-                        }
-                    },
-                     _ =>return None,
+        let mut white_king_moved1: bool = false;
+        let mut white_king_moved2: bool = false;
+        let mut black_king_moved1: bool = false;
+        let mut black_king_moved2: bool = false;
+        let mut white_rook_k_moved1: bool = false;
+        let mut white_rook_q_moved1: bool = false;
+        let mut white_rook_k_moved2: bool = false;
+        let mut white_rook_q_moved2: bool = false;
+        let mut black_rook_k_moved1: bool = false;
+        let mut black_rook_q_moved1: bool = false;
+        let mut black_rook_k_moved2: bool = false;
+        let mut black_rook_q_moved2: bool = false;
+        for c in splt1[2].chars() {
+            match c {
+                'K' => {
+                    white_king_moved1 = false;
+                    white_rook_k_moved1 = false;
                 }
+                'Q' => {
+                    white_king_moved1 = false;
+                    white_rook_q_moved1 = false;
+                }
+                'k' => {
+                    black_king_moved1 = false;
+                    black_rook_k_moved1 = false;
+                }
+                'q' => {
+                    black_king_moved1 = false;
+                    black_rook_q_moved1 = false;
+                }
+                '-' => {
+                    if board1.board[7][7] == Piece::R {
+                        white_king_moved1 = true;
+                    } else {
+                        white_rook_k_moved1 = true;
+                    }
+                    if board1.board[7][0] == Piece::R {
+                        white_king_moved1 = true;
+                    } else {
+                        white_rook_q_moved1 = true;
+                    }
+                    if board1.board[0][7] == Piece::r {
+                        black_king_moved1 = true;
+                    } else {
+                        black_rook_k_moved1 = true;
+                    }
+                    if board1.board[0][0] == Piece::r {
+                        black_king_moved1 = true;
+                    } else {
+                        black_rook_q_moved1 = true;
+                    }
+                }
+                _ => return None,
             }
+        }
 
-            for c in splt2[2].chars() {
-                match c {
-                    'K' => {
-                        white_king_moved2=false;
-                        white_rook_k_moved2=false;
-                    },
-                    'Q' => {
-                        white_king_moved2=false;
-                        white_rook_q_moved2=false;
-                    },
-                    'k' => {
-                        black_king_moved2=false;
-                        black_rook_k_moved2=false;
-                    },
-                    'q' => {
-                        black_king_moved2=false;
-                        black_rook_q_moved2=false;
-                    },
-                    '-' => {
-                        if board2.board[7][7]==Piece::R{
-                            white_king_moved2=true;
-                        }else{
-                            white_rook_k_moved2=true;
-                        }
-                        if board2.board[7][0]==Piece::R{
-                            white_king_moved2=true;
-                        }else{
-                            white_rook_q_moved2=true;
-                        }
-                        if board2.board[0][7]==Piece::r{
-                            black_king_moved2=true;
-                        }else{
-                            black_rook_k_moved2=true;
-                        }
-                        if board2.board[0][0]==Piece::r{
-                            black_king_moved2=true;
-                        }else{
-                            black_rook_q_moved2=true;
-                        }
-                    },
-                     _ =>return None,
+        for c in splt2[2].chars() {
+            match c {
+                'K' => {
+                    white_king_moved2 = false;
+                    white_rook_k_moved2 = false;
+                }
+                'Q' => {
+                    white_king_moved2 = false;
+                    white_rook_q_moved2 = false;
+                }
+                'k' => {
+                    black_king_moved2 = false;
+                    black_rook_k_moved2 = false;
+                }
+                'q' => {
+                    black_king_moved2 = false;
+                    black_rook_q_moved2 = false;
+                }
+                '-' => {
+                    if board2.board[7][7] == Piece::R {
+                        white_king_moved2 = true;
+                    } else {
+                        white_rook_k_moved2 = true;
+                    }
+                    if board2.board[7][0] == Piece::R {
+                        white_king_moved2 = true;
+                    } else {
+                        white_rook_q_moved2 = true;
+                    }
+                    if board2.board[0][7] == Piece::r {
+                        black_king_moved2 = true;
+                    } else {
+                        black_rook_k_moved2 = true;
+                    }
+                    if board2.board[0][0] == Piece::r {
+                        black_king_moved2 = true;
+                    } else {
+                        black_rook_q_moved2 = true;
+                    }
                 }
+                _ => return None,
             }
+        }
 
         //last pawn (e3)
-        let lastpawns = [splt1[3].to_string(),splt2[3].to_string()];
+        let lastpawns = [splt1[3].to_string(), splt2[3].to_string()];
         let mut _lastpawns = Vec::new();
         let mut f = true;
-        let mut a = 0; let mut b = 0;
-        for s in lastpawns.iter(){
-            for c in s.chars(){
+        let mut a = 0;
+        let mut b = 0;
+        for s in lastpawns.iter() {
+            for c in s.chars() {
                 if c == '-' {
                     _lastpawns.push(None);
-                }else{
+                } else {
                     if f {
                         if let Some(i) = char2ind(c) {
                             a = i;
-                        }else{
+                        } else {
                             a = 8;
                         }
-                    }else{
+                    } else {
                         f = !f;
                         if let Some(i) = line2line(c) {
                             b = i;
-                        }else{
+                        } else {
                             b = 8;
                         }
                     }
-                   
                 }
             }
-            if a!=8 && b!=8{
-                _lastpawns.push(Some((a,b)));
-            }else{
+            if a != 8 && b != 8 {
+                _lastpawns.push(Some((a, b)));
+            } else {
                 _lastpawns.push(None);
             }
-            
-          
         }
 
         //halfturns (0)
         let halfturns1;
-        if let Some(x) = parse_int_str(&splt1[4].to_string()){
+        if let Some(x) = parse_int_str(splt1[4]) {
             halfturns1 = x;
-        }else{
+        } else {
             return None;
         }
 
         let halfturns2;
-        if let Some(x) = parse_int_str(&splt2[4].to_string()){
+        if let Some(x) = parse_int_str(splt2[4]) {
             halfturns2 = x;
-        }else{
+        } else {
             return None;
         }
         //fullturns (1)
 
         let fullturns1;
-        if let Some(x) = parse_int_str(&splt2[4].to_string()) {
+        if let Some(x) = parse_int_str(splt1[5]) {
             fullturns1 = x;
-        }else{
+        } else {
             return None;
         }
         let fullturns2;
-        if let Some(x) = parse_int_str(&splt2[5].to_string()){
-            fullturns2 =x;
-        }else{
+        if let Some(x) = parse_int_str(splt2[5]) {
+            fullturns2 = x;
+        } else {
             return None;
         }
 
         //parse pool1 -b1w
         let pool11;
-        if let Some(x) = parse_pool(p1){
+        if let Some(x) = parse_pool(p1) {
             pool11 = x;
-        }else{
-            pool11 = [0;5];
+        } else {
+            pool11 = [0; 5];
         }
         //pool2 -b1b
         let pool12;
-        if let Some(x) =parse_pool(p2) {
-            pool12 =x;
-        }else{
-            pool12 = [0;5];
+        if let Some(x) = parse_pool(p2) {
+            pool12 = x;
+        } else {
+            pool12 = [0; 5];
         }
         //pool3 -b2w
         let pool21;
-        if let Some(x) =parse_pool(p3) {
-            pool21 =x;
-        }else{
-            pool21 = [0;5];
+        if let Some(x) = parse_pool(p3) {
+            pool21 = x;
+        } else {
+            pool21 = [0; 5];
         }
         //pool4 -b2b
         let pool22;
-        if let Some(x) =parse_pool(p4) {
-            pool22 =x;
-        }else{
-            pool22 = [0;5];
-        }
-
-        board1.white_k_moved=white_king_moved1;
-        board1.black_k_moved=black_king_moved1;
-        board1.white_rook_q_moved=white_rook_q_moved1;
-        board1.white_rook_k_moved=white_rook_k_moved1;
-        board1.black_rook_q_moved=black_rook_q_moved1;
-        board1.black_rook_k_moved=black_rook_k_moved1;
-        board2.white_k_moved=white_king_moved2;
-        board2.black_k_moved=black_king_moved2;
-        board2.white_rook_q_moved=white_rook_q_moved2;
-        board2.white_rook_k_moved=white_rook_k_moved2;
-        board2.black_rook_q_moved=black_rook_q_moved2;
-        board2.black_rook_k_moved=black_rook_k_moved2;
-        return Some(
-            ChessLogic::resume(
-            board1, board2,
-            _lastpawns[0],_lastpawns[1],
-            Piece::E,Piece::E,
-            turns[0],turns[1],
-            halfturns1,halfturns2,
-            fullturns1,fullturns2,
-            pool11,pool12,
-            pool21,pool22,
-            Winner::N)
+        if let Some(x) = parse_pool(p4) {
+            pool22 = x;
+        } else {
+            pool22 = [0; 5];
+        }
+
+        board1.white_k_moved = white_king_moved1;
+        board1.black_k_moved = black_king_moved1;
+        board1.white_rook_q_moved = white_rook_q_moved1;
+        board1.white_rook_k_moved = white_rook_k_moved1;
+        board1.black_rook_q_moved = black_rook_q_moved1;
+        board1.black_rook_k_moved = black_rook_k_moved1;
+        board2.white_k_moved = white_king_moved2;
+        board2.black_k_moved = black_king_moved2;
+        board2.white_rook_q_moved = white_rook_q_moved2;
+        board2.white_rook_k_moved = white_rook_k_moved2;
+        board2.black_rook_q_moved = black_rook_q_moved2;
+        board2.black_rook_k_moved = black_rook_k_moved2;
+        Some(ChessLogic::resume(
+            board1,
+            board2,
+            _lastpawns[0],
+            _lastpawns[1],
+            Piece::E,
+            Piece::E,
+            turns[0],
+            turns[1],
+            halfturns1,
+            halfturns2,
+            fullturns1,
+            fullturns2,
+            pool11,
+            pool12,
+            pool21,
+            pool22,
+            Winner::N,
+        ))
+    }
+
+    ///Generates a BFEN String for each board, a FEN with the pools in brackets and promoted pieces marked with ~
+    ///# Arguments
+    /// * `cl` - A pointer to a ChessLogic
+    ///
+    /// Example: "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR[Qnp] w KQkq - 0 2"
+    /// The pocket of a board has the white pool of the board in capital case and the black pool in lower case.
+    pub fn gen_bfen(cl: &ChessLogic) -> (String, String) {
+        (gen_board_bfen(cl, true), gen_board_bfen(cl, false))
+    }
+
+    ///Generates the BFEN Strings of both boards in a single String, separated by " | ", board1 first
+    ///# Arguments
+    /// * `cl` - A pointer to a ChessLogic
+    pub fn gen_bfen_combined(cl: &ChessLogic) -> String {
+        let (s1, s2) = gen_bfen(cl);
+        format!("{} | {}", s1, s2)
+    }
+
+    ///Generates the BFEN String of a board
+    fn gen_board_bfen(cl: &ChessLogic, board1: bool) -> String {
+        let mut pieces = "".to_string();
+        for i in 0..8 {
+            let mut emptyblock = 0;
+            for j in 0..8 {
+                let piece = cl.get_piece(board1, i, j);
+                if piece == Piece::E {
+                    emptyblock += 1;
+                    continue;
+                }
+                if emptyblock != 0 {
+                    pieces = format!("{}{}", pieces, emptyblock);
+                    emptyblock = 0;
+                }
+                pieces = format!("{}{}", pieces, piece);
+                if piece.is_promoted() {
+                    pieces.push('~');
+                }
+            }
+            if emptyblock != 0 {
+                pieces = format!("{}{}", pieces, emptyblock);
+            }
+            if i != 7 {
+                pieces.push('/');
+            }
+        }
+
+        let pools = cl.get_pools();
+        let (white_pool, black_pool) = if board1 { (pools.0, pools.1) } else { (pools.2, pools.3) };
+        let pocket = format!("[{}{}]", gen_pool(&white_pool, true), gen_pool(&black_pool, false));
+
+        let activecol = if cl.get_white_active(board1) { "w" } else { "b" };
+
+        let board = cl.get_board_n(board1);
+        let mut castling = "".to_string();
+        if !board.white_k_moved && !board.white_rook_k_moved {
+            castling.push('K');
+        }
+        if !board.white_k_moved && !board.white_rook_q_moved {
+            castling.push('Q');
+        }
+        if !board.black_k_moved && !board.black_rook_k_moved {
+            castling.push('k');
+        }
+        if !board.black_k_moved && !board.black_rook_q_moved {
+            castling.push('q');
+        }
+        if castling.is_empty() {
+            castling.push('-');
+        }
+
+        //the en passant square is behind the pawn that has moved
+        let enpassant = match cl.get_pawn_in_last_turn(board1) {
+            Some((3, b)) if cl.get_piece(board1, 3, b) == Piece::p => {
+                format!("{}{}", parser::ind2char(b).unwrap(), parser::ind2line(2).unwrap())
+            }
+            Some((4, b)) if cl.get_piece(board1, 4, b) == Piece::P => {
+                format!("{}{}", parser::ind2char(b).unwrap(), parser::ind2line(5).unwrap())
+            }
+            _ => "-".to_string(),
+        };
+
+        format!(
+            "{}{} {} {} {} {} {}",
+            pieces,
+            pocket,
+            activecol,
+            castling,
+            enpassant,
+            cl.get_half_moves(board1),
+            cl.get_movectr(board1)
         )
     }
-    
+
+    /// Read from 2 BFENs, the pools are read from the pockets
+    ///# Arguments
+    /// * `s1` - BFEN String board1
+    /// * `s2` - BFEN String board2
+    ///
+    /// Example bfen: "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR[Qnp] b KQkq e3 0 1"
+    /// A piece followed by ~ is a promoted piece, a missing pocket is read as an empty pool.
+    /// Returns None if the input is not legal.
+    pub fn read_bfen(s1: &str, s2: &str) -> Option<ChessLogic> {
+        let b1 = read_board_bfen(s1)?;
+        let b2 = read_board_bfen(s2)?;
+        Some(ChessLogic::resume(
+            b1.board,
+            b2.board,
+            b1.lastpawn,
+            b2.lastpawn,
+            Piece::E,
+            Piece::E,
+            b1.white_active,
+            b2.white_active,
+            b1.halfturns,
+            b2.halfturns,
+            b1.fullturns,
+            b2.fullturns,
+            b1.white_pool,
+            b1.black_pool,
+            b2.white_pool,
+            b2.black_pool,
+            Winner::N,
+        ))
+    }
+
+    /// Read from the combined form of gen_bfen_combined, two BFENs separated by |
+    ///# Arguments
+    /// * `s` - BFEN Strings of board1 and board2
+    pub fn read_bfen_combined(s: &str) -> Option<ChessLogic> {
+        let splt: Vec<&str> = s.split('|').collect();
+        if splt.len() != 2 {
+            return None;
+        }
+        read_bfen(splt[0].trim(), splt[1].trim())
+    }
+
+    ///Everything a BFEN String has for a board
+    struct BoardBfen {
+        board: ChessBoard,
+        white_pool: [u8; 5],
+        black_pool: [u8; 5],
+        white_active: bool,
+        lastpawn: Option<(usize, usize)>,
+        halfturns: usize,
+        fullturns: usize,
+    }
+
+    ///Reads the BFEN String of a board, returns None if input is not legal
+    fn read_board_bfen(s: &str) -> Option<BoardBfen> {
+        let splt: Vec<&str> = s.split_whitespace().collect();
+        if splt.len() != 6 {
+            return None;
+        }
+
+        //pieces and pocket
+        let (placement, pocket) = match splt[0].find('[') {
+            Some(x) => {
+                if !splt[0].ends_with(']') {
+                    return None;
+                }
+                (&splt[0][..x], &splt[0][x + 1..splt[0].len() - 1])
+            }
+            None => (splt[0], ""),
+        };
+        let locs: Vec<&str> = placement.split('/').collect();
+        if locs.len() != 8 {
+            return None;
+        }
+        let mut board = ChessBoard::new();
+        for (i, l) in locs.iter().enumerate() {
+            if !read_line(l, &mut board, i) {
+                return None;
+            }
+        }
+        let white_pool = parse_pool(&pocket.chars().filter(|c| c.is_ascii_uppercase()).collect::<String>())?;
+        let black_pool = parse_pool(&pocket.chars().filter(|c| !c.is_ascii_uppercase()).collect::<String>())?;
+
+        let white_active = match splt[1] {
+            "w" => true,
+            "b" => false,
+            _ => return None,
+        };
+
+        //castling, a missing right means that the rook has moved, no rights at all that the king has moved
+        let castling = splt[2];
+        if castling != "-" && (castling.is_empty() || !castling.chars().all(|c| "KQkq".contains(c))) {
+            return None;
+        }
+        board.white_rook_k_moved = !castling.contains('K');
+        board.white_rook_q_moved = !castling.contains('Q');
+        board.black_rook_k_moved = !castling.contains('k');
+        board.black_rook_q_moved = !castling.contains('q');
+        board.white_k_moved = board.white_rook_k_moved && board.white_rook_q_moved;
+        board.black_k_moved = board.black_rook_k_moved && board.black_rook_q_moved;
+
+        //en passant, the pawn is in front of the square
+        let lastpawn = match splt[3] {
+            "-" => None,
+            ep => {
+                let mut chars = ep.chars();
+                let b = char2ind(chars.next()?)?;
+                let a = line2line(chars.next()?)?;
+                if chars.next().is_some() {
+                    return None;
+                }
+                match a {
+                    2 => Some((3, b)),
+                    5 => Some((4, b)),
+                    _ => return None,
+                }
+            }
+        };
+
+        Some(BoardBfen {
+            board,
+            white_pool,
+            black_pool,
+            white_active,
+            lastpawn,
+            halfturns: parse_int_str(splt[4])?,
+            fullturns: parse_int_str(splt[5])?,
+        })
+    }
+
     ///Reads a pool string, returns none if input is not legal
     ///# Arguments
     /// * `st` - A String for deployable piece pool
-    fn parse_pool(st: &String) -> Option<[u8;5]> {
-        let mut ar = [0;5];
+    fn parse_pool(st: &str) -> Option<[u8; 5]> {
+        let mut ar = [0; 5];
         let mut upperfound = false;
         let mut lowerfound = false;
         for c in st.chars() {
             match c {
-                'P' => ar[0]+=1,
-                'R' => ar[1]+=1,
-                'N' => ar[2]+=1,
-                'B' => ar[3]+=1,
-                'Q' => ar[4]+=1,
-                'p' => ar[0]+=1,
-                'r' => ar[1]+=1,
-                'n' => ar[2]+=1,
-                'n' => ar[3]+=1,
-                'q' => ar[4]+=1,
-                _ => return None
+                'P' => ar[0] += 1,
+                'R' => ar[1] += 1,
+                'N' => ar[2] += 1,
+                'B' => ar[3] += 1,
+                'Q' => ar[4] += 1,
+                'p' => ar[0] += 1,
+                'r' => ar[1] += 1,
+                'n' => ar[2] += 1,
+                'b' => ar[3] += 1,
+                'q' => ar[4] += 1,
+                _ => return None,
+            }
+            if c.is_ascii_uppercase() {
+                upperfound = true;
+            } else {
+                lowerfound = true;
             }
         }
         if upperfound && lowerfound {
-            return None
+            return None;
         }
-        return Some(ar)
+        Some(ar)
     }
 
     ///Parse a legal int string to an integer, returns None if input is legal
     /// # Arguments
     /// * `st` - A string that is a valid decimal number
-    fn parse_int_str(st: &String) -> Option<usize> {
-        let mut amn = st.chars().count() as u32;
-        let mut intega: u32 = 1;
+    fn parse_int_str(st: &str) -> Option<usize> {
+        if st.is_empty() {
+            return None;
+        }
+        let mut intega: usize = 0;
         for c in st.chars() {
-            if let Some(x) = c.to_digit(10){
-                intega += 10u32.pow(amn)*x;
-                amn -=1;
-            }else{
-                return None
+            if let Some(x) = c.to_digit(10) {
+                intega = intega.checked_mul(10)?.checked_add(x as usize)?;
+            } else {
+                return None;
             }
         }
-        return Some(intega as usize);
+        Some(intega)
     }
 
     ///Parse a FEN substring for piece locations, false if input is not legal
@@ -487,84 +710,122 @@ pub mod infoCourier {
     /// * `s1` - A string that is a valid FEN Substring for pieces
     /// * `ch` - A pointer to a board, for saving the piece locations
     /// * `line` - index for the current line
-    fn read_line(s1: & String,ch: &mut ChessBoard,line:usize) -> bool{
+    ///
+    /// A piece followed by ~ is a promoted piece, e.g. Q~ is read as Piece::UQ
+    fn read_line(s1: &str, ch: &mut ChessBoard, line: usize) -> bool {
+        let mut ct = 0;
         for c in s1.chars() {
-            let mut ct = 0;
             if let Some(num) = c.to_digit(10) {
                 //somany emptiness
-                for i in 0..num {
+                if num == 0 || ct + num as usize > 8 {
+                    return false;
+                }
+                for _ in 0..num {
                     ch.board[line][ct] = Piece::E;
-                    ct+=1;
+                    ct += 1;
                 }
-            }else{
+            } else if c == '~' {
+                //the last piece is a promoted piece
+                if ct == 0 {
+                    return false;
+                }
+                match promoted(ch.board[line][ct - 1]) {
+                    Some(piece) => ch.board[line][ct - 1] = piece,
+                    None => return false,
+                }
+            } else {
                 //then it has to be one of the pieces
-                match c {
-                    'p' => {ch.board[line][ct]=Piece::p},
-                    'r' => {ch.board[line][ct]=Piece::r},
-                    'q' => {ch.board[line][ct]=Piece::q},
-                    'n' => {ch.board[line][ct]=Piece::n},
-                    'b' => {ch.board[line][ct]=Piece::b},
-                    'k' => {ch.board[line][ct]=Piece::k},
-                    'P' => {ch.board[line][ct]=Piece::P},
-                    'R' => {ch.board[line][ct]=Piece::R},
-                    'Q' => {ch.board[line][ct]=Piece::Q},
-                    'N' => {ch.board[line][ct]=Piece::N},
-                    'B' => {ch.board[line][ct]=Piece::B},
-                    'K' => {ch.board[line][ct]=Piece::K},
-                    _ =>  return false,
+                if ct >= 8 {
+                    return false;
+                }
+                match char2piece(c) {
+                    Some(piece) => ch.board[line][ct] = piece,
+                    None => return false,
                 }
-                ct+=1;
+                ct += 1;
             }
-            
         }
-        return true
+        ct == 8
+    }
+
+    ///Converts a FEN piece letter to a piece, None if the letter is not a piece
+    /// # Arguments
+    /// * `c` - the letter, capital case for white, lower case for black
+    fn char2piece(c: char) -> Option<Piece> {
+        match c {
+            'p' => Some(Piece::p),
+            'r' => Some(Piece::r),
+            'q' => Some(Piece::q),
+            'n' => Some(Piece::n),
+            'b' => Some(Piece::b),
+            'k' => Some(Piece::k),
+            'P' => Some(Piece::P),
+            'R' => Some(Piece::R),
+            'Q' => Some(Piece::Q),
+            'N' => Some(Piece::N),
+            'B' => Some(Piece::B),
+            'K' => Some(Piece::K),
+            _ => None,
+        }
+    }
+
+    ///Returns the promoted variant of a piece, None for pawns, kings and already promoted pieces
+    /// # Arguments
+    /// * `piece` - the piece a pawn was promoted to
+    fn promoted(piece: Piece) -> Option<Piece> {
+        match piece {
+            Piece::Q => Some(Piece::UQ),
+            Piece::R => Some(Piece::UR),
+            Piece::N => Some(Piece::UN),
+            Piece::B => Some(Piece::UB),
+            Piece::q => Some(Piece::Uq),
+            Piece::r => Some(Piece::Ur),
+            Piece::n => Some(Piece::Un),
+            Piece::b => Some(Piece::Ub),
+            _ => None,
+        }
     }
 
     ///Generates string output of a pool
     ///# Arguments
     /// ´p´ - A deployable piece pool
     /// ´white´ - wether the pieces are white
-    fn gen_pool(p:&[u8;5],white:bool) -> String {
+    fn gen_pool(p: &[u8; 5], white: bool) -> String {
         let mut st = "".to_string();
-        for i in 0..p[0] {
-           if white {
-            st.push('P');
-           } else{
-               st.push('p');
-           }
-           
-        }
-        for i in 0..p[1] {
+        for _ in 0..p[0] {
+            if white {
+                st.push('P');
+            } else {
+                st.push('p');
+            }
+        }
+        for _ in 0..p[1] {
             if white {
                 st.push('R');
-               } else{
-                   st.push('r');
-               }
-            
+            } else {
+                st.push('r');
+            }
         }
-        for i in 0..p[2] {
+        for _ in 0..p[2] {
             if white {
                 st.push('N');
-               } else{
-                   st.push('n');
-               }
-            
+            } else {
+                st.push('n');
+            }
         }
-        for i in 0..p[3] {
+        for _ in 0..p[3] {
             if white {
                 st.push('B');
-               } else{
-                   st.push('b');
-               }
-            
+            } else {
+                st.push('b');
+            }
         }
-        for i in 0..p[4] {
+        for _ in 0..p[4] {
             if white {
                 st.push('Q');
-               } else{
-                   st.push('Q');
-               }
-            
+            } else {
+                st.push('q');
+            }
         }
         st
     }
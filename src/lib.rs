@@ -2,24 +2,22 @@
 //!
 //! A library for creating and managing a bughouse chess game.
 
-#![allow(warnings)] 
+//every file keeps its items in a module of the same name, e.g. bpgn::bpgn
+#![allow(clippy::module_inception)]
+pub mod bpgn;
+pub mod engine;
+pub mod game;
+#[allow(non_snake_case)]
+pub mod infoCourier;
 pub mod logic;
 pub mod parse;
-pub mod infoCourier;
+pub mod replay;
 pub mod util;
 
 #[cfg(test)]
 mod tests_mod {
-    use super::*;
-    use crate::logic::board::Piece;
-    use crate::logic::ChessLogic;
-    use crate::parse::parser;
-    use crate::infoCourier::infoCourier::*;
-    use crate::util::contains;
-
     #[test]
     fn trivial() {
-        assert_eq!(1,1);
+        assert_eq!(1, 1);
     }
-
-}
\ No newline at end of file
+}
@@ -0,0 +1,302 @@
+use crate::logic::board::{ChessBoard, Piece};
+use std::sync::OnceLock;
+
+///A set of squares, the bit i*8+j is the square of row i and col j
+pub type Bitboard = u64;
+
+///Returns the bitboard of a single square
+/// # Arguments
+/// * `i` - row index
+/// * `j` - col index
+pub fn bit(i: usize, j: usize) -> Bitboard {
+    1 << (i * 8 + j)
+}
+
+///Returns the squares of a bitboard as (row,col), in the order of their index
+pub fn squares(mut bb: Bitboard) -> impl Iterator<Item = (usize, usize)> {
+    std::iter::from_fn(move || {
+        if bb == 0 {
+            return None;
+        }
+        let sq = bb.trailing_zeros() as usize;
+        bb &= bb - 1;
+        Some((sq / 8, sq % 8))
+    })
+}
+
+///Enum class for the kind of a piece regardless of its colour, a promoted piece moves like its kind
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Kind {
+    Pawn,
+    Rook,
+    Knight,
+    Bishop,
+    Queen,
+    King,
+}
+
+///Returns the kind and the colour of a piece, None for E and L
+pub fn kind(piece: Piece) -> Option<(Kind, bool)> {
+    match piece {
+        Piece::P => Some((Kind::Pawn, true)),
+        Piece::R | Piece::UR => Some((Kind::Rook, true)),
+        Piece::N | Piece::UN => Some((Kind::Knight, true)),
+        Piece::B | Piece::UB => Some((Kind::Bishop, true)),
+        Piece::Q | Piece::UQ => Some((Kind::Queen, true)),
+        Piece::K => Some((Kind::King, true)),
+        Piece::p => Some((Kind::Pawn, false)),
+        Piece::r | Piece::Ur => Some((Kind::Rook, false)),
+        Piece::n | Piece::Un => Some((Kind::Knight, false)),
+        Piece::b | Piece::Ub => Some((Kind::Bishop, false)),
+        Piece::q | Piece::Uq => Some((Kind::Queen, false)),
+        Piece::k => Some((Kind::King, false)),
+        Piece::E | Piece::L => None,
+    }
+}
+
+///The colour and the kind of each piece in the order of the Piece enum, 2 and 6 for E and L
+const SLOTS: [(usize, usize); 22] = {
+    let (p, r, n, b, q, k) = (
+        Kind::Pawn as usize,
+        Kind::Rook as usize,
+        Kind::Knight as usize,
+        Kind::Bishop as usize,
+        Kind::Queen as usize,
+        Kind::King as usize,
+    );
+    [
+        (0, p),
+        (0, r),
+        (0, n),
+        (0, b),
+        (0, q),
+        (0, k),
+        (2, 6),
+        (2, 6),
+        (1, p),
+        (1, r),
+        (1, n),
+        (1, b),
+        (1, q),
+        (1, k),
+        (0, r),
+        (0, n),
+        (0, b),
+        (0, q),
+        (1, r),
+        (1, n),
+        (1, b),
+        (1, q),
+    ]
+};
+
+///The directions of the sliding pieces as (row,col) steps, the first four increase the square index
+const DIRECTIONS: [(i32, i32); 8] = [(1, 0), (0, 1), (1, 1), (1, -1), (-1, 0), (0, -1), (-1, -1), (-1, 1)];
+
+///Attack tables, they are computed at the first call
+struct Tables {
+    knight: [Bitboard; 64],
+    king: [Bitboard; 64],
+    ///Squares a pawn attacks, white first
+    pawn: [[Bitboard; 64]; 2],
+    ///Squares from a square to the edge of the board in each direction of DIRECTIONS
+    rays: [[Bitboard; 64]; 8],
+}
+
+///Returns the squares reached from (i,j) by the steps, one step each
+fn steps(i: usize, j: usize, offsets: &[(i32, i32)]) -> Bitboard {
+    let mut bb = 0;
+    for (di, dj) in offsets.iter() {
+        let (a, b) = (i as i32 + di, j as i32 + dj);
+        if (0..=7).contains(&a) && (0..=7).contains(&b) {
+            bb |= bit(a as usize, b as usize);
+        }
+    }
+    bb
+}
+
+///Returns the tables
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let mut t = Tables {
+            knight: [0; 64],
+            king: [0; 64],
+            pawn: [[0; 64]; 2],
+            rays: [[0; 64]; 8],
+        };
+        let knight = [(-2, -1), (-2, 1), (2, -1), (2, 1), (-1, -2), (-1, 2), (1, -2), (1, 2)];
+        let king = [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)];
+        for i in 0..8 {
+            for j in 0..8 {
+                let sq = i * 8 + j;
+                t.knight[sq] = steps(i, j, &knight);
+                t.king[sq] = steps(i, j, &king);
+                t.pawn[0][sq] = steps(i, j, &[(-1, -1), (-1, 1)]);
+                t.pawn[1][sq] = steps(i, j, &[(1, -1), (1, 1)]);
+                for (d, (di, dj)) in DIRECTIONS.iter().enumerate() {
+                    let (mut a, mut b) = (i as i32 + di, j as i32 + dj);
+                    while (0..=7).contains(&a) && (0..=7).contains(&b) {
+                        t.rays[d][sq] |= bit(a as usize, b as usize);
+                        a += di;
+                        b += dj;
+                    }
+                }
+            }
+        }
+        t
+    })
+}
+
+///Returns the squares a slider reaches in a direction until the first piece, the piece included
+fn ray_attacks(d: usize, sq: usize, occ: Bitboard) -> Bitboard {
+    let rays = &tables().rays[d];
+    let ray = rays[sq];
+    let blockers = ray & occ;
+    if blockers == 0 {
+        return ray;
+    }
+    let first = if d < 4 {
+        blockers.trailing_zeros()
+    } else {
+        63 - blockers.leading_zeros()
+    };
+    ray ^ rays[first as usize]
+}
+
+///Returns the squares a rook on a square attacks
+/// # Arguments
+/// * `i` - row index
+/// * `j` - col index
+/// * `occ` - the occupied squares
+pub fn rook_attacks(i: usize, j: usize, occ: Bitboard) -> Bitboard {
+    let sq = i * 8 + j;
+    ray_attacks(0, sq, occ) | ray_attacks(1, sq, occ) | ray_attacks(4, sq, occ) | ray_attacks(5, sq, occ)
+}
+
+///Returns the squares a bishop on a square attacks
+/// # Arguments
+/// * `i` - row index
+/// * `j` - col index
+/// * `occ` - the occupied squares
+pub fn bishop_attacks(i: usize, j: usize, occ: Bitboard) -> Bitboard {
+    let sq = i * 8 + j;
+    ray_attacks(2, sq, occ) | ray_attacks(3, sq, occ) | ray_attacks(6, sq, occ) | ray_attacks(7, sq, occ)
+}
+
+///Returns the squares a knight on a square attacks
+pub fn knight_attacks(i: usize, j: usize) -> Bitboard {
+    tables().knight[i * 8 + j]
+}
+
+///Returns the squares a king on a square attacks
+pub fn king_attacks(i: usize, j: usize) -> Bitboard {
+    tables().king[i * 8 + j]
+}
+
+///Returns the squares a pawn on a square attacks
+/// # Arguments
+/// * `white` - true for a white pawn
+pub fn pawn_attacks(i: usize, j: usize, white: bool) -> Bitboard {
+    tables().pawn[if white { 0 } else { 1 }][i * 8 + j]
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+///The pieces of a board as bitboards
+pub struct Bitboards {
+    ///Pieces of each colour, white first
+    pub colours: [Bitboard; 2],
+    ///Pieces of each kind of both colours, in the order of Kind
+    pub kinds: [Bitboard; 6],
+    ///Every square that is not empty, including the squares with Piece::L
+    pub occupied: Bitboard,
+}
+
+impl Bitboards {
+    ///Creates the bitboards of a board
+    pub fn new(b: &ChessBoard) -> Bitboards {
+        //E and L go to the extra slot at the end
+        let mut colours = [0; 3];
+        let mut kinds = [0; 7];
+        let mut occupied = 0;
+        for (sq, piece) in b.board.iter().flatten().enumerate() {
+            let (colour, k) = SLOTS[*piece as usize];
+            colours[colour] |= 1 << sq;
+            kinds[k] |= 1 << sq;
+            occupied |= ((*piece != Piece::E) as u64) << sq;
+        }
+        Bitboards {
+            colours: [colours[0], colours[1]],
+            kinds: [kinds[0], kinds[1], kinds[2], kinds[3], kinds[4], kinds[5]],
+            occupied,
+        }
+    }
+
+    ///Puts a piece on a square, whatever was on it before is removed
+    /// # Arguments
+    /// * `i` - row index
+    /// * `j` - col index
+    /// * `piece` - the piece now on the square, Piece::E to empty it
+    pub fn set(&mut self, i: usize, j: usize, piece: Piece) {
+        let sq = bit(i, j);
+        for bb in self.colours.iter_mut().chain(self.kinds.iter_mut()) {
+            *bb &= !sq;
+        }
+        let (colour, k) = SLOTS[piece as usize];
+        if colour < 2 {
+            self.colours[colour] |= sq;
+        }
+        if k < 6 {
+            self.kinds[k] |= sq;
+        }
+        if piece == Piece::E {
+            self.occupied &= !sq;
+        } else {
+            self.occupied |= sq;
+        }
+    }
+
+    ///Returns the pieces of a colour
+    pub fn own(&self, white: bool) -> Bitboard {
+        self.colours[if white { 0 } else { 1 }]
+    }
+
+    ///Returns the pieces of a kind and a colour
+    pub fn pieces(&self, k: Kind, white: bool) -> Bitboard {
+        self.kinds[k as usize] & self.own(white)
+    }
+
+    ///Returns true if a piece of a colour attacks a square
+    /// # Arguments
+    /// * `i` - row index
+    /// * `j` - col index
+    /// * `by_white` - true if the attackers are white
+    pub fn attacked(&self, i: usize, j: usize, by_white: bool) -> bool {
+        let queens = self.pieces(Kind::Queen, by_white);
+        pawn_attacks(i, j, !by_white) & self.pieces(Kind::Pawn, by_white) != 0
+            || knight_attacks(i, j) & self.pieces(Kind::Knight, by_white) != 0
+            || king_attacks(i, j) & self.pieces(Kind::King, by_white) != 0
+            || rook_attacks(i, j, self.occupied) & (self.pieces(Kind::Rook, by_white) | queens) != 0
+            || bishop_attacks(i, j, self.occupied) & (self.pieces(Kind::Bishop, by_white) | queens) != 0
+    }
+
+    ///Returns the squares a piece can move to or capture on, without pawn pushes, castling and en passant
+    /// # Arguments
+    /// * `k` - the kind of the piece
+    /// * `white` - the colour of the piece
+    /// * `i` - row index
+    /// * `j` - col index
+    ///
+    ///Own pieces and Piece::L cannot be captured.
+    pub fn targets(&self, k: Kind, white: bool, i: usize, j: usize) -> Bitboard {
+        let attacks = match k {
+            Kind::Pawn => pawn_attacks(i, j, white) & self.own(!white),
+            Kind::Rook => rook_attacks(i, j, self.occupied),
+            Kind::Bishop => bishop_attacks(i, j, self.occupied),
+            Kind::Queen => rook_attacks(i, j, self.occupied) | bishop_attacks(i, j, self.occupied),
+            Kind::Knight => knight_attacks(i, j),
+            Kind::King => king_attacks(i, j),
+        };
+        attacks & (!self.occupied | self.own(!white))
+    }
+}
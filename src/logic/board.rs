@@ -1,19 +1,82 @@
 use std::fmt;
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 ///Enum Class representing a Chess Piece
-/// 
+///
 /// UX enums are the Upgraded variants of X Piece
 /// It is needed when a piece is captured bcs the captured
 /// Piece is sent to the teammate as a pawn.
 /// Capital case -> white, Lower case -> black
 /// Pawn, Rook, Knight, Bishop, Queen, King, Empty, Legal
 /// Legal and Empty are needed for printing the legal moves
+#[allow(non_camel_case_types)]
 pub enum Piece {
-    P,R,N,B,Q,K,E,L,
-    p,r,n,b,q,k,
-    UR,UN,UB,UQ,
-    Ur,Un,Ub,Uq,
+    P,
+    R,
+    N,
+    B,
+    Q,
+    K,
+    E,
+    L,
+    p,
+    r,
+    n,
+    b,
+    q,
+    k,
+    UR,
+    UN,
+    UB,
+    UQ,
+    Ur,
+    Un,
+    Ub,
+    Uq,
+}
+
+impl Piece {
+    ///Returns true if the piece is white, E and L are neither white nor black
+    pub fn is_white(&self) -> bool {
+        matches!(
+            self,
+            Piece::P
+                | Piece::R
+                | Piece::N
+                | Piece::B
+                | Piece::Q
+                | Piece::K
+                | Piece::UR
+                | Piece::UN
+                | Piece::UB
+                | Piece::UQ
+        )
+    }
+
+    ///Returns true if the piece is a promoted pawn, the U* pieces
+    pub fn is_promoted(&self) -> bool {
+        matches!(
+            self,
+            Piece::UR | Piece::UN | Piece::UB | Piece::UQ | Piece::Ur | Piece::Un | Piece::Ub | Piece::Uq
+        )
+    }
+
+    ///Returns true if the piece is black, E and L are neither white nor black
+    pub fn is_black(&self) -> bool {
+        matches!(
+            self,
+            Piece::p
+                | Piece::r
+                | Piece::n
+                | Piece::b
+                | Piece::q
+                | Piece::k
+                | Piece::Ur
+                | Piece::Un
+                | Piece::Ub
+                | Piece::Uq
+        )
+    }
 }
 
 impl fmt::Display for Piece {
@@ -27,7 +90,7 @@ impl fmt::Display for Piece {
             Piece::Q => write!(f, "Q"),
             Piece::K => write!(f, "K"),
             Piece::E => write!(f, " "),
-            Piece::L => write!(f, "L"), 
+            Piece::L => write!(f, "L"),
             Piece::r => write!(f, "r"),
             Piece::n => write!(f, "n"),
             Piece::b => write!(f, "b"),
@@ -46,26 +109,25 @@ impl fmt::Display for Piece {
     }
 }
 
+#[derive(Clone, Copy, PartialEq)]
 pub struct ChessBoard {
     ///Chess board as an 8x8 Piece array
-    pub board: [[Piece; 8]; 8], 
+    pub board: [[Piece; 8]; 8],
     ///A preconditon for castling
-    pub white_k_moved: bool, 
+    pub white_k_moved: bool,
     ///A preconditon for castling
-    pub black_k_moved: bool, 
+    pub black_k_moved: bool,
     ///A preconditon for Queenside castling
     pub white_rook_q_moved: bool,
     ///A preconditon for Kingside castling
-    pub white_rook_k_moved: bool, 
+    pub white_rook_k_moved: bool,
     ///A preconditon for Queenside castling
-    pub black_rook_q_moved: bool, 
+    pub black_rook_q_moved: bool,
     ///A preconditon for Kingside castling
-    pub black_rook_k_moved: bool, 
+    pub black_rook_k_moved: bool,
 }
 
-
 impl ChessBoard {
-
     ///Default constructor for the Chessboard
     pub fn new() -> ChessBoard {
         let arr = get_init_array();
@@ -78,86 +140,92 @@ impl ChessBoard {
             black_rook_q_moved: false,
             black_rook_k_moved: false,
         }
-        
     }
 
     ///Moves piece from old i old j to i j
     ///
     ///Does not check if the move is legal, or if wether the i,j are out of bounds
-    pub fn move_piece(&mut self,old_i: usize, old_j: usize, i:usize, j:usize) {
+    pub fn move_piece(&mut self, old_i: usize, old_j: usize, i: usize, j: usize) {
         self.board[i][j] = self.board[old_i][old_j];
         self.board[old_i][old_j] = Piece::E;
     }
 
-    ///Prints the chess board 
+    ///Prints the chess board
     pub fn print_board(&self) {
         for i in 0..8 {
             for j in 0..8 {
-                print!("[{}]",self.board[i][j]);
+                print!("[{}]", self.board[i][j]);
             }
-            println!("");
+            println!();
         }
-        
     }
 
-
-    ///Sets the board with the starts positions 
-    pub fn set_init_array(&mut self){
+    ///Sets the board with the starts positions
+    pub fn set_init_array(&mut self) {
         for i in 0..8 {
             self.board[1][i] = Piece::p;
             self.board[6][i] = Piece::P;
         }
 
-            self.board[7][0] = Piece::R;
-            self.board[7][1] = Piece::N;
-            self.board[7][2] = Piece::B;
-            self.board[7][3] = Piece::Q;
-            self.board[7][4] = Piece::K;
-            self.board[7][5] = Piece::B;
-            self.board[7][6] = Piece::N;
-            self.board[7][7] = Piece::R;
-            self.board[0][0] = Piece::r;
-            self.board[0][1] = Piece::n;
-            self.board[0][2] = Piece::b;
-            self.board[0][3] = Piece::q;
-            self.board[0][4] = Piece::k;
-            self.board[0][5] = Piece::b;
-            self.board[0][6] = Piece::n;
-            self.board[0][7] = Piece::r;
+        self.board[7][0] = Piece::R;
+        self.board[7][1] = Piece::N;
+        self.board[7][2] = Piece::B;
+        self.board[7][3] = Piece::Q;
+        self.board[7][4] = Piece::K;
+        self.board[7][5] = Piece::B;
+        self.board[7][6] = Piece::N;
+        self.board[7][7] = Piece::R;
+        self.board[0][0] = Piece::r;
+        self.board[0][1] = Piece::n;
+        self.board[0][2] = Piece::b;
+        self.board[0][3] = Piece::q;
+        self.board[0][4] = Piece::k;
+        self.board[0][5] = Piece::b;
+        self.board[0][6] = Piece::n;
+        self.board[0][7] = Piece::r;
     }
-
 }
 
-    /// Returns the start position of pieces
-    pub fn get_init_array() -> [[Piece; 8]; 8] {
-        let mut arr = [[Piece::E; 8]; 8];
-            for i in 0..8 {
-                arr[1][i] = Piece::p;
-                arr[6][i] = Piece::P;
-            }
-                arr[7][0] = Piece::R;
-                arr[7][1] = Piece::N;
-                arr[7][2] = Piece::B;
-                arr[7][3] = Piece::Q;
-                arr[7][4] = Piece::K;
-                arr[7][5] = Piece::B;
-                arr[7][6] = Piece::N;
-                arr[7][7] = Piece::R;
-                arr[0][0] = Piece::r;
-                arr[0][1] = Piece::n;
-                arr[0][2] = Piece::b;
-                arr[0][3] = Piece::q;
-                arr[0][4] = Piece::k;
-                arr[0][5] = Piece::b;
-                arr[0][6] = Piece::n;
-                arr[0][7] = Piece::r;
-    
-            arr
-        }
-
-
-
-
-
+impl Default for ChessBoard {
+    fn default() -> ChessBoard {
+        ChessBoard::new()
+    }
+}
 
+///Returns the piece of an index of a pool, the order is P-R-N-B-Q, E for any other index
+/// # Arguments
+/// * `ind` - index in the pool
+/// * `white` - true if the piece should be white
+pub(crate) fn pool_piece(ind: usize, white: bool) -> Piece {
+    let pieces = if white {
+        [Piece::P, Piece::R, Piece::N, Piece::B, Piece::Q]
+    } else {
+        [Piece::p, Piece::r, Piece::n, Piece::b, Piece::q]
+    };
+    pieces.get(ind).copied().unwrap_or(Piece::E)
+}
 
+/// Returns the start position of pieces
+pub fn get_init_array() -> [[Piece; 8]; 8] {
+    let mut arr = [[Piece::E; 8]; 8];
+    arr[1] = [Piece::p; 8];
+    arr[6] = [Piece::P; 8];
+    arr[7][0] = Piece::R;
+    arr[7][1] = Piece::N;
+    arr[7][2] = Piece::B;
+    arr[7][3] = Piece::Q;
+    arr[7][4] = Piece::K;
+    arr[7][5] = Piece::B;
+    arr[7][6] = Piece::N;
+    arr[7][7] = Piece::R;
+    arr[0][0] = Piece::r;
+    arr[0][1] = Piece::n;
+    arr[0][2] = Piece::b;
+    arr[0][3] = Piece::q;
+    arr[0][4] = Piece::k;
+    arr[0][5] = Piece::b;
+    arr[0][6] = Piece::n;
+    arr[0][7] = Piece::r;
+
+    arr
+}
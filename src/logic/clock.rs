@@ -0,0 +1,389 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+///A source of monotonic time for the chess clocks
+///
+///The clocks only care about differences between two calls of now,
+///so the starting point can be chosen freely by the implementation
+pub trait ClockSource {
+    ///Time passed since the (fixed) starting point of the source
+    fn now(&self) -> Duration;
+}
+
+///Clock source backed by the system's monotonic clock
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    ///Creates a clock source which starts counting at the time of the call
+    pub fn new() -> SystemClock {
+        SystemClock { start: Instant::now() }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> SystemClock {
+        SystemClock::new()
+    }
+}
+
+impl ClockSource for SystemClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+#[derive(Clone)]
+///Clock source that only moves when it is told to, needed for tests and replays
+///
+///Clones share the same time, so a clone can be handed to the game
+///while the caller keeps another one to advance the time
+pub struct ManualClock {
+    now: Rc<Cell<Duration>>,
+}
+
+impl ManualClock {
+    ///Creates a manual clock standing at zero
+    pub fn new() -> ManualClock {
+        ManualClock {
+            now: Rc::new(Cell::new(Duration::from_secs(0))),
+        }
+    }
+
+    ///Moves the time forward
+    /// # Arguments
+    /// * `d` - the amount of time that passes
+    pub fn advance(&self, d: Duration) {
+        self.now.set(self.now.get() + d);
+    }
+
+    ///Sets the time to an absolute value
+    /// # Arguments
+    /// * `d` - the new time since the start of the source
+    pub fn set(&self, d: Duration) {
+        self.now.set(d);
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> ManualClock {
+        ManualClock::new()
+    }
+}
+
+impl ClockSource for ManualClock {
+    fn now(&self) -> Duration {
+        self.now.get()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+///Enum class for the time added or not charged per move
+///
+///None -> sudden death
+///Fischer -> the increment is added after every move
+///Bronstein -> the used time is given back after the move, but at most the delay
+///Delay -> (simple/US delay) the clock starts counting down only after the delay
+pub enum Increment {
+    None,
+    Fischer(Duration),
+    Bronstein(Duration),
+    Delay(Duration),
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+///Time control of a single board, both players get the same time control
+pub struct TimeControl {
+    ///Starting time of each player
+    pub base: Duration,
+    ///Increment or delay per move
+    pub increment: Increment,
+}
+
+impl TimeControl {
+    ///Creates a time control
+    /// # Arguments
+    /// * `base` - the time each player starts with
+    /// * `increment` - increment or delay per move
+    pub fn new(base: Duration, increment: Increment) -> TimeControl {
+        TimeControl { base, increment }
+    }
+}
+
+#[derive(Clone)]
+///The two clocks of a single board
+///
+///Every method gets the current time of the clock source, the clock itself
+///only remembers when the running side was started
+pub struct ChessClock {
+    tc: TimeControl,
+    ///Remaining time at the moment the side was last stopped, white-black
+    remaining: [Duration; 2],
+    ///Some(true) if white's clock is running, Some(false) if black's, None if stopped
+    running: Option<bool>,
+    ///The time at which the running clock was started
+    since: Duration,
+}
+
+impl ChessClock {
+    ///Creates a stopped clock, both players have the base time
+    /// # Arguments
+    /// * `tc` - the time control of the board
+    pub fn new(tc: TimeControl) -> ChessClock {
+        ChessClock {
+            tc,
+            remaining: [tc.base, tc.base],
+            running: None,
+            since: Duration::from_secs(0),
+        }
+    }
+
+    ///Creates a stopped clock with the remaining times of the players, e.g. of an adjourned game
+    /// # Arguments
+    /// * `tc` - the time control of the board
+    /// * `remaining` - the remaining times, white-black
+    pub fn with_remaining(tc: TimeControl, remaining: [Duration; 2]) -> ChessClock {
+        ChessClock {
+            remaining,
+            ..ChessClock::new(tc)
+        }
+    }
+
+    ///Returns the time control of the clock
+    pub fn get_time_control(&self) -> TimeControl {
+        self.tc
+    }
+
+    ///Returns Some(true) if white's clock is running, Some(false) for black, None if stopped
+    pub fn get_running(&self) -> Option<bool> {
+        self.running
+    }
+
+    ///Time that is charged from the running side for the elapsed time
+    fn charge(&self, elapsed: Duration) -> Duration {
+        match self.tc.increment {
+            Increment::Delay(d) => elapsed.checked_sub(d).unwrap_or_default(),
+            _ => elapsed,
+        }
+    }
+
+    ///Returns the remaining time of a player
+    /// # Arguments
+    /// * `white` - true if white, false if black
+    /// * `now` - current time of the clock source
+    pub fn remaining(&self, white: bool, now: Duration) -> Duration {
+        let idx = if white { 0 } else { 1 };
+        if self.running == Some(white) {
+            let elapsed = now.checked_sub(self.since).unwrap_or_default();
+            self.remaining[idx]
+                .checked_sub(self.charge(elapsed))
+                .unwrap_or_default()
+        } else {
+            self.remaining[idx]
+        }
+    }
+
+    ///Starts the clock of a player, the other clock has to be stopped
+    /// # Arguments
+    /// * `white` - true if white's clock should run, false if black's
+    /// * `now` - current time of the clock source
+    pub fn start(&mut self, white: bool, now: Duration) {
+        if self.running.is_none() {
+            self.running = Some(white);
+            self.since = now;
+        }
+    }
+
+    ///Stops the running clock without applying any increment
+    /// # Arguments
+    /// * `now` - current time of the clock source
+    pub fn stop(&mut self, now: Duration) {
+        if let Some(white) = self.running {
+            let idx = if white { 0 } else { 1 };
+            self.remaining[idx] = self.remaining(white, now);
+            self.running = None;
+        }
+    }
+
+    ///Ends the turn of the running side and starts the clock of `white`
+    /// # Arguments
+    /// * `white` - the side to move after the switch
+    /// * `now` - current time of the clock source
+    ///
+    ///The increment or the bronstein delay is credited to the side that has moved.
+    ///If the clock was stopped it only starts the given side.
+    pub fn switch_to(&mut self, white: bool, now: Duration) {
+        match self.running {
+            Some(w) if w == white => {}
+            Some(w) => {
+                let idx = if w { 0 } else { 1 };
+                let elapsed = now.checked_sub(self.since).unwrap_or_default();
+                let mut left = self.remaining(w, now);
+                if left > Duration::from_secs(0) {
+                    match self.tc.increment {
+                        Increment::Fischer(inc) => left += inc,
+                        Increment::Bronstein(d) => left += std::cmp::min(elapsed, d),
+                        _ => {}
+                    }
+                }
+                self.remaining[idx] = left;
+                self.running = Some(white);
+                self.since = now;
+            }
+            None => self.start(white, now),
+        }
+    }
+
+    ///Returns the color whose flag has fallen, None if nobody has run out of time
+    /// # Arguments
+    /// * `now` - current time of the clock source
+    pub fn flagged(&self, now: Duration) -> Option<bool> {
+        for white in [true, false].iter() {
+            if self.remaining(*white, now) == Duration::from_secs(0) {
+                return Some(*white);
+            }
+        }
+        None
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+///The state of the four clocks without their source, e.g. to save an adjourned game
+pub struct ClockState {
+    ///Time control of both boards
+    pub tc: TimeControl,
+    ///Remaining times of the players, board1 first and white first on each board
+    pub remaining: [[Duration; 2]; 2],
+    ///The running side of each board, see ChessClock::get_running
+    pub running: [Option<bool>; 2],
+}
+
+#[derive(Clone)]
+///The four clocks of a bughouse game, two for each board, with a shared source
+pub struct GameClock {
+    source: Rc<dyn ClockSource>,
+    ///Clocks of board1
+    clock1: ChessClock,
+    ///Clocks of board2
+    clock2: ChessClock,
+}
+
+impl GameClock {
+    ///Creates the clocks of both boards with the same time control
+    /// # Arguments
+    /// * `tc` - time control for all of the players
+    /// * `source` - where the clocks get their time from
+    pub fn new(tc: TimeControl, source: Rc<dyn ClockSource>) -> GameClock {
+        GameClock {
+            source,
+            clock1: ChessClock::new(tc),
+            clock2: ChessClock::new(tc),
+        }
+    }
+
+    ///Creates stopped clocks with the remaining times of a state, the running sides of the state are not started
+    /// # Arguments
+    /// * `state` - the state, e.g. of an adjourned game
+    /// * `source` - where the clocks get their time from
+    pub fn from_state(state: &ClockState, source: Rc<dyn ClockSource>) -> GameClock {
+        GameClock {
+            source,
+            clock1: ChessClock::with_remaining(state.tc, state.remaining[0]),
+            clock2: ChessClock::with_remaining(state.tc, state.remaining[1]),
+        }
+    }
+
+    ///Returns the remaining times and the running sides at the current time of the source
+    pub fn state(&self) -> ClockState {
+        let now = self.now();
+        let board = |c: &ChessClock| [c.remaining(true, now), c.remaining(false, now)];
+        ClockState {
+            tc: self.clock1.get_time_control(),
+            remaining: [board(&self.clock1), board(&self.clock2)],
+            running: [self.clock1.get_running(), self.clock2.get_running()],
+        }
+    }
+
+    ///Sets the clocks of both boards back to the base time and stops them
+    pub fn reset(&mut self) {
+        self.clock1 = ChessClock::new(self.clock1.get_time_control());
+        self.clock2 = ChessClock::new(self.clock2.get_time_control());
+    }
+
+    ///Current time of the clock source
+    pub fn now(&self) -> Duration {
+        self.source.now()
+    }
+
+    ///Returns the clock of a board
+    /// # Arguments
+    /// * `board1` - true if board1, false if board2
+    pub fn get_clock(&self, board1: bool) -> &ChessClock {
+        match board1 {
+            true => &self.clock1,
+            false => &self.clock2,
+        }
+    }
+
+    fn get_clock_mut(&mut self, board1: bool) -> &mut ChessClock {
+        match board1 {
+            true => &mut self.clock1,
+            false => &mut self.clock2,
+        }
+    }
+
+    ///Returns the remaining time of a player
+    /// # Arguments
+    /// * `board1` - true if board1, false if board2
+    /// * `white` - true if white, false if black
+    pub fn remaining(&self, board1: bool, white: bool) -> Duration {
+        self.get_clock(board1).remaining(white, self.now())
+    }
+
+    ///Starts the clock of the given side on a board, if the board clock is stopped
+    /// # Arguments
+    /// * `board1` - true if board1, false if board2
+    /// * `white` - true if white's clock should run
+    pub fn start(&mut self, board1: bool, white: bool) {
+        let now = self.now();
+        self.get_clock_mut(board1).start(white, now);
+    }
+
+    ///Stops the clocks of both boards
+    pub fn stop(&mut self) {
+        let now = self.now();
+        self.clock1.stop(now);
+        self.clock2.stop(now);
+    }
+
+    ///Presses the clock of a board, the side to move afterwards is running
+    /// # Arguments
+    /// * `board1` - true if board1, false if board2
+    /// * `white` - the side to move after the press
+    pub fn press(&mut self, board1: bool, white: bool) {
+        let now = self.now();
+        self.get_clock_mut(board1).switch_to(white, now);
+    }
+
+    ///Stops the clock of a board and starts the given side without any increment, needed for takebacks
+    /// # Arguments
+    /// * `board1` - true if board1, false if board2
+    /// * `white` - the side to move
+    pub fn restart(&mut self, board1: bool, white: bool) {
+        let now = self.now();
+        let c = self.get_clock_mut(board1);
+        if c.get_running().is_some() {
+            c.stop(now);
+            c.start(white, now);
+        }
+    }
+
+    ///Returns the color whose flag has fallen on the board
+    /// # Arguments
+    /// * `board1` - true if board1, false if board2
+    pub fn flagged(&self, board1: bool) -> Option<bool> {
+        self.get_clock(board1).flagged(self.now())
+    }
+}
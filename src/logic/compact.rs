@@ -0,0 +1,369 @@
+use crate::logic::bitboard::{self, Kind};
+use crate::logic::board::{pool_piece, ChessBoard, Piece};
+use crate::logic::history::Action;
+use crate::logic::types::{BoardId, Color, Drop, Move, Seat, Square};
+use crate::logic::zobrist;
+use crate::logic::{ChessLogic, Winner};
+
+///Kind of a compact move that is neither a deploy nor a promotion
+const MOVE: u16 = 0;
+///Kind of a deploy, the promotions follow with 1 + the pool index of the piece
+const DROP: u16 = 1;
+
+///A move or a deploy in 16 bits, to play and take back many actions when searching
+///
+/// Bits 0-5 are the target square, bits 6-11 the start square or the pool index of a deployed piece and
+/// bits 12-14 the kind: 0 for a move, 1 for a deploy and 2-5 for a promotion to a rook, knight, bishop or queen.
+/// A square is row*8+col, so 0 is a8 and 63 is h1. The colour is the colour of the player to move.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct CompactMove(u16);
+
+///Returns the square of an index
+fn square(ind: u16) -> Square {
+    Square::new((ind / 8) as usize, (ind % 8) as usize).unwrap()
+}
+
+///Returns the index of a square
+fn index(sq: Square) -> u16 {
+    (sq.get_row() * 8 + sq.get_col()) as u16
+}
+
+///Returns the index of a piece in the pools, the order is P-R-N-B-Q, None for kings, E and L
+fn pool_index(piece: Piece) -> Option<u16> {
+    match bitboard::kind(piece) {
+        Some((Kind::Pawn, _)) => Some(0),
+        Some((Kind::Rook, _)) => Some(1),
+        Some((Kind::Knight, _)) => Some(2),
+        Some((Kind::Bishop, _)) => Some(3),
+        Some((Kind::Queen, _)) => Some(4),
+        _ => None,
+    }
+}
+
+impl CompactMove {
+    ///Creates a move without promotion
+    pub fn new(from: Square, to: Square) -> CompactMove {
+        CompactMove(MOVE << 12 | index(from) << 6 | index(to))
+    }
+
+    ///Creates a move that promotes a pawn, the colour of the piece does not matter
+    /// # Arguments
+    /// * `piece` - a rook, knight, bishop or queen
+    pub fn with_promotion(from: Square, to: Square, piece: Piece) -> CompactMove {
+        let ind = pool_index(piece)
+            .filter(|i| *i > 0)
+            .expect("a pawn is promoted to a rook, knight, bishop or queen");
+        CompactMove((DROP + ind) << 12 | index(from) << 6 | index(to))
+    }
+
+    ///Creates a deploy, the colour of the piece does not matter
+    /// # Arguments
+    /// * `piece` - a pawn, rook, knight, bishop or queen
+    pub fn drop(piece: Piece, to: Square) -> CompactMove {
+        let ind = pool_index(piece).expect("only pieces of the pool are deployed");
+        CompactMove(DROP << 12 | ind << 6 | index(to))
+    }
+
+    ///Creates the compact move of a move or a deploy, the board is not kept
+    pub fn from_action(action: &Action) -> CompactMove {
+        match *action {
+            Action::Move { mv, .. } => match mv.promotion {
+                Some(promotion) => CompactMove::with_promotion(mv.from, mv.to, promotion),
+                None => CompactMove::new(mv.from, mv.to),
+            },
+            Action::Drop { drop, .. } => CompactMove::drop(drop.piece, drop.to),
+        }
+    }
+
+    ///Returns the move or deploy of a player
+    /// # Arguments
+    /// * `board` - the board of the action
+    /// * `white` - the colour of the player
+    pub fn to_action(self, board: BoardId, white: bool) -> Action {
+        let to = self.get_to();
+        match self.get_kind() {
+            MOVE => Action::Move {
+                board,
+                mv: Move::new(square(self.0 >> 6 & 63), to),
+            },
+            DROP => Action::Drop {
+                board,
+                drop: Drop::new(pool_piece((self.0 >> 6 & 7) as usize, white), to),
+            },
+            k => Action::Move {
+                board,
+                mv: Move::with_promotion(square(self.0 >> 6 & 63), to, pool_piece((k - DROP) as usize, white)),
+            },
+        }
+    }
+
+    ///Returns the kind, see CompactMove
+    fn get_kind(self) -> u16 {
+        self.0 >> 12
+    }
+
+    ///Returns the target square
+    pub fn get_to(self) -> Square {
+        square(self.0 & 63)
+    }
+
+    ///Returns the start square of a move, None for a deploy
+    pub fn get_from(self) -> Option<Square> {
+        match self.get_kind() {
+            DROP => None,
+            _ => Some(square(self.0 >> 6 & 63)),
+        }
+    }
+
+    ///Returns true if it is a deploy
+    pub fn is_drop(self) -> bool {
+        self.get_kind() == DROP
+    }
+
+    ///Returns the promoted or deployed piece in a colour, None for a move without promotion
+    /// # Arguments
+    /// * `white` - the colour of the player
+    pub fn get_piece(self, white: bool) -> Option<Piece> {
+        self.get_pool_index().map(|ind| pool_piece(ind, white))
+    }
+
+    ///Returns the pool index of the promoted or deployed piece, None for a move without promotion
+    fn get_pool_index(self) -> Option<usize> {
+        match self.get_kind() {
+            MOVE => None,
+            DROP => Some((self.0 >> 6 & 7) as usize),
+            k => Some((k - DROP) as usize),
+        }
+    }
+}
+
+///What make changed, unmake puts it back
+///
+/// Only the squares of the action, the fields of its board, the one pool count it changed and the hashes are kept.
+#[derive(Clone, Copy)]
+pub struct Undo {
+    board1: bool,
+    ///The squares the action can change with their pieces before it, only the first len are used
+    squares: [(usize, usize, Piece); 4],
+    len: usize,
+    ///The castling flags of the board, see zobrist::castling_flags
+    castling: [bool; 6],
+    pawn_in_last_turn: Option<(usize, usize)>,
+    upgrade_to: Piece,
+    half_moves: usize,
+    movectr: usize,
+    ///The pool that changed with the index of the piece and the count before, see pool_mut
+    pool: Option<(usize, usize, u8)>,
+    winner: Winner,
+    hash1: u64,
+    hash2: u64,
+}
+
+///Sets the castling flags of a board, the inverse of zobrist::castling_flags
+fn set_castling_flags(b: &mut ChessBoard, f: [bool; 6]) {
+    b.white_k_moved = f[0];
+    b.white_rook_k_moved = f[1];
+    b.white_rook_q_moved = f[2];
+    b.black_k_moved = f[3];
+    b.black_rook_k_moved = f[4];
+    b.black_rook_q_moved = f[5];
+}
+
+impl ChessLogic {
+    ///Returns the moves and deploys of the player to move on a board, empty if the game has ended
+    /// # Arguments
+    /// * `board` - the board
+    ///
+    /// A move can leave the own king in check, after make is_in_check tells if it was legal.
+    /// A pawn reaching the last rank has a move for each promotion, the queen first. Deploys follow the pawn rules of
+    /// get_legal_drops, without drop_mate a deploy that checkmates is left out like in get_all_legal_actions.
+    pub fn compact_moves(&self, board: BoardId) -> Vec<CompactMove> {
+        let mut vec = Vec::new();
+        if self.winner != Winner::N {
+            return vec;
+        }
+        let board1 = board.is_board1();
+        let white = self.get_white_active(board1);
+        let bb = self.bitboards(board1);
+        let last = if white { 0 } else { 7 };
+        let mut moves = Vec::new();
+        for (i, j) in bitboard::squares(bb.own(white)) {
+            let from = Square::new(i, j).unwrap();
+            self.moves_with(&bb, board1, i, j, &mut moves);
+            let pawn = bb.pieces(Kind::Pawn, white) & bitboard::bit(i, j) != 0;
+            for &(a, b) in moves.iter() {
+                let to = Square::new(a, b).unwrap();
+                if pawn && a == last {
+                    for promotion in [Piece::Q, Piece::R, Piece::B, Piece::N].iter() {
+                        vec.push(CompactMove::with_promotion(from, to, *promotion));
+                    }
+                } else {
+                    vec.push(CompactMove::new(from, to));
+                }
+            }
+        }
+        let first = if white { 7 } else { 0 };
+        for ind in 0..5 {
+            if self.get_captured_piece(board1, white, ind) == 0 {
+                continue;
+            }
+            for (i, j) in bitboard::squares(!bb.occupied) {
+                if ind == 0 && (i == last || (i == first && !self.rules.pawn_drop_first_rank)) {
+                    continue;
+                }
+                vec.push(CompactMove(DROP << 12 | (ind as u16) << 6 | (i * 8 + j) as u16));
+            }
+        }
+        if !self.rules.drop_mate && !self.lookahead {
+            let seat = Seat::new(board, Color::from_white(white));
+            vec.retain(|m| !m.is_drop() || !self.drop_mates(seat, m.to_action(board, white)));
+        }
+        vec
+    }
+
+    ///Plays a move or deploy of the player to move without checking it, returns the state to take it back with unmake
+    /// # Arguments
+    /// * `board` - the board
+    /// * `m` - one of compact_moves
+    /// * `partner_pool` - false to keep the pools of the other board, e.g. for a search of one board
+    ///
+    /// Nothing else is done: no history, events, clock, premoves, checkmate, stalemate or draw. A king capture sets the winner.
+    /// The position hashes are updated from the squares of the action.
+    pub fn make(&mut self, board: BoardId, m: CompactMove, partner_pool: bool) -> Undo {
+        let board1 = board.is_board1();
+        let white = self.get_white_active(board1);
+        let to = m.get_to();
+        let (squares, len) = self.touched(board1, m);
+        let b = self.get_board_n(board1);
+        let mut undo = Undo {
+            board1,
+            squares: [(0, 0, Piece::E); 4],
+            len,
+            castling: zobrist::castling_flags(b),
+            pawn_in_last_turn: self.get_pawn_in_last_turn(board1),
+            upgrade_to: if board1 { self.upgrade_to1 } else { self.upgrade_to2 },
+            half_moves: self.get_half_moves(board1),
+            movectr: self.get_movectr(board1),
+            pool: None,
+            winner: self.winner,
+            hash1: self.hash1,
+            hash2: self.hash2,
+        };
+        for (k, &(i, j)) in squares[..len].iter().enumerate() {
+            undo.squares[k] = (i, j, b.board[i][j]);
+        }
+        let en_passant = zobrist::en_passant_key(b, undo.pawn_in_last_turn);
+        let pools = [0, 1, 2, 3].map(|n| *self.pool_mut(n));
+
+        match (m.get_from(), m.get_pool_index()) {
+            (None, Some(ind)) => {
+                let dropped = pool_piece(ind, white);
+                self.execute_deploy(board1, white, dropped, ind, to.get_row(), to.get_col());
+            }
+            (Some(from), promotion) => {
+                let promotion = promotion.map(|ind| pool_piece(ind, white));
+                self.apply_move(
+                    board1,
+                    from.get_row(),
+                    from.get_col(),
+                    to.get_row(),
+                    to.get_col(),
+                    promotion,
+                );
+            }
+            (None, None) => unreachable!("a deploy has a piece"),
+        }
+        //a deploy takes a piece from the pool of the board, a capture sends one to the pool of the other board
+        for (n, old) in pools.iter().enumerate() {
+            let board1_pool = n < 2;
+            if !partner_pool && board1_pool != board1 {
+                *self.pool_mut(n) = *old;
+            }
+            if let Some(ind) = (0..5).find(|ind| self.pool_mut(n)[*ind] != old[*ind]) {
+                undo.pool = Some((n, ind, old[ind]));
+            }
+        }
+
+        let b = self.get_board_n(board1);
+        let mut hash = if board1 { self.hash1 } else { self.hash2 };
+        for &(i, j, old) in undo.squares[..len].iter() {
+            hash ^= zobrist::square_key(old, i, j) ^ zobrist::square_key(b.board[i][j], i, j);
+        }
+        hash ^= zobrist::white_active_key();
+        let castling = zobrist::castling_flags(b);
+        for (f, (now, before)) in castling.iter().zip(undo.castling.iter()).enumerate() {
+            if now != before {
+                hash ^= zobrist::castling_key(f);
+            }
+        }
+        hash ^= en_passant ^ zobrist::en_passant_key(b, self.get_pawn_in_last_turn(board1));
+        let (mut hash1, mut hash2) = if board1 { (hash, self.hash2) } else { (self.hash1, hash) };
+        if let Some((n, ind, old)) = undo.pool {
+            let key = zobrist::pool_key(n % 2, ind, old) ^ zobrist::pool_key(n % 2, ind, self.pool_mut(n)[ind]);
+            if n < 2 {
+                hash1 ^= key;
+            } else {
+                hash2 ^= key;
+            }
+        }
+        self.hash1 = hash1;
+        self.hash2 = hash2;
+        undo
+    }
+
+    ///Takes back a make, the game is exactly as before it
+    /// # Arguments
+    /// * `undo` - the state returned by make, the makes after it have to be taken back first
+    pub fn unmake(&mut self, undo: &Undo) {
+        let board1 = undo.board1;
+        let b = if board1 {
+            &mut self.chess_board1
+        } else {
+            &mut self.chess_board2
+        };
+        let mut squares = [(0, 0); 4];
+        for (k, &(i, j, old)) in undo.squares[..undo.len].iter().enumerate() {
+            b.board[i][j] = old;
+            squares[k] = (i, j);
+        }
+        set_castling_flags(b, undo.castling);
+        self.update_bitboards(board1, &squares[..undo.len]);
+        self.set_pawn_in_last_turn(board1, undo.pawn_in_last_turn);
+        if board1 {
+            self.white_active_1 = !self.white_active_1;
+            self.upgrade_to1 = undo.upgrade_to;
+            self.half_moves_last_capture1 = undo.half_moves;
+            self.movectr1 = undo.movectr;
+        } else {
+            self.white_active_2 = !self.white_active_2;
+            self.upgrade_to2 = undo.upgrade_to;
+            self.half_moves_last_capture2 = undo.half_moves;
+            self.movectr2 = undo.movectr;
+        }
+        if let Some((n, ind, old)) = undo.pool {
+            self.pool_mut(n)[ind] = old;
+        }
+        self.winner = undo.winner;
+        self.hash1 = undo.hash1;
+        self.hash2 = undo.hash2;
+    }
+
+    ///Returns the squares an action can change, see move_squares, a deploy only changes its target
+    fn touched(&self, board1: bool, m: CompactMove) -> ([(usize, usize); 4], usize) {
+        let (i, j) = (m.get_to().get_row(), m.get_to().get_col());
+        match m.get_from() {
+            Some(from) => self.move_squares(board1, from.get_row(), from.get_col(), i, j),
+            None => ([(i, j), (0, 0), (0, 0), (0, 0)], 1),
+        }
+    }
+
+    ///Returns a pool: 0 and 1 are the pools of white and black on board1, 2 and 3 on board2
+    fn pool_mut(&mut self, n: usize) -> &mut [u8; 5] {
+        match n {
+            0 => &mut self.board1_white_capture,
+            1 => &mut self.board1_black_capture,
+            2 => &mut self.board2_white_capture,
+            _ => &mut self.board2_black_capture,
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use crate::logic::board::Piece;
+use crate::logic::types::{BoardId, Square};
+use crate::logic::{MoveError, Winner};
+use std::error::Error;
+use std::fmt;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+///Enum class for the reason an action is rejected
+///
+///GameOver -> the board has ended, it carries the final result
+///OutOfBounds -> a square is not on the board
+///EmptySquare -> there is no piece to move
+///WrongColor -> the piece belongs to the player who is not to move
+///NotTurn -> the deploying colour is not to move
+///IllegalPieceMove -> the piece cannot move like that
+///BlockedPath -> a piece stands between the squares
+///OwnPiece -> the target square is taken by an own piece
+///OccupiedSquare -> a piece cannot be deployed on a taken square
+///NotDeployable -> the piece is never in the pool of the player, e.g. a king or a piece of the other colour
+///EmptyPool -> the pool of the player has no such piece
+///PawnDropLastRank -> a pawn cannot be deployed on the last rank
+///PawnDropFirstRank -> the house rules forbid deploying a pawn on the first rank
+///MissingPromotion -> a pawn reaches the last rank without a promotion piece
+///InvalidPromotion -> a pawn cannot be promoted to the piece
+///LeavesKingInCheck -> the action leaves the own king in check
+///DropMate -> the house rules forbid a deploy that checkmates
+pub enum RejectCause {
+    GameOver(Winner),
+    OutOfBounds,
+    EmptySquare,
+    WrongColor,
+    NotTurn,
+    IllegalPieceMove,
+    BlockedPath,
+    OwnPiece,
+    OccupiedSquare,
+    NotDeployable,
+    EmptyPool,
+    PawnDropLastRank,
+    PawnDropFirstRank,
+    MissingPromotion,
+    InvalidPromotion,
+    LeavesKingInCheck,
+    DropMate,
+}
+
+impl RejectCause {
+    ///Returns the MoveError of the cause, the coarse error of movemaker and deploy_piece
+    pub fn kind(&self) -> MoveError {
+        match self {
+            RejectCause::GameOver(_) => MoveError::AlreadyOver,
+            RejectCause::WrongColor | RejectCause::NotTurn => MoveError::NotTurn,
+            RejectCause::OccupiedSquare
+            | RejectCause::NotDeployable
+            | RejectCause::PawnDropFirstRank
+            | RejectCause::DropMate => MoveError::CannotDeploy,
+            RejectCause::EmptyPool => MoveError::NoPieceInPool,
+            RejectCause::MissingPromotion | RejectCause::InvalidPromotion => MoveError::PromotionProblem,
+            _ => MoveError::NotLegal,
+        }
+    }
+}
+
+impl fmt::Display for RejectCause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RejectCause::GameOver(w) => match w {
+                Winner::W1 => write!(f, "the game is over, white won on board1"),
+                Winner::B1 => write!(f, "the game is over, black won on board1"),
+                Winner::W2 => write!(f, "the game is over, white won on board2"),
+                Winner::B2 => write!(f, "the game is over, black won on board2"),
+                Winner::P => write!(f, "the game is over by stalemate"),
+                Winner::D => write!(f, "the game is drawn"),
+                Winner::N => write!(f, "the game is over"),
+            },
+            RejectCause::OutOfBounds => write!(f, "the square is not on the board"),
+            RejectCause::EmptySquare => write!(f, "there is no piece to move"),
+            RejectCause::WrongColor => write!(f, "the piece belongs to the player who is not to move"),
+            RejectCause::NotTurn => write!(f, "it is not the turn of the player"),
+            RejectCause::IllegalPieceMove => write!(f, "the piece cannot move there"),
+            RejectCause::BlockedPath => write!(f, "the path is blocked"),
+            RejectCause::OwnPiece => write!(f, "the square is taken by an own piece"),
+            RejectCause::OccupiedSquare => write!(f, "a piece cannot be deployed on a taken square"),
+            RejectCause::NotDeployable => write!(f, "the piece cannot be deployed by the player"),
+            RejectCause::EmptyPool => write!(f, "the piece is not in the pool"),
+            RejectCause::PawnDropLastRank => write!(f, "a pawn cannot be deployed on the last rank"),
+            RejectCause::PawnDropFirstRank => write!(f, "a pawn cannot be deployed on the first rank"),
+            RejectCause::MissingPromotion => write!(f, "the pawn needs a promotion piece"),
+            RejectCause::InvalidPromotion => write!(f, "the pawn cannot be promoted to the piece"),
+            RejectCause::LeavesKingInCheck => write!(f, "the own king would be in check"),
+            RejectCause::DropMate => write!(f, "a deploy cannot checkmate"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+///A rejected move or deploy with the reason it is rejected
+///
+///from is None for a deploy, from and to are None if the square is out of bounds.
+///piece is the moved or deployed piece, Piece::E if there is none.
+pub struct ActionError {
+    pub board: BoardId,
+    pub from: Option<Square>,
+    pub to: Option<Square>,
+    pub piece: Piece,
+    pub cause: RejectCause,
+}
+
+impl ActionError {
+    ///Creates an error
+    /// # Arguments
+    /// * `board` - the board of the action
+    /// * `from` - the square of the moved piece, None for a deploy
+    /// * `to` - the target square
+    /// * `piece` - the moved or deployed piece
+    /// * `cause` - the reason the action is rejected
+    pub fn new(
+        board: BoardId,
+        from: Option<Square>,
+        to: Option<Square>,
+        piece: Piece,
+        cause: RejectCause,
+    ) -> ActionError {
+        ActionError {
+            board,
+            from,
+            to,
+            piece,
+            cause,
+        }
+    }
+
+    ///Returns the MoveError of the error, the coarse error of movemaker and deploy_piece
+    pub fn kind(&self) -> MoveError {
+        self.cause.kind()
+    }
+}
+
+impl fmt::Display for ActionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.board {
+            BoardId::Board1 => write!(f, "board1")?,
+            BoardId::Board2 => write!(f, "board2")?,
+        }
+        let piece = self.piece.to_string().to_uppercase();
+        match (self.from, self.to) {
+            (Some(from), Some(to)) => write!(f, " {}-{}", from, to)?,
+            (None, Some(to)) if self.piece != Piece::E => write!(f, " {}@{}", piece, to)?,
+            (Some(sq), None) | (None, Some(sq)) => write!(f, " {}", sq)?,
+            (None, None) => {}
+        }
+        write!(f, ": {}", self.cause)
+    }
+}
+
+impl Error for ActionError {}
+
+impl From<ActionError> for MoveError {
+    fn from(e: ActionError) -> MoveError {
+        e.kind()
+    }
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveError::NotLegal => write!(f, "the action is not legal"),
+            MoveError::NotTurn => write!(f, "it is not the turn of the player"),
+            MoveError::CannotDeploy => write!(f, "the piece cannot be deployed"),
+            MoveError::AlreadyOver => write!(f, "the game is over"),
+            MoveError::NoPieceInPool => write!(f, "the piece is not in the pool"),
+            MoveError::PromotionProblem => write!(f, "the promotion piece is not set"),
+        }
+    }
+}
+
+impl Error for MoveError {}
@@ -0,0 +1,216 @@
+use crate::logic::board::{pool_piece, Piece};
+use crate::logic::error::RejectCause;
+use crate::logic::history::{Action, Snapshot};
+use crate::logic::types::{BoardId, Color, Seat, Square};
+use crate::logic::Winner;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+///Enum class for the changes of the game, they are collected by ChessLogic::take_events and sent to the observers
+///
+///Moved -> a piece moved, when castling the king and the rook move
+///Captured -> a piece was captured on the square
+///SentToPool -> a piece was added to the pool of a player, board and color are the ones of the receiving player
+///Dropped -> a piece from the pool was deployed
+///Castled -> a player castled, kingside is false for the long castling
+///EnPassant -> a pawn captured en passant, captured is the square of the captured pawn
+///Promoted -> a pawn was promoted, piece is the promoted piece on the board, e.g. Piece::UQ
+///TurnChanged -> color is the player to move on the board
+///GameEnded -> the game ended, e.g. by a king capture, a resignation or a draw
+///PremoveFired -> a queued action of a player was played, it follows the events of the action
+///PremoveCancelled -> a queued action was removed, cause is the reason it was rejected, None if the player cancelled it
+///or an earlier action of the queue was rejected
+pub enum Event {
+    Moved {
+        board: BoardId,
+        piece: Piece,
+        from: Square,
+        to: Square,
+    },
+    Captured {
+        board: BoardId,
+        piece: Piece,
+        square: Square,
+    },
+    SentToPool {
+        board: BoardId,
+        color: Color,
+        piece: Piece,
+    },
+    Dropped {
+        board: BoardId,
+        piece: Piece,
+        to: Square,
+    },
+    Castled {
+        board: BoardId,
+        color: Color,
+        kingside: bool,
+    },
+    EnPassant {
+        board: BoardId,
+        from: Square,
+        to: Square,
+        captured: Square,
+    },
+    Promoted {
+        board: BoardId,
+        square: Square,
+        piece: Piece,
+    },
+    TurnChanged {
+        board: BoardId,
+        color: Color,
+    },
+    GameEnded {
+        winner: Winner,
+    },
+    PremoveFired {
+        seat: Seat,
+        action: Action,
+    },
+    PremoveCancelled {
+        seat: Seat,
+        action: Action,
+        cause: Option<RejectCause>,
+    },
+}
+
+///Returns the pools of a board in the snapshot, white first
+fn pools(s: &Snapshot, board1: bool) -> [[u8; 5]; 2] {
+    if board1 {
+        [s.board1_white_capture, s.board1_black_capture]
+    } else {
+        [s.board2_white_capture, s.board2_black_capture]
+    }
+}
+
+///Returns the piece on a square of the snapshot
+fn piece_at(s: &Snapshot, board1: bool, sq: Square) -> Piece {
+    s.get_piece(board1, sq.get_row(), sq.get_col())
+}
+
+///Returns the pieces that were added to the pools of a board, e.g. by a capture on the other board
+/// # Arguments
+/// * `before` - the state before
+/// * `after` - the state after
+/// * `board` - the board of the pools
+pub fn pool_events(before: &Snapshot, after: &Snapshot, board: BoardId) -> Vec<Event> {
+    let mut events = Vec::new();
+    let (old, new) = (pools(before, board.is_board1()), pools(after, board.is_board1()));
+    for (colour, white) in [true, false].iter().enumerate() {
+        for ind in 0..5 {
+            for _ in 0..new[colour][ind].saturating_sub(old[colour][ind]) {
+                events.push(Event::SentToPool {
+                    board,
+                    color: Color::from_white(*white),
+                    piece: pool_piece(ind, *white),
+                });
+            }
+        }
+    }
+    events
+}
+
+///Returns the events of a played action in the order they happened
+/// # Arguments
+/// * `before` - the state before the action
+/// * `after` - the state after the action
+/// * `action` - the action, with the resolved promotion of a move
+pub fn action_events(before: &Snapshot, after: &Snapshot, action: &Action) -> Vec<Event> {
+    let board = action.get_board();
+    let board1 = board.is_board1();
+    let mut events = Vec::new();
+    match *action {
+        Action::Move { mv, .. } => {
+            let piece = piece_at(before, board1, mv.from);
+            let target = piece_at(before, board1, mv.to);
+            let color = Color::from_white(piece.is_white());
+            let pawn = piece == Piece::P || piece == Piece::p;
+            let b = if board1 {
+                &before.chess_board1
+            } else {
+                &before.chess_board2
+            };
+            let k_moved = if piece == Piece::K {
+                b.white_k_moved
+            } else {
+                b.black_k_moved
+            };
+            let castling = (piece == Piece::K || piece == Piece::k)
+                && !k_moved
+                && mv.from.get_col() == 4
+                && mv.from.get_row() == mv.to.get_row()
+                && (mv.to.get_col() == 6 || mv.to.get_col() == 2);
+            if castling {
+                let row = mv.from.get_row();
+                let kingside = mv.to.get_col() == 6;
+                let (rook_from, rook_to) = if kingside { (7, 5) } else { (0, 3) };
+                let (rook_from, rook_to) = (Square::new(row, rook_from).unwrap(), Square::new(row, rook_to).unwrap());
+                events.push(Event::Castled { board, color, kingside });
+                events.push(Event::Moved {
+                    board,
+                    piece,
+                    from: mv.from,
+                    to: mv.to,
+                });
+                events.push(Event::Moved {
+                    board,
+                    piece: piece_at(before, board1, rook_from),
+                    from: rook_from,
+                    to: rook_to,
+                });
+            } else {
+                if target != Piece::E {
+                    events.push(Event::Captured {
+                        board,
+                        piece: target,
+                        square: mv.to,
+                    });
+                } else if pawn && mv.from.get_col() != mv.to.get_col() {
+                    let captured = Square::new(mv.from.get_row(), mv.to.get_col()).unwrap();
+                    events.push(Event::EnPassant {
+                        board,
+                        from: mv.from,
+                        to: mv.to,
+                        captured,
+                    });
+                }
+                events.push(Event::Moved {
+                    board,
+                    piece,
+                    from: mv.from,
+                    to: mv.to,
+                });
+                let placed = piece_at(after, board1, mv.to);
+                if pawn && placed != piece {
+                    events.push(Event::Promoted {
+                        board,
+                        square: mv.to,
+                        piece: placed,
+                    });
+                }
+            }
+        }
+        Action::Drop { drop, .. } => events.push(Event::Dropped {
+            board,
+            piece: drop.piece,
+            to: drop.to,
+        }),
+    }
+    events.extend(pool_events(before, after, board.other()));
+    let (old, new) = if board1 {
+        (before.white_active_1, after.white_active_1)
+    } else {
+        (before.white_active_2, after.white_active_2)
+    };
+    if old != new {
+        events.push(Event::TurnChanged {
+            board,
+            color: Color::from_white(new),
+        });
+    }
+    if before.winner == Winner::N && after.winner != Winner::N {
+        events.push(Event::GameEnded { winner: after.winner });
+    }
+    events
+}
@@ -0,0 +1,219 @@
+use crate::logic::board::ChessBoard;
+use crate::logic::board::Piece;
+use crate::logic::types::{BoardId, Drop, Move};
+use crate::logic::Winner;
+use std::fmt;
+use std::time::Duration;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+///Enum class for the actions a player can take on a board
+///
+///Move -> a piece is moved, a promotion is part of the move
+///Drop -> a piece from the pool is deployed
+pub enum Action {
+    Move { board: BoardId, mv: Move },
+    Drop { board: BoardId, drop: Drop },
+}
+
+impl Action {
+    ///Returns the board the action is played on
+    pub fn get_board(&self) -> BoardId {
+        match self {
+            Action::Move { board, .. } => *board,
+            Action::Drop { board, .. } => *board,
+        }
+    }
+}
+
+impl fmt::Display for Action {
+    ///Writes the move or the deploy without the board, e.g. "e2-e4" or "N@f3"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::Move { mv, .. } => write!(f, "{}", mv),
+            Action::Drop { drop, .. } => write!(f, "{}", drop),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+///Everything a move or a deploy can change in a ChessLogic, the clocks are not included
+pub struct Snapshot {
+    pub(crate) chess_board1: ChessBoard,
+    pub(crate) chess_board2: ChessBoard,
+    pub(crate) pawn_in_last_turn_b1: Option<(usize, usize)>,
+    pub(crate) pawn_in_last_turn_b2: Option<(usize, usize)>,
+    pub(crate) white_active_1: bool,
+    pub(crate) white_active_2: bool,
+    pub(crate) upgrade_to1: Piece,
+    pub(crate) upgrade_to2: Piece,
+    pub(crate) half_moves_last_capture1: usize,
+    pub(crate) half_moves_last_capture2: usize,
+    pub(crate) movectr1: usize,
+    pub(crate) movectr2: usize,
+    pub(crate) board1_white_capture: [u8; 5],
+    pub(crate) board1_black_capture: [u8; 5],
+    pub(crate) board2_white_capture: [u8; 5],
+    pub(crate) board2_black_capture: [u8; 5],
+    pub(crate) winner: Winner,
+    pub(crate) hash1: u64,
+    pub(crate) hash2: u64,
+}
+
+impl Snapshot {
+    ///Returns the piece on a board of the snapshot
+    /// # Arguments
+    /// * `board1` - true if board1, false if board2
+    /// * `i` - row index
+    /// * `j` - col index
+    pub fn get_piece(&self, board1: bool, i: usize, j: usize) -> Piece {
+        match board1 {
+            true => self.chess_board1.board[i][j],
+            false => self.chess_board2.board[i][j],
+        }
+    }
+}
+
+#[derive(Clone)]
+///A played action with the state of the game before and after it
+pub struct HistoryEntry {
+    ///The action that was played
+    pub action: Action,
+    before: Snapshot,
+    after: Snapshot,
+    ///Remaining time of the player after the action, None if the game is played without clock
+    remaining: Option<Duration>,
+    ///Number of the action in the game counted over both boards, the first action is 1
+    seq: usize,
+    ///Time of the clock source when the action was played, None if the game is played without clock
+    timestamp: Option<Duration>,
+}
+
+impl HistoryEntry {
+    ///Returns the state of the game before the action
+    pub fn get_before(&self) -> &Snapshot {
+        &self.before
+    }
+
+    ///Returns the state of the game after the action
+    pub fn get_after(&self) -> &Snapshot {
+        &self.after
+    }
+
+    ///Returns the remaining time of the player after the action, None if there was no clock
+    pub fn get_remaining(&self) -> Option<Duration> {
+        self.remaining
+    }
+
+    ///Returns the number of the action in the game counted over both boards, the first action is 1
+    pub fn get_seq(&self) -> usize {
+        self.seq
+    }
+
+    ///Returns the time of the clock source when the action was played, None if there was no clock
+    pub fn get_timestamp(&self) -> Option<Duration> {
+        self.timestamp
+    }
+}
+
+#[derive(Clone)]
+///The actions of both boards in the order they were played
+///
+///Since an action on one board can change the pools of the other board,
+///the history is shared by both boards and undo always takes back the latest action
+pub struct History {
+    ///Played actions, the latest is the last
+    entries: Vec<HistoryEntry>,
+    ///Actions that were taken back, the latest undo is the last
+    undone: Vec<HistoryEntry>,
+    ///Number of the last recorded action, it is not decreased by undo
+    last_seq: usize,
+}
+
+impl History {
+    ///Creates an empty history
+    pub fn new() -> History {
+        History {
+            entries: Vec::new(),
+            undone: Vec::new(),
+            last_seq: 0,
+        }
+    }
+
+    ///Records a played action, actions that were taken back cannot be redone afterwards
+    /// # Arguments
+    /// * `action` - the action that was played
+    /// * `before` - the state before the action
+    /// * `after` - the state after the action
+    /// * `remaining` - the remaining time of the player after the action
+    /// * `timestamp` - the time of the clock source
+    ///
+    ///The action gets the next number, the numbers of taken back actions are not used again.
+    pub fn push(
+        &mut self,
+        action: Action,
+        before: Snapshot,
+        after: Snapshot,
+        remaining: Option<Duration>,
+        timestamp: Option<Duration>,
+    ) {
+        self.undone.clear();
+        self.last_seq += 1;
+        let seq = self.last_seq;
+        self.entries.push(HistoryEntry {
+            action,
+            before,
+            after,
+            remaining,
+            seq,
+            timestamp,
+        });
+    }
+
+    ///Sets the remaining time of the latest action, needed if the times are read from a game record
+    /// # Arguments
+    /// * `remaining` - the remaining time of the player after the action
+    pub(crate) fn set_last_remaining(&mut self, remaining: Option<Duration>) {
+        if let Some(e) = self.entries.last_mut() {
+            e.remaining = remaining;
+        }
+    }
+
+    ///Sets the timestamp of the latest action, needed if the actions are replayed from a log
+    /// # Arguments
+    /// * `timestamp` - the time of the clock source when the action was played
+    pub(crate) fn set_last_timestamp(&mut self, timestamp: Option<Duration>) {
+        if let Some(e) = self.entries.last_mut() {
+            e.timestamp = timestamp;
+        }
+    }
+
+    ///Moves the latest action to the undone actions, returns the entry if there was one
+    pub fn undo(&mut self) -> Option<&HistoryEntry> {
+        let e = self.entries.pop()?;
+        self.undone.push(e);
+        self.undone.last()
+    }
+
+    ///Moves the latest undone action back to the played actions
+    pub fn redo(&mut self) -> Option<&HistoryEntry> {
+        let e = self.undone.pop()?;
+        self.entries.push(e);
+        self.entries.last()
+    }
+
+    ///Returns the played actions, the latest is the last
+    pub fn get_entries(&self) -> &Vec<HistoryEntry> {
+        &self.entries
+    }
+
+    ///Returns true if there is an action that can be redone
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+}
+
+impl Default for History {
+    fn default() -> History {
+        History::new()
+    }
+}
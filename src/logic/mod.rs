@@ -1,160 +1,251 @@
+pub mod bitboard;
 pub mod board;
-pub mod tests;
+pub mod clock;
+pub mod compact;
+pub mod error;
+pub mod events;
+pub mod history;
+pub mod perft;
+pub mod rules;
+#[cfg(test)]
+mod tests;
+pub mod types;
+pub mod zobrist;
+use crate::logic::bitboard::{Bitboards, Kind};
 use crate::logic::board::ChessBoard;
 use crate::logic::board::Piece;
+use crate::logic::clock::{ClockSource, GameClock, TimeControl};
+use crate::logic::error::{ActionError, RejectCause};
+use crate::logic::events::Event;
+use crate::logic::history::{Action, History, HistoryEntry, Snapshot};
+use crate::logic::rules::{DropReset, Rules, Termination};
+use crate::logic::types::{BoardId, CheckStatus, Color, Drop, Move, Seat, Square, Team, TeamResult};
+use std::cell::Cell;
 use std::cmp;
+use std::collections::VecDeque;
+use std::rc::Rc;
+use std::time::Duration;
 
-
-
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 ///Enum class to keep track of the winner
 ///
-///It can be W,B from Board 1 or 2, None or Stalemate(Patt)
+///It can be W,B from Board 1 or 2, None, Stalemate(Patt) or a Draw, e.g. by repetition
 pub enum Winner {
-    W1,B1,N,P,W2,B2,
+    W1,
+    B1,
+    N,
+    P,
+    W2,
+    B2,
+    D,
+}
+
+impl Winner {
+    ///Returns the player that has won, None if there is no winner
+    pub fn seat(&self) -> Option<Seat> {
+        match self {
+            Winner::W1 => Some(Seat::new(BoardId::Board1, Color::White)),
+            Winner::B1 => Some(Seat::new(BoardId::Board1, Color::Black)),
+            Winner::W2 => Some(Seat::new(BoardId::Board2, Color::White)),
+            Winner::B2 => Some(Seat::new(BoardId::Board2, Color::Black)),
+            Winner::N | Winner::P | Winner::D => None,
+        }
+    }
+
+    ///Returns the team that has won, None if there is no winner
+    pub fn team(&self) -> Option<Team> {
+        self.seat().map(|s| s.team())
+    }
+
+    ///Returns the winner for a player, the inverse of seat
+    /// # Arguments
+    /// * `seat` - the player that has won
+    pub fn from_seat(seat: Seat) -> Winner {
+        match (seat.board, seat.color) {
+            (BoardId::Board1, Color::White) => Winner::W1,
+            (BoardId::Board1, Color::Black) => Winner::B1,
+            (BoardId::Board2, Color::White) => Winner::W2,
+            (BoardId::Board2, Color::Black) => Winner::B2,
+        }
+    }
 }
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 ///Enum class for possible movement errors, could be usefull for calls from a server
-/// 
+///
 ///NotLegal -> move is not a legal move
 ///NotTurn -> not the turn of the caller
 ///CannotDeploy -> the given piece cannot be deployed, e.g. the cell is not empty
 ///NoPieceInPool -> the pool for needed piece is empty
 ///PromotionProblem -> the promotion booleans are not set (the boolean has to be set to promote pawns)
 pub enum MoveError {
-    NotLegal,NotTurn,CannotDeploy,AlreadyOver,NoPieceInPool,
+    NotLegal,
+    NotTurn,
+    CannotDeploy,
+    AlreadyOver,
+    NoPieceInPool,
     PromotionProblem,
 }
 
+///Callback that receives the events of a game, see subscribe
+type Observer = Box<dyn FnMut(&Event)>;
+
 ///Chesslogic struct has everything needed for a Bughouse game
 pub struct ChessLogic {
     ///Chessboard of game 1, aka. board1
-    pub chess_board1: ChessBoard, 
-    ///Chessboard of game 2, aka. board2
-    pub chess_board2: ChessBoard, 
+    ///
+    /// After changing its array directly, reset_bitboards has to be called before the next move.
+    pub chess_board1: ChessBoard,
+    ///Chessboard of game 2, aka. board2, the same as for board1 holds
+    pub chess_board2: ChessBoard,
     ///The last moved pawn of board1, None if another piece has moved
-    pawn_in_last_turn_b1: Option<(usize,usize)>,
+    pawn_in_last_turn_b1: Option<(usize, usize)>,
     ///The last moved pawn of board2, None if another piece has moved
-    pawn_in_last_turn_b2: Option<(usize,usize)>,
+    pawn_in_last_turn_b2: Option<(usize, usize)>,
     ///True if white is active on board1, else false
-    white_active_1: bool, 
+    white_active_1: bool,
     ///True if white is active on board2, else false
-    white_active_2: bool, 
+    white_active_2: bool,
     ///The piece to upgrade to in the next pawn, board1
-    pub upgrade_to1: Piece, 
+    pub upgrade_to1: Piece,
     ///The piece to upgrade to in the next pawn, board2
-    pub upgrade_to2: Piece, 
+    pub upgrade_to2: Piece,
     ///Count of half moves since last pawn move or piece capture, board1
-    half_moves_last_capture1: usize, 
+    half_moves_last_capture1: usize,
     ///Same but for board2
-    half_moves_last_capture2: usize, 
+    half_moves_last_capture2: usize,
     ///Number of moves in board1
-    movectr1: usize, 
+    movectr1: usize,
     ///Same but for board2
-    movectr2: usize, 
+    movectr2: usize,
 
-   
     ///Pieces that can be deployed on board1 (white pieces),the order is P-R-N-B-Q
-    board1_white_capture: [u8;5], 
+    board1_white_capture: [u8; 5],
     ///Same but for board1,black
-    board1_black_capture: [u8;5], 
+    board1_black_capture: [u8; 5],
     ///Same but for board2,white
-    board2_white_capture: [u8;5], 
+    board2_white_capture: [u8; 5],
     ///Same but for board2,black
-    board2_black_capture: [u8;5],
-    ///A field to save the winner 
+    board2_black_capture: [u8; 5],
+    ///A field to save the winner
     winner: Winner,
+    ///Clocks of the four players, None if the game is played without time
+    clock: Option<GameClock>,
+    ///Played moves and deploys of both boards
+    history: History,
+    ///House rules of the game
+    rules: Rules,
+    ///True for the copies made to look ahead, they skip the checks that look ahead themselves
+    lookahead: bool,
+    ///Position hash of board1, updated by every move and deploy
+    hash1: u64,
+    ///Same but for board2
+    hash2: u64,
+    ///Events that have not been taken by take_events
+    events: Vec<Event>,
+    ///Callbacks that receive every event
+    observers: Vec<Observer>,
+    ///Queued moves and deploys of the players, see seat_index for the order
+    premoves: [VecDeque<Action>; 4],
+    ///True while the premoves are played, so a premove does not start playing the premoves again
+    premoving: bool,
+    ///Bitboards of the boards, board1 first, None until they are created from the array at the first use
+    ///
+    /// Moves and deploys update them square by square, the functions that change the whole array set them to None.
+    bitboards: [Cell<Option<Bitboards>>; 2],
 }
 
 impl ChessLogic {
-
     ///Sets both games to initial state (none of the games have started)
-    pub fn refresh(&mut self){
+    pub fn refresh(&mut self) {
         self.chess_board1.set_init_array();
-       
+
         self.chess_board1.white_k_moved = false;
-        self.chess_board1.black_k_moved = false; 
-        self.chess_board1.white_rook_q_moved = false; 
-        self.chess_board1.white_rook_k_moved = false; 
-        self.chess_board1.black_rook_q_moved = false; 
-        self.chess_board1.black_rook_k_moved = false; 
+        self.chess_board1.black_k_moved = false;
+        self.chess_board1.white_rook_q_moved = false;
+        self.chess_board1.white_rook_k_moved = false;
+        self.chess_board1.black_rook_q_moved = false;
+        self.chess_board1.black_rook_k_moved = false;
 
         self.chess_board2.set_init_array();
 
-        self.chess_board2.white_k_moved = false; 
-        self.chess_board2.black_k_moved = false; 
-        self.chess_board2.white_rook_q_moved = false; 
-        self.chess_board2.white_rook_k_moved = false; 
-        self.chess_board2.black_rook_q_moved = false; 
-        self.chess_board2.black_rook_k_moved = false; 
-
-        self.pawn_in_last_turn_b1 = None;  
-        self.pawn_in_last_turn_b2 = None; 
-        self.upgrade_to1 = Piece::E; 
-        self.upgrade_to2 = Piece::E; 
-        self.white_active_1 = true; 
-        self.white_active_2 = true; 
-        self.half_moves_last_capture1 = 0; 
-        self.half_moves_last_capture2 = 0; 
-        self.movectr1 = 1; 
-        self.movectr2 = 1; 
-        self.board1_white_capture = [0;5]; 
-        self.board1_black_capture = [0;5]; 
-        self.board2_white_capture = [0;5]; 
-        self.board2_black_capture = [0;5]; 
-        self.winner= Winner::N; 
+        self.chess_board2.white_k_moved = false;
+        self.chess_board2.black_k_moved = false;
+        self.chess_board2.white_rook_q_moved = false;
+        self.chess_board2.white_rook_k_moved = false;
+        self.chess_board2.black_rook_q_moved = false;
+        self.chess_board2.black_rook_k_moved = false;
+
+        self.pawn_in_last_turn_b1 = None;
+        self.pawn_in_last_turn_b2 = None;
+        self.upgrade_to1 = Piece::E;
+        self.upgrade_to2 = Piece::E;
+        self.white_active_1 = true;
+        self.white_active_2 = true;
+        self.half_moves_last_capture1 = 0;
+        self.half_moves_last_capture2 = 0;
+        self.movectr1 = 1;
+        self.movectr2 = 1;
+        self.board1_white_capture = [0; 5];
+        self.board1_black_capture = [0; 5];
+        self.board2_white_capture = [0; 5];
+        self.board2_black_capture = [0; 5];
+        self.winner = Winner::N;
+        if let Some(c) = self.clock.as_mut() {
+            c.reset();
+        }
+        self.history = History::new();
+        self.reset_bitboards(true);
+        self.reset_bitboards(false);
     }
 
     ///Prints one of the boards
     /// # Arguments
     /// * `board1` - true if board1, false if board 2
-    pub fn print(&self,board1:bool){
-        if board1{
+    pub fn print(&self, board1: bool) {
+        if board1 {
             self.chess_board1.print_board();
             println!("------------------------");
-        }else{
+        } else {
             self.chess_board2.print_board();
             println!("------------------------");
         }
-
     }
 
     ///Return if the king has moved
     /// # Arguments
     /// * `board1` - true if board1, false if board2
     /// * `white` - true if white, false if black
-
-    pub fn get_if_king_moved(&self,board1:bool,white:bool) -> bool{
+    pub fn get_if_king_moved(&self, board1: bool, white: bool) -> bool {
         if board1 {
-            if white{
+            if white {
                 self.chess_board1.white_k_moved
-            }  else{
+            } else {
                 self.chess_board1.black_k_moved
-            }         
-        }else{
-            if white{
-               self.chess_board2.white_k_moved
-            }else{
+            }
+        } else {
+            if white {
+                self.chess_board2.white_k_moved
+            } else {
                 self.chess_board2.black_k_moved
             }
         }
     }
 
     ///Returns the capture pools
-    pub fn get_pools(&self) -> ([u8;5],[u8;5],[u8;5],[u8;5]){
+    pub fn get_pools(&self) -> ([u8; 5], [u8; 5], [u8; 5], [u8; 5]) {
         (
-            self.board1_white_capture, 
-            self.board1_black_capture, 
-            self.board2_white_capture, 
-            self.board2_black_capture
+            self.board1_white_capture,
+            self.board1_black_capture,
+            self.board2_white_capture,
+            self.board2_black_capture,
         )
     }
 
     ///Get a bool that is true if white is active
     ///# Arguments
     /// * `board1` - true if board1, false if board2
-    pub fn get_white_active(&self, board1:bool) -> bool{
+    pub fn get_white_active(&self, board1: bool) -> bool {
         match board1 {
             true => self.white_active_1,
             false => self.white_active_2,
@@ -164,7 +255,7 @@ impl ChessLogic {
     ///Get number of played turns
     /// # Arguments
     /// * `board1` - true if board1, false if board2
-    pub fn get_movectr(&self, board1:bool) -> usize {
+    pub fn get_movectr(&self, board1: bool) -> usize {
         match board1 {
             true => self.movectr1,
             false => self.movectr2,
@@ -174,7 +265,7 @@ impl ChessLogic {
     ///Get half moves since last capture or pawn movement
     /// # Arguments
     /// * `board1` - true if board1, false if board2
-    pub fn get_half_moves(&self, board1:bool) -> usize {
+    pub fn get_half_moves(&self, board1: bool) -> usize {
         match board1 {
             true => self.half_moves_last_capture1,
             false => self.half_moves_last_capture2,
@@ -186,64 +277,26 @@ impl ChessLogic {
     /// * `board1` - true if board1, false if board2
     /// * `white` - true if white, false if black
     /// * `i` - index for the pool, can be generated with box_index
-    pub fn get_captured_piece(&self, board1:bool,white:bool, i:usize) -> u8 {
+    pub fn get_captured_piece(&self, board1: bool, white: bool, i: usize) -> u8 {
         if board1 {
-            if white { 
+            if white {
                 self.board1_white_capture[i]
-            }else{
+            } else {
                 self.board1_black_capture[i]
             }
-        }else{
+        } else {
             if white {
                 self.board2_white_capture[i]
-            }else{
+            } else {
                 self.board2_black_capture[i]
             }
         }
     }
 
-        ///Sets the captured pieces after a piece capture
-        /// # Arguments
-        /// * `board1` - true if board1, false if board2
-        /// * `white` - true if white, false if black
-        /// * `i` - index for the pool, can be generated with box_index
-        /// * `inc` - if inc then the piece count in the pool will be increased by 1 else decreased by 1
-        fn set_captured_piece(&mut self, board1:bool,white:bool, i:usize,inc:bool){
-            if board1 {
-                if white { 
-                   if !inc {
-                    self.board1_white_capture[i]-=1;
-                   } else{
-                    self.board1_white_capture[i]+=1;
-                   }
-                }else{
-                    if !inc {
-                        self.board1_black_capture[i]-=1;
-                       } else{
-                        self.board1_black_capture[i]+=1;
-                       }
-                }
-            }else{
-                if white {
-                    if !inc {
-                        self.board2_white_capture[i]-=1;
-                       } else{
-                        self.board2_white_capture[i]+=1;
-                       }
-                }else{
-                    if !inc {
-                        self.board2_black_capture[i]-=1;
-                       } else{
-                        self.board2_black_capture[i]+=1;
-                       }
-                }
-            }
-        }
-
     ///Returns the last moved pawn, needed for enpassant encoding
     /// # Arguments
     /// * `board1` - true if board1, false if board2
-    pub fn get_pawn_in_last_turn(&self, board1:bool) -> Option<(usize,usize)> {
+    pub fn get_pawn_in_last_turn(&self, board1: bool) -> Option<(usize, usize)> {
         match board1 {
             true => self.pawn_in_last_turn_b1,
             false => self.pawn_in_last_turn_b2,
@@ -252,17 +305,17 @@ impl ChessLogic {
 
     ///Returns the winner type (white,black,none ...)
     /// * `board1` - true if board1, false if board2
-    pub fn get_winner(&self, board1:bool) -> Winner {
+    pub fn get_winner(&self, _board1: bool) -> Winner {
         self.winner
     }
 
     ///Sets the pawn_in_last_turn field
-    /// 
+    ///
     /// only for testing, movemaker and deploy piece update this field
     /// # Arguments
     /// * `board1` - true if board1, else false
     /// * `x` - location of the pawn_in_last_turn, can be none
-    fn set_pawn_in_last_turn(&mut self, board1:bool, x:Option<(usize,usize)>) {
+    fn set_pawn_in_last_turn(&mut self, board1: bool, x: Option<(usize, usize)>) {
         match board1 {
             true => self.pawn_in_last_turn_b1 = x,
             false => self.pawn_in_last_turn_b2 = x,
@@ -273,61 +326,61 @@ impl ChessLogic {
     /// # Arguments
     /// * `board1` - true if board1, else false
     /// * `locs` - vector of legal moves
-    pub fn print_w_legal(&mut self,board1:bool,locs: &Vec<(usize,usize)>){
+    pub fn print_w_legal(&mut self, board1: bool, locs: &[(usize, usize)]) {
         //its highly probably that a piece will have 8-16 legal moves
         let mut vec = Vec::with_capacity(8);
-        for &(i,j) in locs.iter() {
-            if self.get_piece(board1,i,j)== Piece::E {
+        for &(i, j) in locs.iter() {
+            if self.get_piece(board1, i, j) == Piece::E {
                 //legal moves are encoded with 'L'
                 if board1 {
                     self.chess_board1.board[i][j] = Piece::L;
-                }else{
+                } else {
                     self.chess_board2.board[i][j] = Piece::L;
                 }
-            }else{
-                vec.push((i,j,self.get_piece(board1,i,j)));
+            } else {
+                vec.push((i, j, self.get_piece(board1, i, j)));
                 //legal moves are encoded with 'L'
-                if board1{
+                if board1 {
                     self.chess_board1.board[i][j] = Piece::L;
-                }else{
+                } else {
                     self.chess_board2.board[i][j] = Piece::L;
                 }
-               
             }
         }
         //print the boards with legal moves as L
         if board1 {
             self.chess_board1.print_board();
-        }else{
+        } else {
             self.chess_board2.print_board();
         }
 
         //set back the legal moves 'L' with their old pieces
-        for &(i,j) in locs.iter() {
+        for &(i, j) in locs.iter() {
             // L -> E
-            if board1 {self.chess_board1.board[i][j] = Piece::E;} 
-            else {self.chess_board2.board[i][j] = Piece::E;}
+            if board1 {
+                self.chess_board1.board[i][j] = Piece::E;
+            } else {
+                self.chess_board2.board[i][j] = Piece::E;
+            }
         }
-        for &(i,j,t) in vec.iter() {
+        for &(i, j, t) in vec.iter() {
             // non L -> revert to old Piece
             if board1 {
                 self.chess_board1.board[i][j] = t;
-            }else{
+            } else {
                 self.chess_board2.board[i][j] = t;
             }
-          
         }
         println!("------------------------");
     }
 
-
     /// Default constructor for chesslogic
-    /// 
-    /// Initializes the game with initial positions 
+    ///
+    /// Initializes the game with initial positions
     pub fn new() -> ChessLogic {
-        ChessLogic{
-            chess_board1: ChessBoard::new(), 
-            chess_board2: ChessBoard::new(), 
+        let mut cl = ChessLogic {
+            chess_board1: ChessBoard::new(),
+            chess_board2: ChessBoard::new(),
             pawn_in_last_turn_b1: None,
             pawn_in_last_turn_b2: None,
             upgrade_to1: Piece::E,
@@ -338,22 +391,57 @@ impl ChessLogic {
             half_moves_last_capture2: 0,
             movectr1: 1,
             movectr2: 1,
-            board1_white_capture: [0;5],
-            board1_black_capture: [0;5],
-            board2_white_capture: [0;5],
-            board2_black_capture: [0;5],
+            board1_white_capture: [0; 5],
+            board1_black_capture: [0; 5],
+            board2_white_capture: [0; 5],
+            board2_black_capture: [0; 5],
             winner: Winner::N,
-        }
+            clock: None,
+            history: History::new(),
+            rules: Rules::new(),
+            lookahead: false,
+            hash1: 0,
+            hash2: 0,
+            events: Vec::new(),
+            observers: Vec::new(),
+            premoves: Default::default(),
+            premoving: false,
+            bitboards: Default::default(),
+        };
+        cl.rehash();
+        cl
+    }
+
+    ///Constructor for a game with house rules, both games are in the initial state
+    /// # Arguments
+    /// * `rules` - the rules of the game
+    pub fn with_rules(rules: Rules) -> ChessLogic {
+        let mut cl = ChessLogic::new();
+        cl.rules = rules;
+        cl
+    }
+
+    ///Returns the rules of the game
+    pub fn get_rules(&self) -> Rules {
+        self.rules
+    }
+
+    ///Sets the rules of the game, should be called before the first move
+    /// # Arguments
+    /// * `rules` - the rules of the game
+    pub fn set_rules(&mut self, rules: Rules) {
+        self.rules = rules;
     }
 
     /// Constructor to read from fen/resume a game
-    /// 
+    ///
     /// Resumes a game with given values, checks for nothing
+    #[allow(clippy::too_many_arguments)]
     pub fn resume(
-        _chess_board1: ChessBoard, 
-        _chess_board2: ChessBoard, 
-        _pawn_in_last_turn_b1: Option<(usize,usize)>, 
-        _pawn_in_last_turn_b2: Option<(usize,usize)>,
+        _chess_board1: ChessBoard,
+        _chess_board2: ChessBoard,
+        _pawn_in_last_turn_b1: Option<(usize, usize)>,
+        _pawn_in_last_turn_b2: Option<(usize, usize)>,
         _upgrade_to1: Piece,
         _upgrade_to2: Piece,
         _white_active_1: bool,
@@ -362,31 +450,44 @@ impl ChessLogic {
         _half_moves_last_capture2: usize,
         _movectr1: usize,
         _movectr2: usize,
-        _board1_white_capture: [u8;5],
-        _board1_black_capture: [u8;5],
-        _board2_white_capture: [u8;5],
-        _board2_black_capture: [u8;5],
-        _winner: Winner
-        ) -> ChessLogic {
-            ChessLogic{
-                chess_board1: _chess_board1, 
-                chess_board2: _chess_board2, 
-                pawn_in_last_turn_b1: _pawn_in_last_turn_b1,
-                pawn_in_last_turn_b2: _pawn_in_last_turn_b2,
-                upgrade_to1: _upgrade_to1,
-                upgrade_to2: _upgrade_to2,
-                white_active_1: _white_active_1,
-                white_active_2: _white_active_2,
-                half_moves_last_capture1: _half_moves_last_capture1,
-                half_moves_last_capture2: _half_moves_last_capture2,
-                movectr1: _movectr1,
-                movectr2: _movectr2,
-                board1_white_capture: _board1_white_capture ,
-                board1_black_capture: _board1_black_capture,
-                board2_white_capture: _board2_white_capture,
-                board2_black_capture: _board2_black_capture,
-                winner: _winner,
-            }
+        _board1_white_capture: [u8; 5],
+        _board1_black_capture: [u8; 5],
+        _board2_white_capture: [u8; 5],
+        _board2_black_capture: [u8; 5],
+        _winner: Winner,
+    ) -> ChessLogic {
+        let mut cl = ChessLogic {
+            chess_board1: _chess_board1,
+            chess_board2: _chess_board2,
+            pawn_in_last_turn_b1: _pawn_in_last_turn_b1,
+            pawn_in_last_turn_b2: _pawn_in_last_turn_b2,
+            upgrade_to1: _upgrade_to1,
+            upgrade_to2: _upgrade_to2,
+            white_active_1: _white_active_1,
+            white_active_2: _white_active_2,
+            half_moves_last_capture1: _half_moves_last_capture1,
+            half_moves_last_capture2: _half_moves_last_capture2,
+            movectr1: _movectr1,
+            movectr2: _movectr2,
+            board1_white_capture: _board1_white_capture,
+            board1_black_capture: _board1_black_capture,
+            board2_white_capture: _board2_white_capture,
+            board2_black_capture: _board2_black_capture,
+            winner: _winner,
+            clock: None,
+            history: History::new(),
+            rules: Rules::new(),
+            lookahead: false,
+            hash1: 0,
+            hash2: 0,
+            events: Vec::new(),
+            observers: Vec::new(),
+            premoves: Default::default(),
+            premoving: false,
+            bitboards: Default::default(),
+        };
+        cl.rehash();
+        cl
     }
 
     ///Returns a vector of legal moves for the board, and for the location
@@ -394,454 +495,217 @@ impl ChessLogic {
     /// * `board1` - true if board1, else false
     /// * `old_i` - row index of the piece
     /// * `old_j` - col index of the piece
-    pub fn get_legal_moves(&mut self,board1:bool, old_i:usize, old_j:usize)
-    -> Vec<(usize,usize)>
-    {
-        match self.get_piece(board1,old_i,old_j) {
-            Piece::P  => {
-                match (old_i,old_j) {
-                    //unmoved (insert double move too -- Double move)
-                    (6,_) =>  {
-                        //max 4 moves
-                        let mut vec = Vec::with_capacity(2);
-                        if self.is_empty(board1,old_i-1,old_j) {
-                            vec.push((old_i-1,old_j));
-                            // -2 only if -1 is empty
-                            if self.is_empty(board1,old_i-2,old_j) {
-                                vec.push((old_i-2,old_j));
-                            }
-                        }
-                        //enemy piece on -1,-1
-                        if old_j > 0 && self.is_enemy(board1,true,old_i-1,old_j-1) {
-                            vec.push((old_i-1,old_j-1));
-                        }
-                        //enemy piece on -1,+1
-                        if old_j < 7 && self.is_enemy(board1,true,old_i-1,old_j+1) {
-                            vec.push((old_i-1,old_j+1));
-                        }
-                        vec
-                    },
-                    //6th lane with en passant
-                    (3,_) => {
-                        //max 5 moves
-                        let mut vec = Vec::with_capacity(2);
-                        if self.is_empty(board1,old_i-1,old_j) {
-                            vec.push((old_i-1,old_j));
-                        }
-                        if old_j > 0 && self.is_enemy(board1,true,old_i-1,old_j-1) {
-                            vec.push((old_i-1,old_j-1));
-                        }
-                        if old_j < 7 && self.is_enemy(board1,true,old_i-1,old_j+1) {
-                            vec.push((old_i-1,old_j+1));
-                        }
-                        //en passant from left
-                        if old_j > 0 && self.get_piece(board1,old_i,old_j-1) == Piece::p {
-                            match self.get_pawn_in_last_turn(board1) {
-                                Some((a,b)) => vec.push((a-1,b)),
-                                _ => (),
-                            }
-                        }
-                        //en passant from right
-                        if old_j < 7 && self.get_piece(board1,old_i,old_j+1) == Piece::p {
-                            match self.get_pawn_in_last_turn(board1) {
-                                Some((a,b)) => vec.push((a-1,b)),
-                                _ => (),
-                            }
-                        }
-                        vec
-
-
-                    },
-                    //there can be no white pawns on line
-                    (0,_) => {
-                       let mut vec =  Vec::new();
-                       println!("This indicates pawn upgrade malfunction");
-                       vec
-                    },
-                    //no special moves
-                    (_,_) => {
-                        //max 4 moves
-                        let mut vec = Vec::with_capacity(2);
-                        if self.is_empty(board1,old_i-1,old_j) {
-                            vec.push((old_i-1,old_j));
-                        }
-                        if old_j > 0 && self.is_enemy(board1,true,old_i-1,old_j-1) {
-                            vec.push((old_i-1,old_j-1));
-                        }
-                        if old_j < 7 && self.is_enemy(board1,true,old_i-1,old_j+1) {
-                            vec.push((old_i-1,old_j+1));
-                        }
-                        vec
-                    },
-                }
-            },
-            Piece::p  => {
-                //same for the black
-                match (old_i,old_j) {
-                    //double moves
-                    (1,_) =>  {
-                        //max 4 moves
-                        let mut vec = Vec::with_capacity(2);
-                        //W double move
-                        if self.is_empty(board1,old_i+1,old_j) {
-                            vec.push((old_i+1,old_j));
-                            if self.is_empty(board1,old_i+2,old_j) {
-                                vec.push((old_i+2,old_j));
-                            }
-                        }
-                        //enemy left above
-                        if old_j > 0 && self.is_enemy(board1,false,old_i+1,old_j-1) {
-                            vec.push((old_i+1,old_j-1));
-                        }
-                        //enemy right above
-                        if old_j < 7 && self.is_enemy(board1,false,old_i+1,old_j+1) {
-                            vec.push((old_i+1,old_j+1));
-                        }
-                        vec
-                    },
-                    //4th line with enpassant 
-                    (4,_) => {
-                            //max 5 moves
-                            let mut vec = Vec::with_capacity(2);
-                            //front empty
-                            if self.is_empty(board1,old_i+1,old_j) {
-                                vec.push((old_i+1,old_j));
-                            }
-                            //enemy left above
-                            if old_j > 0 && self.is_enemy(board1,true,old_i+1,old_j-1) {
-                                vec.push((old_i+1,old_j-1));
-                            }
-                            //enemy right above
-                            if old_j < 7 && self.is_enemy(board1,true,old_i+1,old_j+1) {
-                                vec.push((old_i+1,old_j+1));
-                            }
-                            //en passant, enemy left
-                            if old_j > 0 && self.get_piece(board1,old_i,old_j-1) == Piece::P {
-                                match self.get_pawn_in_last_turn(board1){
-                                    Some((a,b)) => vec.push((a+1,b)),
-                                    _ => (),
-                                }
-                            }
-                            //en passant, enemy right
-                            if old_j < 7 && self.get_piece(board1,old_i,old_j+1) == Piece::P {
-                                match self.get_pawn_in_last_turn(board1) {
-                                    Some((a,b)) => vec.push((a+1,b)),
-                                    _ => (),
-                                }
-                            }
-                            vec
-    
-                    },
-                    //no pawn can be at the 7th line, promotion!
-                    (7,_) => {
-                        let mut vec = Vec::new();
-                        println!("This indicates pawn upgrade malfunction");
-                        vec
-                    },  
-                    //no special moves
-                    (_,_) => {
-                        let mut vec = Vec::new();
-                        if self.is_empty(board1,old_i+1,old_j) {
-                            vec.push((old_i+1,old_j));
-                        }
-                        if old_j > 0 && self.is_enemy(board1,false,old_i+1,old_j-1) {
-                            vec.push((old_i+1,old_j-1));
-                        }
-                        if old_j < 7 && self.is_enemy(board1,false,old_i+1,old_j+1) {
-                            vec.push((old_i+1,old_j+1));
-                        }
-                        vec
-                    },
-                }
-            },
-            Piece::R | Piece::r | Piece::Ur | Piece::UR => self.cross_mov(board1,old_i,old_j),
-            Piece::B | Piece::b | Piece::UB | Piece::Ub => self.x_mov(board1,old_i,old_j),
-            Piece::Q | Piece::q | Piece::UQ | Piece::Uq => 
-            { let mut vec = self.cross_mov(board1,old_i,old_j);
-              vec.append(&mut self.x_mov(board1,old_i,old_j));
-              vec },
-            Piece::N | Piece::n |Piece::UN | Piece::Un=> self.horse_jump(board1,old_i,old_j),
-            Piece::K | Piece::k => self.king_move(board1,old_i,old_j),
-            //should not come to here
-             _ => {println!("This indicates a bug!"); Vec::new()},
-        }
+    pub fn get_legal_moves(&self, board1: bool, old_i: usize, old_j: usize) -> Vec<(usize, usize)> {
+        let mut vec = Vec::new();
+        self.moves_with(&self.bitboards(board1), board1, old_i, old_j, &mut vec);
+        vec
     }
 
-    ///Returns if the location on the given board is empty
+    ///Returns the bitboards of a board
     /// # Arguments
     /// * `board1` - true if board1, else false
-    /// * `i` - row index
-    /// * `j` - col index
-    fn is_empty(&self, board1:bool, i:usize, j:usize) -> bool {
-        if board1 {
-            match self.chess_board1.board[i][j] {
-                Piece::E => true, 
-                _ => false,
-            }
-        }else{
-            match self.chess_board2.board[i][j] {
-                Piece::E => true, 
-                _ => false,
+    pub fn bitboards(&self, board1: bool) -> Bitboards {
+        let cell = &self.bitboards[if board1 { 0 } else { 1 }];
+        match cell.get() {
+            Some(bb) => bb,
+            None => {
+                let bb = Bitboards::new(self.get_board_n(board1));
+                cell.set(Some(bb));
+                bb
             }
         }
     }
 
-    ///Returns if the location on the given board is an enemy piece (needs to know if the caller is white)
-    /// # Arguments 
+    ///Creates the bitboards of a board again from its array at their next use
+    /// # Arguments
     /// * `board1` - true if board1, else false
-    /// * `white` - true if white, else false
-    /// * `i` - row index of the other piece
-    /// * `j` - col index of the other piece
-    /// E and L are never the enemies
-    fn is_enemy(&self, board1:bool,white:bool, i:usize, j:usize) -> bool {
-            if white {
-                //true for black, false for white
-                match  self.get_board_n(board1).board[i][j] {
-                    Piece::E => false,
-                    Piece::L => false,
-                    Piece::P  => false,
-                    Piece::R | Piece::UR => false,
-                    Piece::N | Piece::UN => false,
-                    Piece::B | Piece::UB => false,
-                    Piece::Q | Piece::UQ => false,
-                    Piece::K => false,
-                    _ => true,
-    
-                }
-            }else {
-                //true for white, false for black
-                match self.get_board_n(board1).board[i][j] {
-                    Piece::E => false,
-                    Piece::L => false,
-                    Piece::p  => false,
-                    Piece::r | Piece::Ur => false,
-                    Piece::n | Piece::Un => false,
-                    Piece::b | Piece::Ub => false,
-                    Piece::q | Piece::Uq => false,
-                    Piece::k => false,
-                    _ => true,
-    
-                }
-            }
-       
+    ///
+    /// Needed after the array of chess_board1 or chess_board2 was changed directly, get_board calls it.
+    pub fn reset_bitboards(&mut self, board1: bool) {
+        self.bitboards[if board1 { 0 } else { 1 }].set(None);
     }
 
-    ///Return a bool that is true if the given piece is white, else false
+    ///Updates the bitboards of a board for squares whose pieces have changed in the array
     /// # Arguments
-    /// * `white` - true if white, else false
-    /// * `i` - row index of the other piece
-    /// * `j` - col index of the other piece
-    fn is_white(&self, board1:bool, i:usize, j:usize) -> bool {
-            match self.get_board_n(board1).board[i][j] {
-                Piece::E => false,
-                Piece::L => false,
-                Piece::p  => false,
-                Piece::r | Piece::Ur => false,
-                Piece::n | Piece::Un => false,
-                Piece::b | Piece::Ub => false,
-                Piece::q | Piece::Uq => false,
-                Piece::k  => false,
-                _ => true,
-            }
-    }
-
-    ///Gets legal moves on a line
-    /// # Argumnets
-    /// * `board1` - true if board1 else false
-    /// * `i` - row index of the piece
-    /// * `j` - col index of the piece
-    fn horizontal_mov(&self,board1:bool, i:usize, j:usize) -> Vec<(usize,usize)> {
-        let mut vec = Vec::with_capacity(4);
-        let mut jc = j;
-        while jc > 0 {
-            jc -= 1;
-            if self.is_empty(board1,i,jc) {
-                vec.push((i,jc));
-            }else {
-                if self.is_enemy(board1,self.is_white(board1,i,j),i,jc) {
-                    vec.push((i,jc));
-                }
-                break;
-            }
-        }
-        jc = j;
-        while jc < 7 {
-            jc += 1;
-            if self.is_empty(board1,i,jc) {
-                vec.push((i,jc));
-            }else {
-                if self.is_enemy(board1,self.is_white(board1,i,j),i,jc) {
-                    vec.push((i,jc));
-                }
-                break;
+    /// * `board1` - true if board1, else false
+    /// * `squares` - the changed squares as (row,col)
+    fn update_bitboards(&mut self, board1: bool, squares: &[(usize, usize)]) {
+        let (b, cell) = if board1 {
+            (&self.chess_board1, &mut self.bitboards[0])
+        } else {
+            (&self.chess_board2, &mut self.bitboards[1])
+        };
+        if let Some(bb) = cell.get_mut() {
+            for &(i, j) in squares.iter() {
+                bb.set(i, j, b.board[i][j]);
             }
         }
-        vec
     }
 
-    ///Gets legal moves on a column, horizontal_move but jc is changed with ic
+    ///Same as get_legal_moves with the bitboards of the board, so they are only created once for all pieces
     /// # Arguments
-    /// * `board1` - true if board1 else false
+    /// * `bb` - the bitboards of the board
+    /// * `board1` - true if board1, else false
     /// * `i` - row index of the piece
     /// * `j` - col index of the piece
-    fn vertical_mov(&self,board1:bool, i:usize, j:usize)  -> Vec<(usize,usize)>{
-        let mut vec = Vec::with_capacity(4);
-        let mut ic = i;
-        while ic > 0 {
-            ic -= 1;
-            if self.is_empty(board1,ic,j) {
-                vec.push((ic,j));
-            }else {
-                if self.is_enemy(board1,self.is_white(board1,i,j),ic,j) {
-                    vec.push((ic,j));
+    ///
+    /// The moves of a pawn are in the order: forward, double step, captures and en passant, like get_legal_moves always returned them.
+    fn moves_with(&self, bb: &Bitboards, board1: bool, i: usize, j: usize, vec: &mut Vec<(usize, usize)>) {
+        vec.clear();
+        let (k, white) = match bitboard::kind(self.get_piece(board1, i, j)) {
+            Some(x) => x,
+            None => return,
+        };
+        match k {
+            Kind::Pawn => {
+                //a pawn on the last rank would have been promoted
+                if (white && i == 0) || (!white && i == 7) {
+                    return;
+                }
+                let (front, start, ep_row) = if white { (i - 1, 6, 3) } else { (i + 1, 1, 4) };
+                if bb.occupied & bitboard::bit(front, j) == 0 {
+                    vec.push((front, j));
+                    let double = if white { front.wrapping_sub(1) } else { front + 1 };
+                    if i == start && bb.occupied & bitboard::bit(double, j) == 0 {
+                        vec.push((double, j));
+                    }
+                }
+                let captures = bb.targets(Kind::Pawn, white, i, j);
+                for c in [j.wrapping_sub(1), j + 1].iter() {
+                    if *c < 8 && captures & bitboard::bit(front, *c) != 0 {
+                        vec.push((front, *c));
+                    }
+                }
+                if i == ep_row {
+                    for c in [j.wrapping_sub(1), j + 1].iter() {
+                        if *c < 8 && self.en_passant_from(board1, white, i, *c) {
+                            vec.push((front, *c));
+                        }
+                    }
                 }
-                break;
             }
-        }
-        ic = i;
-        while ic < 7 {
-            ic += 1;
-            if self.is_empty(board1,ic,j) {
-                vec.push((ic,j));
-            }else {
-                if self.is_enemy(board1,self.is_white(board1,i,j),ic,j) {
-                    vec.push((ic,j));
+            Kind::King => {
+                vec.extend(bitboard::squares(bb.targets(Kind::King, white, i, j)));
+                let row = if white { 7 } else { 0 };
+                let b = self.get_board_n(board1);
+                let rook = if white { Piece::R } else { Piece::r };
+                let (k_moved, rook_k_moved, rook_q_moved) = if white {
+                    (b.white_k_moved, b.white_rook_k_moved, b.white_rook_q_moved)
+                } else {
+                    (b.black_k_moved, b.black_rook_k_moved, b.black_rook_q_moved)
+                };
+                let free = |cols: &[usize]| cols.iter().all(|c| bb.occupied & bitboard::bit(row, *c) == 0);
+                let safe = |cols: &[usize]| cols.iter().all(|c| !self.is_attacked(board1, white, row, *c));
+                if !k_moved && !rook_k_moved && b.board[row][7] == rook && free(&[5, 6]) && safe(&[4, 5, 6]) {
+                    vec.push((row, 6));
+                }
+                if !k_moved && !rook_q_moved && b.board[row][0] == rook && free(&[1, 2, 3]) && safe(&[2, 3, 4]) {
+                    vec.push((row, 2));
                 }
-                break;
             }
+            _ => vec.extend(bitboard::squares(bb.targets(k, white, i, j))),
         }
-        vec
     }
 
-    ///Makes a cross with vertical and horizontal moves 
+    ///Returns true if a pawn next to the pawn of a player can be captured en passant
     /// # Arguments
-    /// * `board1` - true if board1 else false
-    /// * `i` - row index of the piece
-    /// * `j` - col index of the piece
-    fn cross_mov(&self,board1:bool, i:usize, j:usize) -> Vec<(usize,usize)> {
-        let mut vec = self.vertical_mov(board1,i,j);
-        vec.append(&mut self.horizontal_mov(board1,i,j));
-        vec
+    /// * `board1` - true if board1, else false
+    /// * `white` - the colour of the capturing pawn
+    /// * `i` - row index of the pawn that can be captured
+    /// * `j` - col index of the pawn that can be captured
+    ///
+    /// Only the pawn that moved last can be captured and the square behind it has to be empty.
+    fn en_passant_from(&self, board1: bool, white: bool, i: usize, j: usize) -> bool {
+        let enemy = if white { Piece::p } else { Piece::P };
+        let behind = if white { i - 1 } else { i + 1 };
+        self.get_piece(board1, i, j) == enemy
+            && self.get_pawn_in_last_turn(board1) == Some((i, j))
+            && self.is_empty(board1, behind, j)
     }
 
-    ///Iterates for [+n,-n] ; [+n,-n] combinations for 1..7 and gets legal moves
+    ///Returns every action the given color can play on a board: moves, castling, en passant,
+    ///each possible promotion and every deploy from the pool
     /// # Arguments
-    /// * `board1` - true if board1 else false
-    /// * `i` - row index of the piece
-    /// * `j` - col index of the piece
-    fn x_mov(&self,board1:bool, i:usize, j:usize)  -> Vec<(usize,usize)>{
-        let mut vec = Vec::with_capacity(4);
-        let mut ic = i;
-        let mut jc = j;
-        let w = self.is_white(board1,i,j);
-
-        //lower right
-        while ic < 7 && jc < 7 {
-            ic+=1;
-            jc+=1;
-            if self.is_empty(board1,ic,jc) {
-                vec.push((ic,jc));
-            }else{
-                if self.is_enemy(board1,w,ic,jc){
-                    vec.push((ic,jc));
-                }
-                break;
-            }
-        }
-        //upper right
-        ic=i;
-        jc=j;
-        while ic > 0 && jc < 7 {
-            ic-=1;
-            jc+=1;
-            if self.is_empty(board1,ic,jc) {
-                vec.push((ic,jc));
-            }else{
-                if self.is_enemy(board1,w,ic,jc){
-                    vec.push((ic,jc));
-                }
-                break;
-            }
-        }
-        //upper left 
-        ic=i;
-        jc=j;
-        while ic > 0 && jc > 0 {
-            ic-=1;
-            jc-=1;
-            if self.is_empty(board1,ic,jc) {
-                vec.push((ic,jc));
-            }else{
-                if self.is_enemy(board1,w,ic,jc){
-                    vec.push((ic,jc));
-                }
-                break;
-            }
-        }
-        //lower left
-        ic=i;
-        jc=j;
-        while ic < 7 && jc > 0 {
-            ic+=1;
-            jc-=1;
-            if self.is_empty(board1,ic,jc) {
-                vec.push((ic,jc));
-            }else{
-                if self.is_enemy(board1,w,ic,jc){
-                    vec.push((ic,jc));
-                }
-                break;
-            }
+    /// * `seat` - the board and the colour of the player
+    ///
+    /// The actions are generated even if it is not the turn of the color, e.g. for premoves.
+    /// Pawns are never deployed on the last rank, the same restriction as in deploy_piece.
+    /// Without drop_mate a deploy that checkmates is left out, the same rule as in deploy_piece.
+    pub fn get_all_legal_actions(&self, seat: Seat) -> Vec<Action> {
+        let mut vec = self.get_actions(seat);
+        if self.rules.termination == Termination::Checkmate && !self.lookahead {
+            vec.retain(|a| self.keeps_king_safe(seat, *a));
+        }
+        if !self.rules.drop_mate && !self.lookahead {
+            vec.retain(|a| !self.drop_mates(seat, *a));
         }
         vec
     }
 
-    ///Get possible horse moves, combinations of [+2,-2] ; [-1,+1] for all of the valid locations
+    ///Same as get_all_legal_actions but an action can leave the own king in check
     /// # Arguments
-    /// * `board1` - true if board1 else false
-    /// * `i` - row index of the piece
-    /// * `j` - col index of the piece
-    fn horse_jump(&self, board1:bool, i:usize, j:usize) -> Vec<(usize,usize)> {
-        //all possible combinations of +2, -2, +1, -1 for each square
-        let twoway = [-2,2];
-        let oneway = [1,-1];
-        let mut vec = Vec::with_capacity(2);
-        let w = self.is_white(board1,i,j);
-
-        //first -2,2 then 1,-1
-        for i_off in twoway.iter() {
-            for j_off in oneway.iter() {
-                let mut i_signed = i as i32;
-                let mut j_signed = j as i32;
-                i_signed += i_off;
-                j_signed += j_off;
-                //check if the indices are valid
-                if i_signed >= 0 && i_signed <= 7 && j_signed >= 0 && j_signed <= 7 {
-                    //cast back to usize
-                    let itmp = i_signed as usize;
-                    let jtmp = j_signed as usize;
-                    
-                    if self.is_empty(board1,itmp,jtmp) || 
-                    self.is_enemy(board1,w,itmp,jtmp) {
-                        vec.push((itmp,jtmp));
+    /// * `seat` - the board and the colour of the player
+    fn get_actions(&self, seat: Seat) -> Vec<Action> {
+        let board = seat.board;
+        let board1 = board.is_board1();
+        let white = seat.color.is_white();
+        let mut vec = Vec::new();
+        let bb = self.bitboards(board1);
+        let mut moves = Vec::new();
+        for (i_old, j_old) in bitboard::squares(bb.own(white)) {
+            let piece = self.get_piece(board1, i_old, j_old);
+            let from = Square::new(i_old, j_old).unwrap();
+            self.moves_with(&bb, board1, i_old, j_old, &mut moves);
+            for &(i, j) in moves.iter() {
+                let to = Square::new(i, j).unwrap();
+                if (piece == Piece::P && i == 0) || (piece == Piece::p && i == 7) {
+                    for prom in [Piece::Q, Piece::R, Piece::B, Piece::N].iter() {
+                        let promotion = self.pool_piece(self.box_index(*prom).unwrap(), white);
+                        vec.push(Action::Move {
+                            board,
+                            mv: Move::with_promotion(from, to, promotion),
+                        });
                     }
-                   
+                } else {
+                    vec.push(Action::Move {
+                        board,
+                        mv: Move::new(from, to),
+                    });
                 }
             }
         }
-        //first 1,-1 then -2,2
-        for i_off in oneway.iter() {
-            for j_off in twoway.iter() {
-                let mut i_signed = i as i32;
-                let mut j_signed = j as i32;
-                i_signed += i_off;
-                j_signed += j_off;
-                if i_signed >= 0 && i_signed <= 7 && j_signed >= 0 && j_signed <= 7 {
-                    let itmp = i_signed as usize;
-                    let jtmp = j_signed as usize;
-                    if self.is_empty(board1,itmp,jtmp) || 
-                    self.is_enemy(board1,w,itmp,jtmp) {
-                        vec.push((itmp,jtmp));
+        vec.append(&mut self.get_legal_drops(seat));
+        vec
+    }
+
+    ///Returns every deploy the given color can play on a board with its current pool
+    /// # Arguments
+    /// * `seat` - the board and the colour of the player
+    pub fn get_legal_drops(&self, seat: Seat) -> Vec<Action> {
+        let board = seat.board;
+        let board1 = board.is_board1();
+        let white = seat.color.is_white();
+        let mut vec = Vec::new();
+        for ind in 0..5 {
+            if self.get_captured_piece(board1, white, ind) == 0 {
+                continue;
+            }
+            let piece = self.pool_piece(ind, white);
+            for i in 0..8 {
+                //pawns cannot be deployed on the last rank
+                if (piece == Piece::P && i == 0) || (piece == Piece::p && i == 7) {
+                    continue;
+                }
+                if !self.rules.pawn_drop_first_rank && ((piece == Piece::P && i == 7) || (piece == Piece::p && i == 0))
+                {
+                    continue;
+                }
+                for j in 0..8 {
+                    if self.is_empty(board1, i, j) {
+                        vec.push(Action::Drop {
+                            board,
+                            drop: Drop::new(piece, Square::new(i, j).unwrap()),
+                        });
                     }
                 }
             }
@@ -849,12 +713,38 @@ impl ChessLogic {
         vec
     }
 
+    ///Returns the piece for an index of the captured pieces array, the inverse of box_index
+    /// # Arguments
+    /// * `ind` - index in the pool, the order is P-R-N-B-Q
+    /// * `white` - true if the piece should be white
+    pub fn pool_piece(&self, ind: usize, white: bool) -> Piece {
+        board::pool_piece(ind, white)
+    }
+
+    ///Returns if the location on the given board is empty
+    /// # Arguments
+    /// * `board1` - true if board1, else false
+    /// * `i` - row index
+    /// * `j` - col index
+    fn is_empty(&self, board1: bool, i: usize, j: usize) -> bool {
+        self.get_board_n(board1).board[i][j] == Piece::E
+    }
+
+    ///Return a bool that is true if the given piece is white, else false
+    /// # Arguments
+    /// * `white` - true if white, else false
+    /// * `i` - row index of the other piece
+    /// * `j` - col index of the other piece
+    fn is_white(&self, board1: bool, i: usize, j: usize) -> bool {
+        self.get_board_n(board1).board[i][j].is_white()
+    }
+
     ///Returns the piece in given board and location
     /// # Arguments
     /// * `board1` - true if board1 else false
     /// * `i` - row index of the piece
     /// * `j` - col index of the piece
-    pub fn get_piece(&self, chessboard1:bool, i:usize, j:usize) -> Piece {
+    pub fn get_piece(&self, chessboard1: bool, i: usize, j: usize) -> Piece {
         match chessboard1 {
             true => self.chess_board1.board[i][j],
             false => self.chess_board2.board[i][j],
@@ -866,17 +756,52 @@ impl ChessLogic {
     /// * `board1` - true if board1 else false
     /// * `iswhite` - true if the piece is to be converted to white else false
     /// * `piece` - input piece
-    pub fn get_piece_w_en(&self, iswhite:bool, piece:Piece) -> Piece {
+    pub fn get_piece_w_en(&self, iswhite: bool, piece: Piece) -> Piece {
         match piece {
-            Piece::p | Piece::P => if iswhite {Piece::p} else {Piece::P} ,
-            Piece::r | Piece::R | Piece::Ur | Piece::UR => if iswhite {Piece::r} else {Piece::R} ,
-            Piece::b | Piece::B | Piece::UB | Piece::Ub => if iswhite {Piece::b} else {Piece::B} ,
-            Piece::k | Piece::K => if iswhite {Piece::k} else {Piece::K} ,
-            Piece::q | Piece::Q | Piece::Uq | Piece::UQ => if iswhite {Piece::q} else {Piece::Q} ,
-            Piece::n | Piece::N | Piece::Un | Piece::UN => if iswhite {Piece::n} else {Piece::N} ,
+            Piece::p | Piece::P => {
+                if iswhite {
+                    Piece::p
+                } else {
+                    Piece::P
+                }
+            }
+            Piece::r | Piece::R | Piece::Ur | Piece::UR => {
+                if iswhite {
+                    Piece::r
+                } else {
+                    Piece::R
+                }
+            }
+            Piece::b | Piece::B | Piece::UB | Piece::Ub => {
+                if iswhite {
+                    Piece::b
+                } else {
+                    Piece::B
+                }
+            }
+            Piece::k | Piece::K => {
+                if iswhite {
+                    Piece::k
+                } else {
+                    Piece::K
+                }
+            }
+            Piece::q | Piece::Q | Piece::Uq | Piece::UQ => {
+                if iswhite {
+                    Piece::q
+                } else {
+                    Piece::Q
+                }
+            }
+            Piece::n | Piece::N | Piece::Un | Piece::UN => {
+                if iswhite {
+                    Piece::n
+                } else {
+                    Piece::N
+                }
+            }
             Piece::L => Piece::L,
             Piece::E => Piece::E,
-
         }
     }
 
@@ -885,456 +810,115 @@ impl ChessLogic {
     /// * `board1` - true if board1 else false
     /// * `iswhite` - true if the piece is to be converted to white else false
     /// * `piece` - input piece
-    pub fn get_piece_w_upgrade_en(&self, iswhite:bool, piece:Piece) -> Piece {
+    pub fn get_piece_w_upgrade_en(&self, iswhite: bool, piece: Piece) -> Piece {
         match piece {
-            Piece::p | Piece::P => if iswhite {Piece::p} else {Piece::P} ,
-            Piece::r | Piece::R | Piece::Ur | Piece::UR => if iswhite {Piece::Ur} else {Piece::UR} ,
-            Piece::b | Piece::B | Piece::UB | Piece::Ub => if iswhite {Piece::Ub} else {Piece::UB} ,
-            Piece::k | Piece::K => if iswhite {Piece::k} else {Piece::K} ,
-            Piece::q | Piece::Q | Piece::Uq | Piece::UQ => if iswhite {Piece::Uq} else {Piece::UQ} ,
-            Piece::n | Piece::N | Piece::Un | Piece::UN => if iswhite {Piece::Un} else {Piece::UN} ,
-            Piece::L => Piece::L,
-            Piece::E => Piece::E,
-
-        }
-    }
-
-    ///Checks wether the given location is under attack
-    /// # Arguments
-    /// * `board1` - true if board1 else false
-    /// * `iswhite` - true if the piece if checking for white else false
-    /// * `i` - row index
-    /// * `j` - col index
-    pub fn is_attacked(&mut self, board1:bool, iswhite:bool, i:usize,j:usize) -> bool {
-        //remove yourself from board (you could be blocking other places)
-        //check for pawns
-        //check for rook|queen on vertical
-        //check for rook|queen on horizontal
-        //check for bishop on x
-        //check for horses
-        //check for enemy king 
-
-        let mut ic = (i as i32);
-        let mut jc = (j as i32);
-            //check for pawns
-            if iswhite {
-                if self.valid(ic-1,jc-1) {
-                    let a = (ic-1) as usize;
-                    let b = (jc-1) as usize;
-                        if self.get_board_n(board1).board[a][b]==Piece::p
-                        {
-                   
-                            return true
-                        }
-                }
-                if self.valid(ic-1,jc+1) {
-                    let a = (ic-1) as usize;
-                    let b = (jc+1) as usize;
-                        if self.get_board_n(board1).board[a][b]==Piece::p
-                        {
-                     
-                            return true
-                        }
-                }
-            }else{
-                ic = (i as i32);
-                jc = (j as i32);
-                if self.valid(ic+1,jc-1) && self.valid(ic,jc) {
-                    let a = (ic+1) as usize;
-                    let b = (jc-1) as usize;
-                        if self.get_board_n(board1).board[a][b]==Piece::P
-                        {
-                            println!("{},{}: is a piyon",a,b);
-                            return true
-                        }
-                }
-                if self.valid(ic+1,jc+1) && self.valid(ic,jc) {
-                    let a = (ic+1) as usize;
-                    let b = (jc+1) as usize;
-                        if self.get_board_n(board1).board[a][b]==Piece::P
-                        {
-                     
-                            return true
-                        }
+            Piece::p | Piece::P => {
+                if iswhite {
+                    Piece::p
+                } else {
+                    Piece::P
                 }
             }
-           
-            //check for horizontal line
-            jc = j as i32;
-            ic = i as i32;
-            let mut jx = jc+1;
-            while jx < 8 {
-                if self.check_for_piece(board1,self.get_piece_w_en(iswhite,Piece::r),ic,jx) || 
-                self.check_for_piece(board1,self.get_piece_w_en(iswhite,Piece::q),ic,jx) ||
-                self.check_for_piece(board1,self.get_piece_w_upgrade_en(iswhite,Piece::r),ic,jx) || 
-                self.check_for_piece(board1,self.get_piece_w_upgrade_en(iswhite,Piece::q),ic,jx){
-                    return true
-                }else if !self.check_for_piece(board1,Piece::E, ic,jx){
-                    //blocked
-                    break;
-                }
-                jx+=1;
-            }
-            jx = jc-1;
-            while jx >= 0{
-                if self.check_for_piece(board1,self.get_piece_w_en(iswhite,Piece::r),ic,jx) || 
-                self.check_for_piece(board1,self.get_piece_w_en(iswhite,Piece::q),ic,jx) ||
-                self.check_for_piece(board1,self.get_piece_w_upgrade_en(iswhite,Piece::r),ic,jx) || 
-                self.check_for_piece(board1,self.get_piece_w_upgrade_en(iswhite,Piece::q),ic,jx) {
-                    return true
-                }else if !self.check_for_piece(board1,Piece::E, ic,jx){
-                    //blocked
-                    break;
-                }
-                jx -=1;
-            }
-            //check for vertical
-            let mut ix = ic+1;
-            while ix <8 {
-                if self.check_for_piece(board1,self.get_piece_w_en(iswhite,Piece::r),ix,jc) || 
-                self.check_for_piece(board1,self.get_piece_w_en(iswhite,Piece::q),ix,jc) ||
-                self.check_for_piece(board1,self.get_piece_w_upgrade_en(iswhite,Piece::r),ix,jc) || 
-                self.check_for_piece(board1,self.get_piece_w_upgrade_en(iswhite,Piece::q),ix,jc)
-                {
-                    return true
-                }else if !self.check_for_piece(board1,Piece::E, ix,jc){
-                    //blocked 
-                    break;
-                }
-                ix+=1;
-            }
-            ix = ic-1;
-            while ix >=0 {
-                if self.check_for_piece(board1,self.get_piece_w_en(iswhite,Piece::r),ix,jc) || 
-                self.check_for_piece(board1,self.get_piece_w_en(iswhite,Piece::q),ix,jc) ||
-                self.check_for_piece(board1,self.get_piece_w_upgrade_en(iswhite,Piece::r),ix,jc) || 
-                self.check_for_piece(board1,self.get_piece_w_upgrade_en(iswhite,Piece::q),ix,jc)
-                {
-                    return true
-                }else if !self.check_for_piece(board1,Piece::E, ix,jc){
-                    break;
-                }
-                ix-=1;
-            }
-            //check for capraz
-            ic=i as i32;
-            jc=j as i32;
-            //lower right
-            while ic < 7 && jc < 7 {
-                ic+=1;
-                jc+=1;
-                if self.check_for_piece(board1,self.get_piece_w_en(iswhite,Piece::q),ic,jc) ||
-                self.check_for_piece(board1,self.get_piece_w_en(iswhite,Piece::b),ic,jc) ||
-                self.check_for_piece(board1,self.get_piece_w_upgrade_en(iswhite,Piece::q),ic,jc) ||
-                self.check_for_piece(board1,self.get_piece_w_upgrade_en(iswhite,Piece::b),ic,jc) {
-            
-                    return true
-                }else if !self.check_for_piece(board1,Piece::E, ic,jc){
-                    break;
-                }
-                
-            }
-            //upper right
-            ic=i as i32;
-            jc=j as i32;
-            while ic > 0 && jc < 7 {
-                ic-=1;
-                jc+=1;
-                if self.check_for_piece(board1,self.get_piece_w_en(iswhite,Piece::q),ic,jc) ||
-                self.check_for_piece(board1,self.get_piece_w_en(iswhite,Piece::b),ic,jc) ||
-                self.check_for_piece(board1,self.get_piece_w_upgrade_en(iswhite,Piece::q),ic,jc) ||
-                self.check_for_piece(board1,self.get_piece_w_upgrade_en(iswhite,Piece::b),ic,jc) {
-                    return true
-                }else if !self.check_for_piece(board1,Piece::E, ic,jc){
-                    break;
-                }
-                
-            }
-            //upper left 
-            ic=i as i32;
-            jc=j as i32;
-            while ic > 0 && jc > 0 {
-                ic-=1;
-                jc-=1;
-                if self.check_for_piece(board1,self.get_piece_w_en(iswhite,Piece::q),ic,jc) ||
-                self.check_for_piece(board1,self.get_piece_w_en(iswhite,Piece::b),ic,jc) ||
-                self.check_for_piece(board1,self.get_piece_w_upgrade_en(iswhite,Piece::q),ic,jc) ||
-                self.check_for_piece(board1,self.get_piece_w_upgrade_en(iswhite,Piece::b),ic,jc) {
-                    return true
-                }else if !self.check_for_piece(board1,Piece::E, ic,jc){
-                    break;
+            Piece::r | Piece::R | Piece::Ur | Piece::UR => {
+                if iswhite {
+                    Piece::Ur
+                } else {
+                    Piece::UR
                 }
-                
-            }
-            //lower left
-            ic=i as i32;
-            jc=j as i32;
-            while ic < 7 && jc > 0 {
-                ic+=1;
-                jc-=1;
-                if self.check_for_piece(board1,self.get_piece_w_en(iswhite,Piece::q),ic,jc) ||
-                self.check_for_piece(board1,self.get_piece_w_en(iswhite,Piece::b),ic,jc) ||
-                self.check_for_piece(board1,self.get_piece_w_upgrade_en(iswhite,Piece::q),ic,jc) ||
-                self.check_for_piece(board1,self.get_piece_w_upgrade_en(iswhite,Piece::b),ic,jc) {
-                    return true
-                }else if !self.check_for_piece(board1,Piece::E, ic,jc){
-                    break;
+            }
+            Piece::b | Piece::B | Piece::UB | Piece::Ub => {
+                if iswhite {
+                    Piece::Ub
+                } else {
+                    Piece::UB
                 }
             }
-            //check for horses
-            ic = i as i32;
-            jc = j as i32;
-            let a = [-2,2];
-            let b = [-1,1];
-            for i_off in a.iter() {
-                for j_off in b.iter() {
-                    if self.valid(ic+i_off,jc+j_off)  {
-                        if board1 {
-                            if self.chess_board1.board[(ic+i_off) as usize][(jc+j_off) as usize] == 
-                            self.get_piece_w_en(iswhite,Piece::n) ||
-                            self.chess_board1.board[(ic+i_off) as usize][(jc+j_off) as usize] == 
-                            self.get_piece_w_upgrade_en(iswhite,Piece::n){
-                                return true
-                            }
-                        }else{
-                            if self.chess_board2.board[(ic+i_off) as usize][(jc+j_off) as usize] == 
-                            self.get_piece_w_en(iswhite,Piece::n) ||
-                            self.chess_board1.board[(ic+i_off) as usize][(jc+j_off) as usize] == 
-                            self.get_piece_w_upgrade_en(iswhite,Piece::n){
-                                return true
-                            }
-                        }
-                    }
+            Piece::k | Piece::K => {
+                if iswhite {
+                    Piece::k
+                } else {
+                    Piece::K
                 }
             }
-            for j_off in a.iter() {
-                for i_off in b.iter() {
-                    if self.valid(ic+i_off,jc+j_off)  {
-                        if board1 {
-                            if self.chess_board1.board[(ic+i_off) as usize][(jc+j_off) as usize] == 
-                            self.get_piece_w_en(iswhite,Piece::n) || 
-                            self.chess_board1.board[(ic+i_off) as usize][(jc+j_off) as usize] == 
-                            self.get_piece_w_upgrade_en(iswhite,Piece::n){
-                                return true
-                            }
-                        }else{
-                            if self.chess_board2.board[(ic+i_off) as usize][(jc+j_off) as usize] == 
-                            self.get_piece_w_en(iswhite,Piece::n) ||
-                            self.chess_board2.board[(ic+i_off) as usize][(jc+j_off) as usize] == 
-                            self.get_piece_w_upgrade_en(iswhite,Piece::n) {
-                                return true
-                            }
-                        }
-                    }
+            Piece::q | Piece::Q | Piece::Uq | Piece::UQ => {
+                if iswhite {
+                    Piece::Uq
+                } else {
+                    Piece::UQ
                 }
             }
-            //check for enemy king
-            let c = [-1,0,1];
-            let d = [-1,0,1];
-            for i_off in c.iter() {
-                for j_off in d.iter() {
-                    if self.valid(ic+i_off,jc+j_off) && !(*i_off==0 && *j_off==0) {
-                        if self.chess_board1.board[(ic+i_off) as usize][(jc+j_off) as usize] == 
-                        self.get_piece_w_en(iswhite,Piece::k) {
-                            return true
-                        }
-                    }
+            Piece::n | Piece::N | Piece::Un | Piece::UN => {
+                if iswhite {
+                    Piece::Un
+                } else {
+                    Piece::UN
                 }
             }
-        
-        //nothing attacks this place
-        false
+            Piece::L => Piece::L,
+            Piece::E => Piece::E,
+        }
     }
 
-    ///checks if the given indices are in the legal bounds
+    ///Checks wether the given location is under attack
     /// # Arguments
-    /// * `a` - row index
-    /// * `b` - col index
-    fn valid(&self, a: i32, b: i32) -> bool {
-        if a >= 0 && a <= 7 && b >= 0 && b<= 7 {
-            return true
-        }else {
-            return false
-        }
+    /// * `board1` - true if board1 else false
+    /// * `iswhite` - true if the piece if checking for white else false
+    /// * `i` - row index
+    /// * `j` - col index
+    pub fn is_attacked(&self, board1: bool, iswhite: bool, i: usize, j: usize) -> bool {
+        self.bitboards(board1).attacked(i, j, !iswhite)
     }
 
-    ///Checks if the given location has the given piece
+    ///checks if the given indices are in the legal bounds
     /// # Arguments
-    /// * `piece` - piece to check
     /// * `a` - row index
     /// * `b` - col index
-    fn check_for_piece(&self,board1:bool, piece:Piece, i : i32, j:i32) -> bool {
-        if !self.valid(i,j){
-            println!("The index is not valid, this idicates a bug");
-            return false
-        }else{
-                if self.get_board_n(board1).board[i as usize][j as usize]==piece
-                {
-                    return true
-                }else {
-                    return false
-                }
-        }
+    fn valid(&self, a: i32, b: i32) -> bool {
+        (0..=7).contains(&a) && (0..=7).contains(&b)
     }
 
     ///Empties the whole board
     /// # Arguments
     /// * `board1` - true if board1 else false
-    pub fn all_empty(&mut self,board1:bool) {
+    pub fn all_empty(&mut self, board1: bool) {
         for i in 0..8 {
             for j in 0..8 {
-                if board1{
+                if board1 {
                     self.chess_board1.board[i][j] = Piece::E;
-                }else{
+                } else {
                     self.chess_board2.board[i][j] = Piece::E;
                 }
-                
             }
         }
+        self.reset_bitboards(board1);
+        self.rehash();
     }
 
-  
     ///Sets the piece on the given location
     /// # Arguments
     /// * `board1` - true if board1 else false
     /// * `piece` - the piece to set
     /// * `i` - row index
     /// * `j` - col index
-    fn set_piece(&mut self,board1:bool,piece:Piece,i:usize,j:usize){
-        if board1{
+    fn set_piece(&mut self, board1: bool, piece: Piece, i: usize, j: usize) {
+        if board1 {
             self.chess_board1.board[i][j] = piece;
-        }else{
+        } else {
             self.chess_board2.board[i][j] = piece;
         }
+        self.update_bitboards(board1, &[(i, j)]);
+        self.rehash();
     }
 
-    ///Checks if the king can  
-    /// # Arguments
-    /// * `board1` - true if board1 else false
-    /// * `i` - row index
-    /// * `j` - col index
-    /// 
-    /// In Bughouse kind move to a location which is attacked
-    fn king_move(&mut self, board1:bool, i:usize,j:usize) -> Vec<(usize,usize)>{
-        let mut a = [-1,0,1];
-        let mut b = [-1,0,1];
-        let mut vec = Vec::with_capacity(2);
-        let ix = i as i32;
-        let jx = j as i32;
-        let mut piece = Piece::E;
-        let mut wayt = true;
-
-        //remove yourself for is_attacked check if needed
-        if board1 {
-            piece = self.chess_board1.board[i][j];
-            self.chess_board1.board[i][j] = Piece::E;
-        }else{
-            piece = self.chess_board2.board[i][j];
-            self.chess_board2.board[i][j] = Piece::E;
+    pub fn get_board(&mut self, board1: bool) -> &mut ChessBoard {
+        self.reset_bitboards(board1);
+        match board1 {
+            true => &mut self.chess_board1,
+            false => &mut self.chess_board2,
         }
+    }
 
-        //set white
-        if piece == Piece::K {
-            wayt = true;
-        }else{
-            wayt = false;
-        }
-
-        //check for all possible combinations
-        for i_off in a.iter(){
-            for j_off in b.iter(){
-                if self.valid(i_off+ix,j_off+jx) && !(*i_off==0 && *j_off==0){
-                    let mut ic = i_off+ix;
-                    let mut jc = j_off+jx;
-                    //if !self.is_attacked(board1,wayt,ic as usize,jc as usize)  
-                    //keep this in case we want legal moves only
-                    if true 
-                    {
-                        if board1 {
-                            if self.chess_board1.board[ic as usize][jc as usize] == Piece::E 
-                            || self.is_enemy(board1,wayt,ic as usize, jc as usize) {
-                                vec.push((ic as usize,jc as usize));
-                            }
-                        }else{
-                            if self.chess_board2.board[ic as usize][jc as usize] == Piece::E
-                            || self.is_enemy(board1,wayt,ic as usize, jc as usize) {
-                                vec.push((ic as usize,jc as usize));
-                            }
-                        }
-                    }
-                }
-
-            }
-        }
-
-        //set yourself back on the field -- needed only for is_attacked tho
-        if board1 {
-            self.chess_board1.board[i][j] = piece;
-        }else{
-            self.chess_board2.board[i][j] = piece;
-        }
-      
-        //check for castling
-            if wayt {
-                if !self.get_board(board1).white_k_moved 
-                && self.get_board(board1).board[7][7] == Piece::R 
-                && self.get_board(board1).board[7][6] == Piece::E
-                && self.get_board(board1).board[7][5] == Piece::E
-                && !self.is_attacked(board1,wayt,7,6) 
-                && !self.is_attacked(board1,wayt,7,5)
-                && !self.is_attacked(board1,wayt,7,4)
-                && !self.get_board(board1).white_rook_k_moved
-                {
-                    vec.push((7,6))
-                }
-                if !self.get_board(board1).white_k_moved 
-                && self.get_board(board1).board[7][0] == Piece::R 
-                && self.get_board(board1).board[7][1] == Piece::E
-                && self.get_board(board1).board[7][2] == Piece::E
-                && self.get_board(board1).board[7][3] == Piece::E
-                && !self.is_attacked(board1,wayt,7,3) 
-                && !self.is_attacked(board1,wayt,7,2)
-                && !self.is_attacked(board1,wayt,7,4)
-                && !self.is_attacked(board1,wayt,7,1)
-                && !self.get_board(board1).white_rook_q_moved {
-                    vec.push((7,2))
-                }
-            }else{
-                if !self.get_board(board1).black_k_moved 
-                && self.get_board(board1).board[0][7] == Piece::r 
-                && self.get_board(board1).board[0][6] == Piece::E
-                && self.get_board(board1).board[0][5] == Piece::E
-                && !self.is_attacked(board1,wayt,0,6) 
-                && !self.is_attacked(board1,wayt,0,5)
-                && !self.is_attacked(board1,wayt,0,4)
-                && !self.get_board(board1).black_rook_k_moved
-                {
-                    vec.push((0,6))
-                }
-                if !self.get_board(board1).black_k_moved 
-                && self.get_board(board1).board[0][0] == Piece::r 
-                && self.get_board(board1).board[0][1] == Piece::E
-                && self.get_board(board1).board[0][2] == Piece::E
-                && self.get_board(board1).board[0][3] == Piece::E
-                && !self.is_attacked(board1,wayt,0,3) 
-                && !self.is_attacked(board1,wayt,0,2)
-                && !self.is_attacked(board1,wayt,0,4)
-                && !self.is_attacked(board1,wayt,0,1)
-                && !self.get_board(board1).black_rook_q_moved {
-                    vec.push((0,2))
-                }
-            }
-        vec
-    }
-
-    pub fn get_board(&mut self, board1:bool )-> &mut ChessBoard{
-        match board1 {
-            true => &mut self.chess_board1,
-            false => &mut self.chess_board2,
-        }
-    }
-
-    pub fn get_board_n(&self, board1:bool )-> &ChessBoard{
+    pub fn get_board_n(&self, board1: bool) -> &ChessBoard {
         match board1 {
             true => &self.chess_board1,
             false => &self.chess_board2,
@@ -1348,769 +932,1731 @@ impl ChessLogic {
     /// * `old_j` - old col index
     /// * `i` - row index, where the piece wants to move
     /// * `j` - col index, same as above
+    ///
     ///in tandem you can move your pinned piece, then the enemy can capture your king
     ///checks if you can move from i_old, j_old to i,j
-    pub fn legality_check(&mut self, board1:bool, i_old:usize,j_old:usize,i:usize,j:usize) -> bool{
-        
-        match &self.get_board(board1).board[i_old][j_old] {
+    pub fn legality_check(&mut self, board1: bool, i_old: usize, j_old: usize, i: usize, j: usize) -> bool {
+        match &self.get_board_n(board1).board[i_old][j_old] {
             Piece::E | Piece::L => false,
-            _ => { 
-                let vec = self.get_legal_moves(board1,i_old,j_old);
-                for (a,b) in vec.iter() {
+            _ => {
+                let vec = self.get_legal_moves(board1, i_old, j_old);
+                for (a, b) in vec.iter() {
                     if *a == i && *b == j {
-                        return true
+                        return true;
                     }
                 }
-                return false
-            },
+                false
+            }
         }
     }
 
-    ///Deploys a piece on your field as a turn returns an Error if you cant deploy 
+    ///Deploys a piece on your field as a turn returns an Error if you cant deploy
     /// # Arguments
     /// * `board1` - true if to deploy on board1 else false
     /// * `white` - true if the piece to be deployed white else false
     /// * `p` - the piece to deploy
     /// * `i` - the row to deploy
     /// * `j` - the col to deploy
-    /// 
+    ///
     /// Deploys a piece at the location (i,j) if the location (i,j) is within bounds, empty and it is legal to deploy at index (i,j)
     /// For example a white pawn, P cannot be deployed to (0,_). It also checks if the deployable piece pool has
-    /// enough pieces of the type p (>=1 p:Piece).
+    /// enough pieces of the type of the piece.
     /// It updates: pools, winner, count of turns, count of half-turns since last capture.
     /// A king cannot be deployed but if the rook is deployed on the initial position it is possible to castle, it also updated by the deploy piece.
     /// A deploy cannot terminate the game, since you cannot capture the king with a deploy. The game terminates only when a king is captured, a plyer resigns, or stalemate occurs which is prob. never going to happen.
-    pub fn deploy_piece(&mut self,board1:bool,white:bool,p:Piece,i:usize,j:usize) -> Result<bool,MoveError> {
-        //deploy the piece only if it is legal to play
-        if self.winner!=Winner::N {
-            return Ok(true)
+    /// If the game is played with a clock, the flags are checked before the deploy and the clock is pressed after it.
+    pub fn deploy_piece(
+        &mut self,
+        board1: bool,
+        white: bool,
+        piece: Piece,
+        i: usize,
+        j: usize,
+    ) -> Result<bool, MoveError> {
+        self.play_deploy(board1, white, piece, i, j).map_err(|e| e.kind())
+    }
+
+    ///Same as deploy_piece but the error has the reason the deploy is rejected
+    fn play_deploy(
+        &mut self,
+        board1: bool,
+        white: bool,
+        piece: Piece,
+        i: usize,
+        j: usize,
+    ) -> Result<bool, ActionError> {
+        if self.winner == Winner::N {
+            self.check_flags();
+        }
+        let ind = self.validate_deploy(board1, white, piece, i, j)?;
+        if !self.lookahead {
+            self.check_deploy_rules(board1, white, piece, i, j)?;
+        }
+        let before = self.snapshot();
+        self.execute_deploy(board1, white, piece, ind, i, j);
+        self.update_hashes(&before);
+        self.after_action(board1);
+        let after = self.snapshot();
+        let drop = Drop::new(piece, Square::new(i, j).unwrap());
+        let remaining = self.get_remaining_time(board1, white);
+        let action = Action::Drop {
+            board: BoardId::from_board1(board1),
+            drop,
+        };
+        self.emit(events::action_events(&before, &after, &action));
+        let timestamp = self.clock.as_ref().map(|c| c.now());
+        self.history.push(action, before, after, remaining, timestamp);
+        self.run_premoves(board1);
+        Ok(true)
+    }
+
+    ///Checks if a deploy can be played without changing the game, returns the index of the piece in the pool
+    fn validate_deploy(
+        &self,
+        board1: bool,
+        white: bool,
+        piece: Piece,
+        i: usize,
+        j: usize,
+    ) -> Result<usize, ActionError> {
+        let err = |cause| ActionError::new(BoardId::from_board1(board1), None, Square::new(i, j), piece, cause);
+        if self.winner != Winner::N {
+            return Err(err(RejectCause::GameOver(self.winner)));
         }
 
         //check for i j bounds
-        if !self.valid(i as i32,j as i32) {
-            return Err(MoveError::NotLegal)
+        if !self.valid(i as i32, j as i32) {
+            return Err(err(RejectCause::OutOfBounds));
         }
 
         //check if the location is empty
+        if !self.is_empty(board1, i, j) {
+            return Err(err(RejectCause::OccupiedSquare));
+        }
+
+        if self.get_white_active(board1) != white {
+            return Err(err(RejectCause::NotTurn));
+        }
+
+        //only the own unpromoted pieces are in the pool
+        if piece.is_white() != white || piece.is_promoted() {
+            return Err(err(RejectCause::NotDeployable));
+        }
+        let ind = match self.box_index(piece) {
+            Some(ind) => ind,
+            None => return Err(err(RejectCause::NotDeployable)),
+        };
+
+        if self.get_captured_piece(board1, white, ind) == 0 {
+            return Err(err(RejectCause::EmptyPool));
+        }
+
+        if (piece == Piece::P && i == 0) || (piece == Piece::p && i == 7) {
+            return Err(err(RejectCause::PawnDropLastRank));
+        }
+        Ok(ind)
+    }
+
+    ///Applies a deploy that has been checked by validate_deploy
+    /// # Arguments
+    /// * `ind` - the index of the piece in the pool
+    fn execute_deploy(&mut self, board1: bool, white: bool, piece: Piece, ind: usize, i: usize, j: usize) {
+        //depending on the rules a deploy resets the counter of the N-move rule like a pawn move
+        let reset = match self.rules.drop_reset {
+            DropReset::Never => false,
+            DropReset::Pawns => piece == Piece::P || piece == Piece::p,
+            DropReset::Always => true,
+        };
+        let counter = if board1 {
+            &mut self.half_moves_last_capture1
+        } else {
+            &mut self.half_moves_last_capture2
+        };
+        if reset {
+            *counter = 0;
+        } else {
+            *counter += 1;
+        }
+
         if board1 {
-            if self.get_board(board1).board[i][j] != Piece::E {
-               return Err(MoveError::CannotDeploy)
+            self.chess_board1.board[i][j] = piece;
+            self.white_active_1 = !self.white_active_1;
+
+            if white {
+                self.board1_white_capture[ind] -= 1;
+
+                //a deployed pawn cannot be captured en passant
+                self.pawn_in_last_turn_b1 = None;
+
+                if piece == Piece::R && self.rules.rook_drop_castling {
+                    if i == 7 && j == 7 {
+                        self.chess_board1.white_rook_k_moved = false;
+                    }
+                    if i == 7 && j == 0 {
+                        self.chess_board1.white_rook_q_moved = false;
+                    }
+                }
+            } else {
+                self.board1_black_capture[ind] -= 1;
+
+                //a deployed pawn cannot be captured en passant
+                self.pawn_in_last_turn_b1 = None;
+
+                if piece == Piece::r && self.rules.rook_drop_castling {
+                    if i == 0 && j == 7 {
+                        self.chess_board1.black_rook_k_moved = false;
+                    }
+                    if i == 0 && j == 0 {
+                        self.chess_board1.black_rook_q_moved = false;
+                    }
+                }
+                self.movectr1 += 1;
             }
+        } else {
+            self.chess_board2.board[i][j] = piece;
+            self.white_active_2 = !self.white_active_2;
 
             if white {
-                if !self.white_active_1 {  
-                    return Err(MoveError::NotTurn)
-                } else {
-            
-                    if let Some(ind) = self.box_index(p) {
-                        if self.board1_white_capture[ind]>0 {
-                            self.board1_white_capture[ind]-=1;
-                        }else{
-                            return Err(MoveError::NoPieceInPool)
+                self.board2_white_capture[ind] -= 1;
+
+                //a deployed pawn cannot be captured en passant
+                self.pawn_in_last_turn_b2 = None;
+
+                if piece == Piece::R && self.rules.rook_drop_castling {
+                    if i == 7 && j == 7 {
+                        self.chess_board2.white_rook_k_moved = false;
+                    }
+                    if i == 7 && j == 0 {
+                        self.chess_board2.white_rook_q_moved = false;
+                    }
+                }
+            } else {
+                self.board2_black_capture[ind] -= 1;
+
+                //a deployed pawn cannot be captured en passant
+                self.pawn_in_last_turn_b2 = None;
+
+                if piece == Piece::r && self.rules.rook_drop_castling {
+                    if i == 0 && j == 7 {
+                        self.chess_board2.black_rook_k_moved = false;
+                    }
+                    if i == 0 && j == 0 {
+                        self.chess_board2.black_rook_q_moved = false;
+                    }
+                }
+                self.movectr2 += 1;
+            }
+        }
+        self.update_bitboards(board1, &[(i, j)]);
+    }
+
+    ///Moves the piece from i_old,j_old to i,j if the move is legal
+    /// # Arguments
+    /// * `board1` - true if to deploy on board1 else false  
+    /// * `i_old` - the row before the move
+    /// * `j_old` - the col before the move
+    /// * `i` - the row to move
+    /// * `j` - the col to move
+    ///
+    /// Moves the piece p from (i_old,j_old) to (i,j) if (i_old,j_old) has a piece and (i,j) is within bounds and legal moving to.
+    /// Pinned pieces can move in bughouse, and the king can be captured, meaning that the king can move into a square that is attacked
+    /// Function will return an error if a winner was already set (meaning not equal to Winner::N).
+    /// The function updates castling rights on both sides for both colors, if the callers color is not equal to the active color
+    /// the function will return an error. It also updates half-turns and total turns.
+    /// In case of a promotion the fields upgrade_to1 or upgrade_to2 has to be set BEFORE, after a successful promotion the corresponding field
+    /// will be reset (set to Piece::E), if the field is not set to a legal piece the function will return an Error,
+    /// unless the rules have auto_queen, then a pawn without a promotion becomes a queen.
+    /// So the promotion precondition has to be fulfilled before calling the movemaker function. A captures piece is automatically sent to the
+    /// teammates deployable pieces pool. If the game is played with a clock, the flags are checked before the move
+    /// and the clock is pressed after it.
+    pub fn movemaker(
+        &mut self,
+        board1: bool,
+        i_old: usize,
+        j_old: usize,
+        i: usize,
+        j: usize,
+    ) -> Result<bool, MoveError> {
+        let upgrade = if board1 { self.upgrade_to1 } else { self.upgrade_to2 };
+        let promotion = match (self.valid(i_old as i32, j_old as i32), upgrade) {
+            (true, piece) if piece != Piece::E && self.is_promoting(board1, i_old, j_old, i) => Some(piece),
+            _ => None,
+        };
+        self.play_move(board1, i_old, j_old, i, j, promotion)
+            .map_err(|e| e.kind())
+    }
+
+    ///Same as movemaker but the promotion is a parameter and the error has the reason the move is rejected
+    fn play_move(
+        &mut self,
+        board1: bool,
+        i_old: usize,
+        j_old: usize,
+        i: usize,
+        j: usize,
+        promotion: Option<Piece>,
+    ) -> Result<bool, ActionError> {
+        self.check_flags();
+        let promotion = self.validate_move(board1, i_old, j_old, i, j, promotion)?;
+        if !self.lookahead && self.rules.termination == Termination::Checkmate {
+            let white = self.get_piece(board1, i_old, j_old).is_white();
+            let mut cl = self.preview_base();
+            cl.play_move(board1, i_old, j_old, i, j, promotion)?;
+            if cl.is_in_check(Seat::new(BoardId::from_board1(board1), Color::from_white(white))) {
+                return Err(ActionError::new(
+                    BoardId::from_board1(board1),
+                    Square::new(i_old, j_old),
+                    Square::new(i, j),
+                    self.get_piece(board1, i_old, j_old),
+                    RejectCause::LeavesKingInCheck,
+                ));
+            }
+        }
+        let before = self.snapshot();
+        self.apply_move(board1, i_old, j_old, i, j, promotion);
+        //check if the player to move is stalemated
+        if self.winner == Winner::N {
+            self.check_patt(board1, self.get_white_active(board1));
+        }
+        self.update_hashes(&before);
+        self.after_action(board1);
+        let after = self.snapshot();
+        let mv = Move {
+            from: Square::new(i_old, j_old).unwrap(),
+            to: Square::new(i, j).unwrap(),
+            promotion,
+        };
+        let white = if board1 {
+            before.white_active_1
+        } else {
+            before.white_active_2
+        };
+        let remaining = self.get_remaining_time(board1, white);
+        let action = Action::Move {
+            board: BoardId::from_board1(board1),
+            mv,
+        };
+        self.emit(events::action_events(&before, &after, &action));
+        let timestamp = self.clock.as_ref().map(|c| c.now());
+        self.history.push(action, before, after, remaining, timestamp);
+        self.run_premoves(board1);
+        Ok(true)
+    }
+
+    ///Returns true if the piece at i_old,j_old is a pawn that reaches the last rank on row i
+    fn is_promoting(&self, board1: bool, i_old: usize, j_old: usize, i: usize) -> bool {
+        match self.get_piece(board1, i_old, j_old) {
+            Piece::P => i == 0,
+            Piece::p => i == 7,
+            _ => false,
+        }
+    }
+
+    ///Checks if a move can be played without changing the game, see movemaker
+    ///
+    ///Returns the promotion piece in the colour of the pawn, None if the move is not a promotion.
+    ///A promotion is rejected if the move does not promote, without a promotion the pawn becomes a queen if the rules have auto_queen.
+    fn validate_move(
+        &self,
+        board1: bool,
+        i_old: usize,
+        j_old: usize,
+        i: usize,
+        j: usize,
+        promotion: Option<Piece>,
+    ) -> Result<Option<Piece>, ActionError> {
+        let piece = if self.valid(i_old as i32, j_old as i32) {
+            self.get_piece(board1, i_old, j_old)
+        } else {
+            Piece::E
+        };
+        let err = |cause| {
+            ActionError::new(
+                BoardId::from_board1(board1),
+                Square::new(i_old, j_old),
+                Square::new(i, j),
+                piece,
+                cause,
+            )
+        };
+        if self.winner != Winner::N {
+            return Err(err(RejectCause::GameOver(self.winner)));
+        }
+
+        if !self.valid(i_old as i32, j_old as i32) || !self.valid(i as i32, j as i32) {
+            return Err(err(RejectCause::OutOfBounds));
+        }
+        if piece == Piece::E || piece == Piece::L {
+            return Err(err(RejectCause::EmptySquare));
+        }
+        if !self.get_legal_moves(board1, i_old, j_old).contains(&(i, j)) {
+            return Err(err(self.reject_move_cause(board1, i_old, j_old, i, j)));
+        }
+
+        if piece.is_white() != self.get_white_active(board1) {
+            return Err(err(RejectCause::WrongColor));
+        }
+
+        match (self.is_promoting(board1, i_old, j_old, i), promotion) {
+            (false, None) => Ok(None),
+            (false, Some(_)) => Err(err(RejectCause::InvalidPromotion)),
+            (true, None) if self.rules.auto_queen => Ok(Some(self.pool_piece(4, piece.is_white()))),
+            (true, None) => Err(err(RejectCause::MissingPromotion)),
+            (true, Some(requested)) => match self.box_index(requested) {
+                Some(ind) if ind != 0 && !requested.is_promoted() => Ok(Some(self.pool_piece(ind, piece.is_white()))),
+                _ => Err(err(RejectCause::InvalidPromotion)),
+            },
+        }
+    }
+
+    ///Finds out why a piece cannot move to a square
+    /// # Arguments
+    /// * `board1` - true if board1 else false
+    /// * `i_old` - the row of the piece
+    /// * `j_old` - the col of the piece
+    /// * `i` - the row the piece cannot move to
+    /// * `j` - the col the piece cannot move to
+    fn reject_move_cause(&self, board1: bool, i_old: usize, j_old: usize, i: usize, j: usize) -> RejectCause {
+        let piece = self.get_piece(board1, i_old, j_old);
+        let target = self.get_piece(board1, i, j);
+        if target != Piece::E && target != Piece::L && target.is_white() == piece.is_white() {
+            return RejectCause::OwnPiece;
+        }
+        let (di, dj) = (i as i32 - i_old as i32, j as i32 - j_old as i32);
+        let straight = di == 0 || dj == 0;
+        let diagonal = di.abs() == dj.abs();
+        let slides = match piece {
+            Piece::R | Piece::r | Piece::UR | Piece::Ur => straight,
+            Piece::B | Piece::b | Piece::UB | Piece::Ub => diagonal,
+            Piece::Q | Piece::q | Piece::UQ | Piece::Uq => straight || diagonal,
+            Piece::P => dj == 0 && di == -2 && i_old == 6,
+            Piece::p => dj == 0 && di == 2 && i_old == 1,
+            _ => false,
+        };
+        if slides {
+            let (si, sj) = (di.signum(), dj.signum());
+            let (mut a, mut b) = (i_old as i32 + si, j_old as i32 + sj);
+            while (a, b) != (i as i32, j as i32) {
+                if !self.is_empty(board1, a as usize, b as usize) {
+                    return RejectCause::BlockedPath;
+                }
+                a += si;
+                b += sj;
+            }
+        }
+        RejectCause::IllegalPieceMove
+    }
+
+    ///Applies a move that has been checked by validate_move, the pawn that can be captured en passant is only kept after a double step
+    /// # Arguments
+    /// * `promotion` - the piece a pawn reaching the last rank becomes, in the colour of the pawn
+    fn apply_move(&mut self, board1: bool, i_old: usize, j_old: usize, i: usize, j: usize, promotion: Option<Piece>) {
+        let (squares, len) = self.move_squares(board1, i_old, j_old, i, j);
+        self.execute_move(board1, i_old, j_old, i, j, promotion);
+        self.update_bitboards(board1, &squares[..len]);
+        if cmp::max(i, i_old) - cmp::min(i, i_old) != 2 {
+            self.set_pawn_in_last_turn(board1, None);
+        }
+    }
+
+    ///Returns the squares a move can change: the target, the start, the rook of a castling and the pawn captured en passant
+    /// # Arguments
+    /// * `board1` - true if board1, else false
+    /// * `i_old` - the row before the move
+    /// * `j_old` - the col before the move
+    /// * `i` - the row to move
+    /// * `j` - the col to move
+    pub(crate) fn move_squares(
+        &self,
+        board1: bool,
+        i_old: usize,
+        j_old: usize,
+        i: usize,
+        j: usize,
+    ) -> ([(usize, usize); 4], usize) {
+        let mut squares = [(i, j), (i_old, j_old), (0, 0), (0, 0)];
+        match self.get_piece(board1, i_old, j_old) {
+            Piece::K | Piece::k if j_old == 4 && (j == 6 || j == 2) => {
+                squares[2] = (i, if j == 6 { 7 } else { 0 });
+                squares[3] = (i, if j == 6 { 5 } else { 3 });
+                (squares, 4)
+            }
+            Piece::P | Piece::p if j_old != j => {
+                squares[2] = (i_old, j);
+                (squares, 3)
+            }
+            _ => (squares, 2),
+        }
+    }
+
+    ///Applies a move that has been checked by validate_move
+    /// # Arguments
+    /// * `promotion` - the piece a pawn reaching the last rank becomes, in the colour of the pawn
+    fn execute_move(&mut self, board1: bool, i_old: usize, j_old: usize, i: usize, j: usize, promotion: Option<Piece>) {
+        if board1 {
+            match self.chess_board1.board[i_old][j_old] {
+                Piece::R => {
+                    if i_old == 7 && j_old == 7 {
+                        self.chess_board1.white_rook_k_moved = true;
+                    }
+                    if i_old == 7 && j_old == 0 {
+                        self.chess_board1.white_rook_q_moved = true;
+                    }
+                }
+                Piece::r => {
+                    if i_old == 0 && j_old == 7 {
+                        self.chess_board1.black_rook_k_moved = true;
+                    }
+                    if i_old == 0 && j_old == 0 {
+                        self.chess_board1.black_rook_q_moved = true;
+                    }
+                }
+
+                Piece::K => {
+                    self.pawn_in_last_turn_b1 = None;
+
+                    //check if the move is a castling move
+                    if i_old == 7 && j_old == 4 && i == 7 && (j == 6 || j == 2) && !self.chess_board1.white_k_moved {
+                        //we are going castle
+                        self.chess_board1.board[i_old][j_old] = Piece::E;
+                        self.chess_board1.board[i][j] = Piece::K;
+                        if j == 6 {
+                            self.chess_board1.board[i][j - 1] = Piece::R;
+                            self.chess_board1.board[7][7] = Piece::E;
+                        } else {
+                            self.chess_board1.board[i][j + 1] = Piece::R;
+                            self.chess_board1.board[7][0] = Piece::E;
                         }
+                        self.chess_board1.white_k_moved = true;
+                        self.white_active_1 = !self.white_active_1;
 
-                        self.chess_board1.board[i][j] = p;
+                        self.half_moves_last_capture1 += 1;
+                        return;
+                    }
+                    self.chess_board1.white_k_moved = true;
+                }
+                Piece::k => {
+                    self.pawn_in_last_turn_b1 = None;
+
+                    if i_old == 0 && j_old == 4 && i == 0 && (j == 6 || j == 2) && !self.chess_board1.black_k_moved {
+                        //we are going castle
+                        self.chess_board1.board[i_old][j_old] = Piece::E;
+                        self.chess_board1.board[i][j] = Piece::k;
+                        if j == 6 {
+                            self.chess_board1.board[i][j - 1] = Piece::r;
+                            self.chess_board1.board[0][7] = Piece::E;
+                        } else {
+                            self.chess_board1.board[i][j + 1] = Piece::r;
+                            self.chess_board1.board[0][0] = Piece::E;
+                        }
+                        self.chess_board1.black_k_moved = true;
                         self.white_active_1 = !self.white_active_1;
 
-                        if p == Piece::P {
-                            if i==0 {
-                                return Err(MoveError::NotLegal);
-                            }
+                        self.half_moves_last_capture1 += 1;
+                        self.movectr1 += 1;
+                        return;
+                    }
 
-                            self.pawn_in_last_turn_b1 = Some((i,j));
-                            self.half_moves_last_capture2=0;
-                        }else{
-                            self.pawn_in_last_turn_b1 = None;
-                            self.half_moves_last_capture1+=1;
+                    self.chess_board1.black_k_moved = true;
+                }
+                Piece::P => {
+                    self.pawn_in_last_turn_b1 = Some((i, j));
+                    if i == 0 {
+                        self.half_moves_last_capture1 = 0;
+                        match self.box_index(self.chess_board1.board[i][j]) {
+                            None => {}
+                            Some(x) => self.board2_black_capture[x] += 1,
                         }
 
-                        if p == Piece::R {
-                            if i==7 && j==7 {
-                                self.get_board(board1).white_rook_k_moved = false;
+                        match promotion.unwrap_or(Piece::E) {
+                            Piece::Q => {
+                                self.upgrade_to1 = Piece::E;
+                                self.chess_board1.board[i][j] = Piece::UQ;
+                                self.chess_board1.board[i_old][j_old] = Piece::E;
+                                self.white_active_1 = !self.white_active_1;
+                                return;
                             }
-                            if i==7 && j==7 {
-                                self.get_board(board1).white_rook_q_moved = false;
+                            Piece::R => {
+                                self.upgrade_to1 = Piece::E;
+                                self.chess_board1.board[i][j] = Piece::UR;
+                                self.chess_board1.board[i_old][j_old] = Piece::E;
+                                self.white_active_1 = !self.white_active_1;
+                                return;
+                            }
+                            Piece::B => {
+                                self.upgrade_to1 = Piece::E;
+                                self.chess_board1.board[i][j] = Piece::UB;
+                                self.chess_board1.board[i_old][j_old] = Piece::E;
+                                self.white_active_1 = !self.white_active_1;
+                                return;
+                            }
+                            Piece::N => {
+                                self.upgrade_to1 = Piece::E;
+                                self.chess_board1.board[i][j] = Piece::UN;
+                                self.chess_board1.board[i_old][j_old] = Piece::E;
+                                self.white_active_1 = !self.white_active_1;
+                                return;
                             }
+                            _ => unreachable!("the promotion is validated"),
                         }
-
-                        return Ok(true)
-                    } else {return Err(MoveError::CannotDeploy)}
+                    }
                 }
-            }else{
-                if self.white_active_1 {
-                     return Err(MoveError::CannotDeploy)
-                }else {
-
-                    if  let Some(ind) = self.box_index(p) {
-                        if self.board1_black_capture[ind]>0 {
-                            self.board1_black_capture[ind]-=1;
-                        }else{
-                            return Err(MoveError::NoPieceInPool)
+                Piece::p => {
+                    self.pawn_in_last_turn_b1 = Some((i, j));
+                    if i == 7 {
+                        self.half_moves_last_capture1 = 0;
+                        match self.box_index(self.chess_board1.board[i][j]) {
+                            None => {}
+                            Some(x) => self.board2_white_capture[x] += 1,
+                        }
+                        //the promotion returns before the counter below, black has moved
+                        self.movectr1 += 1;
+
+                        match promotion.unwrap_or(Piece::E) {
+                            Piece::q => {
+                                self.upgrade_to1 = Piece::E;
+                                self.chess_board1.board[i][j] = Piece::Uq;
+                                self.chess_board1.board[i_old][j_old] = Piece::E;
+                                self.white_active_1 = !self.white_active_1;
+                                return;
+                            }
+                            Piece::r => {
+                                self.upgrade_to1 = Piece::E;
+                                self.chess_board1.board[i][j] = Piece::Ur;
+                                self.chess_board1.board[i_old][j_old] = Piece::E;
+                                self.white_active_1 = !self.white_active_1;
+                                return;
+                            }
+                            Piece::b => {
+                                self.upgrade_to1 = Piece::E;
+                                self.chess_board1.board[i][j] = Piece::Ub;
+                                self.chess_board1.board[i_old][j_old] = Piece::E;
+                                self.white_active_1 = !self.white_active_1;
+                                return;
+                            }
+                            Piece::n => {
+                                self.upgrade_to1 = Piece::E;
+                                self.chess_board1.board[i][j] = Piece::Un;
+                                self.chess_board1.board[i_old][j_old] = Piece::E;
+                                self.white_active_1 = !self.white_active_1;
+                                return;
+                            }
+                            _ => unreachable!("the promotion is validated"),
                         }
+                    }
+                }
+                _ => {}
+            }
 
-                        self.chess_board1.board[i][j] = p;
-                        self.white_active_1 = !self.white_active_1;
+            //check to move location
+            let tmp = self.chess_board1.board[i][j];
 
-                        if p == Piece::p {
-                            if i==7 {
-                                return Err(MoveError::NotLegal);
-                            }
+            //check if any piece is captured
+            if tmp != Piece::E {
+                self.half_moves_last_capture1 = 0;
+            } else {
+                self.half_moves_last_capture1 += 1;
+            }
+
+            //en passant, the captured pawn is next to the pawn and goes to the ally like any captured piece
+            let moving = self.chess_board1.board[i_old][j_old];
+            if (moving == Piece::P || moving == Piece::p) && j != j_old && tmp == Piece::E {
+                if self.chess_board1.board[i_old][j] == Piece::P {
+                    self.board2_white_capture[0] += 1;
+                } else {
+                    self.board2_black_capture[0] += 1;
+                }
+                self.chess_board1.board[i_old][j] = Piece::E;
+            }
+
+            //check if pawn is moved
+            if self.chess_board1.board[i_old][j_old] == Piece::P || self.chess_board1.board[i_old][j_old] == Piece::p {
+                self.pawn_in_last_turn_b1 = Some((i, j));
+                self.half_moves_last_capture1 = 0;
+
+            //if the king has moved change king has moved flag
+            } else if self.chess_board1.board[i_old][j_old] == Piece::K {
+                self.pawn_in_last_turn_b1 = None;
+                self.chess_board1.white_k_moved = true;
+            } else if self.chess_board1.board[i_old][j_old] == Piece::k {
+                self.pawn_in_last_turn_b1 = None;
+                self.chess_board1.black_k_moved = true;
+            } else {
+                //no king no pawn movement -> pawn in last turn = None
+                self.pawn_in_last_turn_b1 = None;
+            }
+
+            //check if black has moved
+            if !self.is_white(board1, i_old, j_old) {
+                self.movectr1 += 1;
+            }
 
-                            self.pawn_in_last_turn_b1 = Some((i,j));
-                            self.half_moves_last_capture1=0;
-                        }else{
-                            self.pawn_in_last_turn_b1 = None;
-                            self.half_moves_last_capture1+=1;
+            //check if game  should end
+            if tmp == Piece::K || tmp == Piece::k {
+                self.finish_up(tmp, board1);
+            }
+
+            //send captured piece to your ally
+            match self.box_index(tmp) {
+                None => (), //nothing to di
+                Some(a) => {
+                    if self.is_white(board1, i, j) {
+                        self.board2_white_capture[a] += 1;
+                    } else {
+                        self.board2_black_capture[a] += 1;
+                    }
+                }
+            }
+
+            //apply move
+            self.chess_board1.board[i][j] = self.chess_board1.board[i_old][j_old];
+            self.chess_board1.board[i_old][j_old] = Piece::E;
+            self.white_active_1 = !self.white_active_1;
+        } else {
+            match self.chess_board2.board[i_old][j_old] {
+                Piece::R => {
+                    if i_old == 7 && j_old == 7 {
+                        self.chess_board2.white_rook_k_moved = true;
+                    }
+                    if i_old == 7 && j_old == 0 {
+                        self.chess_board2.white_rook_q_moved = true;
+                    }
+                }
+                Piece::r => {
+                    if i_old == 0 && j_old == 7 {
+                        self.chess_board2.black_rook_k_moved = true;
+                    }
+                    if i_old == 0 && j_old == 0 {
+                        self.chess_board2.black_rook_q_moved = true;
+                    }
+                }
+
+                Piece::K => {
+                    self.pawn_in_last_turn_b2 = None;
+
+                    if i_old == 7 && j_old == 4 && i == 7 && (j == 6 || j == 2) && !self.chess_board2.white_k_moved {
+                        //we are going castle
+                        self.chess_board2.board[i_old][j_old] = Piece::E;
+                        self.chess_board2.board[i][j] = Piece::K;
+                        if j == 6 {
+                            self.chess_board2.board[i][j - 1] = Piece::R;
+                            self.chess_board2.board[7][7] = Piece::E;
+                        } else {
+                            self.chess_board2.board[i][j + 1] = Piece::R;
+                            self.chess_board2.board[7][0] = Piece::E;
+                        }
+                        self.chess_board2.white_k_moved = true;
+                        self.white_active_2 = !self.white_active_2;
+
+                        self.half_moves_last_capture2 += 1;
+                        return;
+                    }
+                    self.chess_board2.white_k_moved = true;
+                }
+                Piece::k => {
+                    self.pawn_in_last_turn_b2 = None;
+                    if i_old == 0 && j_old == 4 && i == 0 && (j == 6 || j == 2) && !self.chess_board2.black_k_moved {
+                        //we are going castle
+                        self.chess_board2.board[i_old][j_old] = Piece::E;
+                        self.chess_board2.board[i][j] = Piece::k;
+                        if j == 6 {
+                            self.chess_board2.board[i][j - 1] = Piece::r;
+                            self.chess_board2.board[0][7] = Piece::E;
+                        } else {
+                            self.chess_board2.board[i][j + 1] = Piece::r;
+                            self.chess_board2.board[0][0] = Piece::E;
                         }
+                        self.chess_board2.black_k_moved = true;
+                        self.white_active_2 = !self.white_active_2;
 
-                        if p == Piece::r {
-                            if i==0 && j==7 {
-                                self.get_board(board1).black_rook_k_moved = false;
+                        self.half_moves_last_capture2 += 1;
+                        self.movectr2 += 1;
+                        return;
+                    }
+
+                    self.chess_board2.black_k_moved = true;
+                }
+                Piece::P if i == 0 => {
+                    self.pawn_in_last_turn_b2 = Some((i, j));
+                    self.half_moves_last_capture2 = 0;
+                    match self.box_index(self.chess_board2.board[i][j]) {
+                        None => {}
+                        Some(x) => self.board1_black_capture[x] += 1,
+                    }
+
+                    match promotion.unwrap_or(Piece::E) {
+                        Piece::Q => {
+                            self.upgrade_to2 = Piece::E;
+                            self.chess_board2.board[i][j] = Piece::UQ;
+                            self.chess_board2.board[i_old][j_old] = Piece::E;
+                            self.white_active_2 = !self.white_active_2;
+                            return;
+                        }
+                        Piece::R => {
+                            self.upgrade_to2 = Piece::E;
+                            self.chess_board2.board[i][j] = Piece::UR;
+                            self.chess_board2.board[i_old][j_old] = Piece::E;
+                            self.white_active_2 = !self.white_active_2;
+                            return;
+                        }
+                        Piece::B => {
+                            self.upgrade_to2 = Piece::E;
+                            self.chess_board2.board[i][j] = Piece::UB;
+                            self.chess_board2.board[i_old][j_old] = Piece::E;
+                            self.white_active_2 = !self.white_active_2;
+                            return;
+                        }
+                        Piece::N => {
+                            self.upgrade_to2 = Piece::E;
+                            self.chess_board2.board[i][j] = Piece::UN;
+                            self.chess_board2.board[i_old][j_old] = Piece::E;
+                            self.white_active_2 = !self.white_active_2;
+                            return;
+                        }
+                        _ => unreachable!("the promotion is validated"),
+                    }
+                }
+                Piece::p => {
+                    self.pawn_in_last_turn_b2 = Some((i, j));
+                    if i == 7 {
+                        self.half_moves_last_capture2 = 0;
+                        match self.box_index(self.chess_board2.board[i][j]) {
+                            None => {}
+                            Some(x) => self.board1_white_capture[x] += 1,
+                        }
+                        //the promotion returns before the counter below, black has moved
+                        self.movectr2 += 1;
+
+                        match promotion.unwrap_or(Piece::E) {
+                            Piece::q => {
+                                self.upgrade_to2 = Piece::E;
+                                self.chess_board2.board[i][j] = Piece::Uq;
+                                self.chess_board2.board[i_old][j_old] = Piece::E;
+                                self.white_active_2 = !self.white_active_2;
+                                return;
                             }
-                            if i==0 && j==0 {
-                                self.get_board(board1).black_rook_q_moved = false;
+                            Piece::r => {
+                                self.upgrade_to2 = Piece::E;
+                                self.chess_board2.board[i][j] = Piece::Ur;
+                                self.chess_board2.board[i_old][j_old] = Piece::E;
+                                self.white_active_2 = !self.white_active_2;
+                                return;
                             }
+                            Piece::b => {
+                                self.upgrade_to2 = Piece::E;
+                                self.chess_board2.board[i][j] = Piece::Ub;
+                                self.chess_board2.board[i_old][j_old] = Piece::E;
+                                self.white_active_2 = !self.white_active_2;
+                                return;
+                            }
+                            Piece::n => {
+                                self.upgrade_to2 = Piece::E;
+                                self.chess_board2.board[i][j] = Piece::Un;
+                                self.chess_board2.board[i_old][j_old] = Piece::E;
+                                self.white_active_2 = !self.white_active_2;
+                                return;
+                            }
+                            _ => unreachable!("the promotion is validated"),
                         }
-                        self.movectr1+=1;
-                        return Ok(true)
-                    } else {return Err(MoveError::CannotDeploy)}
-                }   
-            } 
-        }else {
-            if self.chess_board2.board[i][j] != Piece::E {
-                return Err(MoveError::CannotDeploy)
+                    }
+                }
+                _ => {}
+            }
+
+            let tmp = self.chess_board2.board[i][j];
+
+            //check if any piece is captured
+            if tmp != Piece::E {
+                self.half_moves_last_capture2 = 0;
+            } else {
+                self.half_moves_last_capture2 += 1;
             }
 
-            if white {
-                if !self.white_active_2 {  
-                    return Err(MoveError::CannotDeploy)
-                } else {
-             
-                    if let Some(ind) = self.box_index(p) {
-                        if self.board2_white_capture[ind]>0 {
-                            self.board2_white_capture[ind]-=1;
-                        }else{
-                           return Err(MoveError::NoPieceInPool)
-                        }
+            //en passant, the captured pawn is next to the pawn and goes to the ally like any captured piece
+            let moving = self.chess_board2.board[i_old][j_old];
+            if (moving == Piece::P || moving == Piece::p) && j != j_old && tmp == Piece::E {
+                if self.chess_board2.board[i_old][j] == Piece::P {
+                    self.board1_white_capture[0] += 1;
+                } else {
+                    self.board1_black_capture[0] += 1;
+                }
+                self.chess_board2.board[i_old][j] = Piece::E;
+            }
+
+            //check if pawn is moved
+            if self.chess_board2.board[i_old][j_old] == Piece::P || self.chess_board2.board[i_old][j_old] == Piece::p {
+                self.pawn_in_last_turn_b2 = Some((i, j));
+                self.half_moves_last_capture2 = 0;
+            } else if self.chess_board2.board[i_old][j_old] == Piece::K {
+                self.pawn_in_last_turn_b2 = None;
+                self.chess_board2.white_k_moved = true;
+            } else if self.chess_board2.board[i_old][j_old] == Piece::k {
+                self.pawn_in_last_turn_b2 = None;
+                self.chess_board2.black_k_moved = true;
+            } else {
+                self.pawn_in_last_turn_b2 = None;
+            }
+
+            //check if black has moved
+            if !self.is_white(board1, i_old, j_old) {
+                self.movectr2 += 1;
+            }
+
+            //check if game  should end
+            if tmp == Piece::K || tmp == Piece::k {
+                self.finish_up(tmp, board1);
+            }
+
+            match self.box_index(tmp) {
+                None => {} //nothing to di
+                Some(a) => {
+                    if self.is_white(board1, i, j) {
+                        self.board1_white_capture[a] += 1;
+                    } else {
+                        self.board1_black_capture[a] += 1;
+                    }
+                }
+            }
+            self.chess_board2.board[i][j] = self.chess_board2.board[i_old][j_old];
+            self.chess_board2.board[i_old][j_old] = Piece::E;
+            self.white_active_2 = !self.white_active_2;
+        }
+    }
+
+    ///Same as movemaker but with a typed move, the promotion is taken from the move
+    /// # Arguments
+    /// * `board` - the board of the move
+    /// * `mv` - the move to play
+    ///
+    /// A promoting move needs a promotion unless the rules have auto_queen, the field set by set_promotion is not used.
+    pub fn make_move(&mut self, board: BoardId, mv: Move) -> Result<bool, MoveError> {
+        self.try_move(board, mv).map_err(|e| e.kind())
+    }
+
+    ///Same as make_move but the error has the reason the move is rejected
+    /// # Arguments
+    /// * `board` - the board of the move
+    /// * `mv` - the move to play
+    pub fn try_move(&mut self, board: BoardId, mv: Move) -> Result<bool, ActionError> {
+        self.play_move(
+            board.is_board1(),
+            mv.from.get_row(),
+            mv.from.get_col(),
+            mv.to.get_row(),
+            mv.to.get_col(),
+            mv.promotion,
+        )
+    }
+
+    ///Same as deploy_piece but with a typed deploy, the colour is the colour of the piece
+    /// # Arguments
+    /// * `board` - the board to deploy on
+    /// * `drop` - the deploy to play
+    pub fn drop_piece(&mut self, board: BoardId, drop: Drop) -> Result<bool, MoveError> {
+        self.try_drop(board, drop).map_err(|e| e.kind())
+    }
+
+    ///Same as drop_piece but the error has the reason the deploy is rejected
+    /// # Arguments
+    /// * `board` - the board to deploy on
+    /// * `drop` - the deploy to play
+    pub fn try_drop(&mut self, board: BoardId, drop: Drop) -> Result<bool, ActionError> {
+        self.play_deploy(
+            board.is_board1(),
+            drop.is_white(),
+            drop.piece,
+            drop.to.get_row(),
+            drop.to.get_col(),
+        )
+    }
+
+    ///Plays a move or a deploy
+    /// # Arguments
+    /// * `action` - the action to play
+    pub fn play(&mut self, action: Action) -> Result<bool, MoveError> {
+        self.try_play(action).map_err(|e| e.kind())
+    }
+
+    ///Same as play but the error has the reason the action is rejected, e.g. to tell a player why a move is not legal
+    /// # Arguments
+    /// * `action` - the action to play
+    pub fn try_play(&mut self, action: Action) -> Result<bool, ActionError> {
+        match action {
+            Action::Move { board, mv } => self.try_move(board, mv),
+            Action::Drop { board, drop } => self.try_drop(board, drop),
+        }
+    }
+
+    ///Queues a move or deploy of a player, it is played as soon as it is the turn of the player
+    /// # Arguments
+    /// * `color` - the colour of the player, the board is the board of the action
+    /// * `action` - the action to queue
+    ///
+    /// If it is the turn of the player and nothing is queued, the action is played at once.
+    /// Returns Ok(true) if it was played, Ok(false) if it was queued and the error if it was played and rejected.
+    /// A queued action is only checked when it is played, if it is rejected then, e.g. since the piece of a deploy is not
+    /// in the pool or the square became occupied, it and the rest of the queue of the player are cancelled.
+    pub fn premove(&mut self, color: Color, action: Action) -> Result<bool, ActionError> {
+        let seat = Seat::new(action.get_board(), color);
+        let ind = ChessLogic::seat_index(seat);
+        if self.premoves[ind].is_empty() && self.is_turn(seat) && self.winner == Winner::N {
+            return self.try_play(action);
+        }
+        self.premoves[ind].push_back(action);
+        Ok(false)
+    }
+
+    ///Returns the queued actions of a player, the next one is the first
+    /// # Arguments
+    /// * `seat` - the player
+    pub fn get_premoves(&self, seat: Seat) -> Vec<Action> {
+        self.premoves[ChessLogic::seat_index(seat)].iter().copied().collect()
+    }
+
+    ///Cancels the queued actions of a player
+    /// # Arguments
+    /// * `seat` - the player
+    pub fn cancel_premoves(&mut self, seat: Seat) {
+        self.cancel_queue(seat, None);
+    }
+
+    ///Returns the index of the queue of a player
+    fn seat_index(seat: Seat) -> usize {
+        match (seat.board, seat.color) {
+            (BoardId::Board1, Color::White) => 0,
+            (BoardId::Board1, Color::Black) => 1,
+            (BoardId::Board2, Color::White) => 2,
+            (BoardId::Board2, Color::Black) => 3,
+        }
+    }
+
+    ///Removes the queued actions of a player and sends a PremoveCancelled event for each
+    /// # Arguments
+    /// * `seat` - the player
+    /// * `cause` - why the first action is cancelled, None if the player cancelled them
+    fn cancel_queue(&mut self, seat: Seat, cause: Option<RejectCause>) {
+        let queue = std::mem::take(&mut self.premoves[ChessLogic::seat_index(seat)]);
+        let events = queue
+            .into_iter()
+            .enumerate()
+            .map(|(n, action)| Event::PremoveCancelled {
+                seat,
+                action,
+                cause: if n == 0 { cause } else { None },
+            })
+            .collect();
+        self.emit(events);
+    }
+
+    ///Plays the queued actions of the players of a board as long as the player to move has one
+    /// # Arguments
+    /// * `board1` - the board an action was played on
+    fn run_premoves(&mut self, board1: bool) {
+        if self.lookahead || self.premoving {
+            return;
+        }
+        self.premoving = true;
+        let board = BoardId::from_board1(board1);
+        while self.winner == Winner::N {
+            let seat = Seat::new(board, Color::from_white(self.get_white_active(board1)));
+            let action = match self.premoves[ChessLogic::seat_index(seat)].pop_front() {
+                Some(a) => a,
+                None => break,
+            };
+            match self.try_play(action) {
+                Ok(_) => self.emit(vec![Event::PremoveFired { seat, action }]),
+                Err(e) => {
+                    self.premoves[ChessLogic::seat_index(seat)].push_front(action);
+                    self.cancel_queue(seat, Some(e.cause));
+                }
+            }
+        }
+        //nothing can be played after the end of the game
+        if self.winner != Winner::N {
+            for seat in [Team::Team1, Team::Team2].iter().flat_map(|t| t.seats().to_vec()) {
+                self.cancel_queue(seat, Some(RejectCause::GameOver(self.winner)));
+            }
+        }
+        self.premoving = false;
+    }
+
+    ///Same as get_legal_moves but with squares
+    /// # Arguments
+    /// * `board` - the board of the piece
+    /// * `from` - the square of the piece
+    ///
+    ///If the board ends with a checkmate, the moves that leave the own king in check are left out.
+    pub fn legal_moves_from(&self, board: BoardId, from: Square) -> Vec<Square> {
+        let piece = self.piece_at(board, from);
+        let seat = Seat::new(board, Color::from_white(piece.is_white()));
+        self.get_legal_moves(board.is_board1(), from.get_row(), from.get_col())
+            .into_iter()
+            .filter_map(|(i, j)| Square::new(i, j))
+            .filter(|to| {
+                if self.rules.termination == Termination::KingCapture || self.lookahead {
+                    return true;
+                }
+                let mv = match (piece, to.get_row()) {
+                    (Piece::P, 0) | (Piece::p, 7) => Move::with_promotion(from, *to, Piece::Q),
+                    _ => Move::new(from, *to),
+                };
+                self.keeps_king_safe(seat, Action::Move { board, mv })
+            })
+            .collect()
+    }
+
+    ///Returns true if a square is attacked by the pieces of a colour
+    /// # Arguments
+    /// * `board` - the board of the square
+    /// * `by` - the colour of the attacking pieces
+    /// * `sq` - the square to check
+    pub fn is_square_attacked(&self, board: BoardId, by: Color, sq: Square) -> bool {
+        self.is_attacked(board.is_board1(), !by.is_white(), sq.get_row(), sq.get_col())
+    }
+
+    ///Same as find_piece but returns a square
+    /// # Arguments
+    /// * `piece` - the piece to be searched
+    /// * `board` - the board to search
+    pub fn find_square(&self, piece: Piece, board: BoardId) -> Option<Square> {
+        self.find_piece(piece, board.is_board1())
+            .and_then(|(i, j)| Square::new(i, j))
+    }
+
+    ///Same as get_piece but with a square
+    /// # Arguments
+    /// * `board` - the board of the piece
+    /// * `sq` - the square of the piece
+    pub fn piece_at(&self, board: BoardId, sq: Square) -> Piece {
+        self.get_piece(board.is_board1(), sq.get_row(), sq.get_col())
+    }
+
+    ///Returns true if it is the turn of the player
+    /// # Arguments
+    /// * `seat` - the player
+    pub fn is_turn(&self, seat: Seat) -> bool {
+        self.get_white_active(seat.board.is_board1()) == seat.color.is_white()
+    }
+
+    ///Returns the pool of deployable pieces of a player, the order is P-R-N-B-Q
+    /// # Arguments
+    /// * `seat` - the player
+    pub fn get_pool(&self, seat: Seat) -> [u8; 5] {
+        let mut pool = [0; 5];
+        for (i, x) in pool.iter_mut().enumerate() {
+            *x = self.get_captured_piece(seat.board.is_board1(), seat.color.is_white(), i);
+        }
+        pool
+    }
 
-                        self.chess_board2.board[i][j] = p;
-                        self.white_active_2 = !self.white_active_2;
+    ///Resigns the game for a player, the team of the player loses
+    /// # Arguments
+    /// * `seat` - the player that resigns
+    pub fn resign_seat(&mut self, seat: Seat) {
+        self.resign(seat.board.is_board1(), seat.color.is_white());
+    }
 
-                        self.chess_board2.board[i][j] = p;
-                        self.white_active_2 = !self.white_active_2;
-    
-                        if p == Piece::P {
-                            if i==0 {
-                                return Err(MoveError::NotLegal);
-                            }
+    ///Returns the result of the game for the teams
+    pub fn get_team_result(&self) -> TeamResult {
+        match self.winner {
+            Winner::N => TeamResult::Ongoing,
+            Winner::P | Winner::D => TeamResult::Draw,
+            w => TeamResult::Won(w.team().unwrap()),
+        }
+    }
 
-                            self.pawn_in_last_turn_b2 = Some((i,j));
-                            self.half_moves_last_capture2=0;
-                        }else{
-                            self.pawn_in_last_turn_b2 = None;
-                            self.half_moves_last_capture2+=1;
-                        }
+    ///Returns true if the king of the player is attacked
+    /// # Arguments
+    /// * `seat` - the player
+    pub fn is_in_check(&self, seat: Seat) -> bool {
+        let white = seat.color.is_white();
+        let bb = self.bitboards(seat.board.is_board1());
+        match bitboard::squares(bb.pieces(Kind::King, white)).next() {
+            Some((i, j)) => bb.attacked(i, j, !white),
+            None => false,
+        }
+    }
 
-                        if p == Piece::R {
-                            if i==7 && j==7 {
-                                self.chess_board2.white_rook_k_moved = false;
-                            }
-                            if i==7 && j==0 {
-                                self.chess_board2.white_rook_q_moved = false;
-                            }
-                        }
-                        return Ok(true)
-                    }else { 
-                        return Err(MoveError::CannotDeploy)
-                    }
-                }
-            }else{
-                if self.white_active_2 { 
-                    return Err(MoveError::NotTurn)
-                } else {
-                  
-                    if let Some(ind) = self.box_index(p) {
-                        if self.board2_black_capture[ind]>0 {
-                            self.board2_black_capture[ind]-=1;
-                        }else{
-                           return Err(MoveError::NotTurn)
-                        }
+    ///Returns true if the player is in check and no move or deploy from the current pool ends the check
+    /// # Arguments
+    /// * `seat` - the player
+    ///
+    ///It is also true if the check could be blocked by a piece the player does not have yet, see classify.
+    ///The search stops at the first action that keeps the king safe.
+    pub fn is_checkmate(&self, seat: Seat) -> bool {
+        self.is_in_check(seat)
+            && !self
+                .get_actions(seat)
+                .into_iter()
+                .any(|a| self.keeps_king_safe(seat, a))
+    }
 
-                        self.chess_board2.board[i][j] = p;
-                        self.white_active_2 = !self.white_active_2;
-                        
-                        if p == Piece::p {
-                            if i==7 {
-                                return Err(MoveError::NotLegal);
-                            }
+    ///Classifies the position of a player as safe, check, checkmate or a check the player has to sit out
+    /// # Arguments
+    /// * `seat` - the player
+    ///
+    ///A check that can be ended with a move or a deploy from the pool is a Check. If it cannot, but a deploy
+    ///could block it once the teammate sends a piece, the player is Sitting. Only a check that no deploy can block is a Checkmate.
+    pub fn classify(&self, seat: Seat) -> CheckStatus {
+        if !self.is_in_check(seat) {
+            CheckStatus::Safe
+        } else if !self.is_checkmate(seat) {
+            CheckStatus::Check
+        } else if self.drop_blockable(seat) {
+            CheckStatus::Sitting
+        } else {
+            CheckStatus::Checkmate
+        }
+    }
 
-                            self.pawn_in_last_turn_b2 = Some((i,j));
-                            self.half_moves_last_capture2=0;
-                        }else{
-                            self.pawn_in_last_turn_b2 = None;
-                            self.half_moves_last_capture2+=1;
-                        }
+    ///Plays an action on a copy of the game, the copy has neither clock nor history
+    /// # Arguments
+    /// * `action` - the action to play
+    ///
+    ///Needed to look ahead, e.g. to find out if a move gives check.
+    pub fn preview(&self, action: Action) -> Result<ChessLogic, MoveError> {
+        let mut cl = self.preview_base();
+        cl.play(action)?;
+        Ok(cl)
+    }
 
-                        if p == Piece::r {
-                            if i==0 && j==7 {
-                                self.chess_board2.black_rook_k_moved = false;
-                            }
-                            if i==0 && j==0 {
-                                self.chess_board2.black_rook_q_moved = false;
-                            }
-                        }
-                        self.movectr2+=1;
-                       return Ok(true)
-                    } else {
-                        return Err(MoveError::CannotDeploy)
+    ///A copy of the game without clock and history
+    fn preview_base(&self) -> ChessLogic {
+        let mut cl = ChessLogic::from_snapshot(&self.snapshot());
+        cl.rules = self.rules;
+        cl.lookahead = true;
+        cl.bitboards = self.bitboards.clone();
+        cl
+    }
+
+    ///Returns true if the check of the player could be blocked by deploying a piece, if the player had one
+    /// # Arguments
+    /// * `seat` - the player
+    ///
+    ///The blocker is a knight of the player. Any own piece blocks a line the same way and cannot attack the own king,
+    ///and unlike a pawn a knight can be deployed on every rank, so no block is missed.
+    fn drop_blockable(&self, seat: Seat) -> bool {
+        let board1 = seat.board.is_board1();
+        let blocker = self.pool_piece(2, seat.color.is_white());
+        for i in 0..8 {
+            for j in 0..8 {
+                if self.is_empty(board1, i, j) {
+                    let mut cl = self.preview_base();
+                    cl.set_piece(board1, blocker, i, j);
+                    if !cl.is_in_check(seat) {
+                        return true;
                     }
                 }
             }
         }
-        
-        return Err(MoveError::CannotDeploy)
+        false
     }
 
+    ///Returns true if the action is a deploy that checkmates the opponent, forbidden without drop_mate
+    /// # Arguments
+    /// * `seat` - the player
+    /// * `action` - a move or deploy of the player
+    fn drop_mates(&self, seat: Seat, action: Action) -> bool {
+        if let Action::Move { .. } = action {
+            return false;
+        }
+        let mut cl = self.preview_base();
+        if seat.board.is_board1() {
+            cl.white_active_1 = seat.color.is_white();
+        } else {
+            cl.white_active_2 = seat.color.is_white();
+        }
+        cl.play(action).is_ok() && cl.classify(seat.opponent()) == CheckStatus::Checkmate
+    }
 
+    ///Returns true if the action does not leave the king of the player in check
+    /// # Arguments
+    /// * `seat` - the player
+    /// * `action` - a move or deploy of the player
+    fn keeps_king_safe(&self, seat: Seat, action: Action) -> bool {
+        let mut cl = self.preview_base();
+        if seat.board.is_board1() {
+            cl.white_active_1 = seat.color.is_white();
+        } else {
+            cl.white_active_2 = seat.color.is_white();
+        }
+        cl.play(action).is_ok() && !cl.is_in_check(seat)
+    }
 
-    ///Moves the piece from i_old,j_old to i,j if the move is legal
+    ///Checks the house rules of a deploy that are not checked by execute_deploy
     /// # Arguments
-    /// * `board1` - true if to deploy on board1 else false  
-    /// * `i_old` - the row before the move
-    /// * `j_old` - the col before the move
-    /// * `i` - the row to move
-    /// * `j` - the col to move
-    /// 
-    /// Moves the piece p from (i_old,j_old) to (i,j) if (i_old,j_old) has a piece and (i,j) is within bounds and legal moving to.
-    /// Pinned pieces can move in bughouse, and the king can be captured, meaning that the king can move into a square that is attacked
-    /// Function will return an error if a winner was already set (meaning not equal to Winner::N). 
-    /// The function updates castling rights on both sides for both colors, if the callers color is not equal to the active color
-    /// the function will return an error. It also updates half-turns and total turns. 
-    /// In case of a promotion the fields upgrade_to1 or upgrade_to2 has to be set BEFORE, after a successful promotion the corresponding field
-    /// will be reset (set to Piece::E), if the field is not set to a legal piece the function will return an Error
-    /// So the promotion precondition has to be fulfilled before calling the movemaker function. A captures piece is automatically sent to the 
-    /// teammates deployable pieces pool
-    pub fn movemaker(&mut self, board1:bool, i_old:usize,j_old:usize,i:usize,j:usize) -> Result<bool,MoveError> {
-        if self.winner!=Winner::N {
-            return Err(MoveError::AlreadyOver)
-        }
-        
-        //check of move is legal
-        if self.legality_check(board1,i_old,j_old,i,j) {
-            if board1 {
-                match self.get_board(board1).board[i_old][j_old] {
-                    Piece::R => {
-                        if !self.white_active_1 {
-                            return Err(MoveError::NotTurn)
-                        }else{
-                            if i_old == 7 && j_old == 7 {
-                                self.get_board(board1).white_rook_k_moved=true;
-                            }
-                            if i_old == 7 && j_old == 0 {
-                                self.get_board(board1).white_rook_q_moved=true;
-                            }
-                        }
-                    },
-                    Piece::r => {
-                        if self.white_active_1 {
-                            return Err(MoveError::NotTurn)
-                        }else{
-                            if i_old == 0 && j_old == 7 {
-                                self.get_board(board1).black_rook_k_moved=true;
-                            }
-                            if i_old == 0 && j_old == 0 {
-                                self.get_board(board1).black_rook_q_moved=true;
-                            }
-                        }
-                    },
-
-                    Piece::K => 
-                    {
-                        if !self.white_active_1 {
-                            return Err(MoveError::NotTurn)
-                        }else{
-                            self.pawn_in_last_turn_b1 = None;
-                           
-                            //check if the move is a castling move
-                            if i_old==7 && j_old==4 && i== 7 && (j==6 || j==2)
-                            && !self.chess_board1.white_k_moved {
-                                //we are going castle
-                                self.chess_board1.board[i_old][j_old]=Piece::E;
-                                self.chess_board1.board[i][j] = Piece::K;
-                                if j==6 {
-                                    self.chess_board1.board[i][j-1] = Piece::R;
-                                    self.chess_board1.board[7][7] = Piece::E;
-                                }else{
-                                    self.chess_board1.board[i][j+1] = Piece::R;
-                                    self.chess_board1.board[7][0] = Piece::E;
-                                }
-                                self.chess_board1.white_k_moved = true;
-                                self.white_active_1 = !self.white_active_1;
+    /// * `board1` - true if to deploy on board1 else false
+    /// * `white` - true if the piece to be deployed white else false
+    /// * `piece` - the piece to deploy
+    /// * `i` - the row to deploy
+    /// * `j` - the col to deploy
+    fn check_deploy_rules(
+        &self,
+        board1: bool,
+        white: bool,
+        piece: Piece,
+        i: usize,
+        j: usize,
+    ) -> Result<(), ActionError> {
+        let err = |cause| ActionError::new(BoardId::from_board1(board1), None, Square::new(i, j), piece, cause);
+        if !self.rules.pawn_drop_first_rank && ((piece == Piece::P && i == 7) || (piece == Piece::p && i == 0)) {
+            return Err(err(RejectCause::PawnDropFirstRank));
+        }
+        if self.rules.termination == Termination::KingCapture && self.rules.drop_mate {
+            return Ok(());
+        }
+        let seat = Seat::new(BoardId::from_board1(board1), Color::from_white(white));
+        let mut cl = self.preview_base();
+        cl.play_deploy(board1, white, piece, i, j)?;
+        if self.rules.termination == Termination::Checkmate && cl.is_in_check(seat) {
+            return Err(err(RejectCause::LeavesKingInCheck));
+        }
+        if !self.rules.drop_mate && cl.classify(seat.opponent()) == CheckStatus::Checkmate {
+            return Err(err(RejectCause::DropMate));
+        }
+        Ok(())
+    }
 
-                                self.half_moves_last_capture1 += 1;
-                                return Ok(true)
-                            }
-                            self.chess_board1.white_k_moved = true;
-                        }
-                    },
-                    Piece::k => 
-                    {
-                        if self.white_active_1 {
-                            return Err(MoveError::NotTurn)
-                        }else{
-                            self.pawn_in_last_turn_b1 = None;
-
-                            if i_old==0 && j_old==4 && i== 0 && (j==6 || j==2)
-                            && !self.chess_board1.black_k_moved
-                            {
-                                //we are going castle
-                                self.chess_board1.board[i_old][j_old]=Piece::E;
-                                self.chess_board1.board[i][j] = Piece::K;
-                                if j==6 {
-                                    self.chess_board1.board[i][j-1] = Piece::R;
-                                    self.chess_board1.board[0][7] = Piece::E;
-                                }else{
-                                    self.chess_board1.board[i][j+1] = Piece::R;
-                                    self.chess_board1.board[0][0] = Piece::E;
-                                }
-                                self.chess_board1.black_k_moved = true;
-                                self.white_active_1 = !self.white_active_1;
+    ///Creates a game without clock and history from a snapshot
+    /// # Arguments
+    /// * `s` - the state of the game, e.g. the state before an action of the history
+    pub fn from_snapshot(s: &Snapshot) -> ChessLogic {
+        let mut cl = ChessLogic::new();
+        cl.restore(s);
+        cl
+    }
 
-                                self.half_moves_last_capture1 += 1;
-                                self.movectr1+=1;
-                                return Ok(true)
-                            }
-                        
-                            self.chess_board1.black_k_moved = true;
-                        }
-                    },
-                    Piece::P => 
-                    {
-                        if !self.white_active_1 {
-                            return Err(MoveError::NotTurn)
-                        }else{
-                            self.pawn_in_last_turn_b1 = Some((i,j));
-                            if i==0 {
-                                if self.upgrade_to1 == Piece::Q ||  self.upgrade_to1 == Piece::R  || self.upgrade_to1 == Piece::B  ||self.upgrade_to1 == Piece::N {
-                                    self.half_moves_last_capture1=0;
-                                    match self.box_index(self.chess_board1.board[i][j]) {
-                                        None => {},
-                                        Some(x) => {self.board2_black_capture[x] += 1},
-                                    }
-                                }else{
-                                    return Err(MoveError::PromotionProblem);
-                                }
-                            
-                                match self.upgrade_to1 {
-                                    Piece::Q => { self.upgrade_to1 = Piece::E; self.chess_board1.board[i][j] = Piece::UQ;
-                                        self.chess_board1.board[i_old][j_old] = Piece::E; 
-                                        self.white_active_1 = !self.white_active_1; return Ok(true)},
-                                    Piece::R => { self.upgrade_to1 = Piece::E; self.chess_board1.board[i][j] = Piece::UR;
-                                        self.chess_board1.board[i_old][j_old] = Piece::E;
-                                        self.white_active_1 = !self.white_active_1; return Ok(true)},
-                                    Piece::B => { self.upgrade_to1 = Piece::E;self.chess_board1.board[i][j] = Piece::UB;
-                                        self.chess_board1.board[i_old][j_old] = Piece::E;
-                                        self.white_active_1 = !self.white_active_1; return Ok(true)},
-                                    Piece::N => { self.upgrade_to1 = Piece::E;self.chess_board1.board[i][j] = Piece::UN;
-                                        self.chess_board1.board[i_old][j_old] = Piece::E;
-                                        self.white_active_1 = !self.white_active_1; return Ok(true)},
-                                    _ =>  {return Err(MoveError::PromotionProblem)},
-                            } 
-                            }
-                        }
-                    },
-                    Piece::p => 
-                    {
-                        if self.white_active_1 {
-                            return Err(MoveError::NotTurn)
-                        }else {
-                            self.pawn_in_last_turn_b1 = Some((i,j));
-                            if i==7 {
-                                if self.upgrade_to1 == Piece::q ||  self.upgrade_to1 == Piece::r  || self.upgrade_to1 == Piece::b  ||self.upgrade_to1 == Piece::n {
-                                    self.half_moves_last_capture1=0;
-                                    match self.box_index(self.chess_board1.board[i][j]) {
-                                        None => {},
-                                        Some(x) => {self.board2_white_capture[x] += 1},
-                                    }
-                                }else{
-                                    return Err(MoveError::PromotionProblem);
-                                }
-
-                                match self.upgrade_to1 {
-                                    Piece::q => {self.upgrade_to1 = Piece::E;self.chess_board1.board[i][j] = Piece::Uq;
-                                    self.chess_board1.board[i_old][j_old] = Piece::E;
-                                    self.white_active_1 = !self.white_active_1; return Ok(true)},
-                                    Piece::r => {self.upgrade_to1 = Piece::E;self.chess_board1.board[i][j] = Piece::Ur; 
-                                        self.chess_board1.board[i_old][j_old] = Piece::E;
-                                        self.white_active_1 = !self.white_active_1;return Ok(true)},
-                                    Piece::b => {self.upgrade_to1 = Piece::E;self.chess_board1.board[i][j] = Piece::Ub; 
-                                        self.chess_board1.board[i_old][j_old] = Piece::E;
-                                        self.white_active_1 = !self.white_active_1;return Ok(true)},
-                                    Piece::n => {self.upgrade_to1 = Piece::E;self.chess_board1.board[i][j] = Piece::Un;
-                                        self.chess_board1.board[i_old][j_old] = Piece::E; 
-                                        self.white_active_1 = !self.white_active_1;return Ok(true)},
-                                    _ =>  {return Err(MoveError::PromotionProblem)},
-                                } 
-                            }
-                        }
-                    },
-                    _ => {},
-                }
+    ///Reads a move or deploy in SAN and plays it for the active player of the board
+    /// # Arguments
+    /// * `board` - the board to play on
+    /// * `san` - the action in SAN, e.g. "Nf3", "exd5", "O-O", "e8=Q+" or "N@f3"
+    ///
+    ///Returns MoveError::NotLegal if the input does not describe exactly one legal action.
+    pub fn play_san(&mut self, board: BoardId, san: &str) -> Result<bool, MoveError> {
+        match crate::parse::san::from_san(self, board, san) {
+            Some(action) => self.play(action),
+            None => Err(MoveError::NotLegal),
+        }
+    }
 
-               
-                if !((self.white_active_1 && self.is_white(board1,i_old,j_old)) 
-                || (!self.white_active_1 && !self.is_white(board1,i_old,j_old))) {
-                    //not your turn!!!
-                    //println!("Siktir git hamlen degil!");
-                    return Err(MoveError::NotTurn)
-                }
-                //check to move location
-                let tmp = self.chess_board1.board[i][j];
-
-                //check if any piece is captured 
-                if tmp!=Piece::E {
-                    self.half_moves_last_capture1=0;
-                }else {
-                    self.half_moves_last_capture1+=1;
-                }
-               
+    ///Updates everything that depends on a successful move or deploy
+    /// # Arguments
+    /// * `board1` - the board the action was played on
+    fn after_action(&mut self, board1: bool) {
+        let white = self.get_white_active(board1);
+        if self.rules.termination == Termination::Checkmate && self.winner == Winner::N && !self.lookahead {
+            let seat = Seat::new(BoardId::from_board1(board1), Color::from_white(white));
+            if self.classify(seat) == CheckStatus::Checkmate {
+                self.winner = Winner::from_seat(seat.opponent());
+            }
+        }
+        //a capture changes the pools of the other board, so both boards are checked
+        if self.winner == Winner::N
+            && !self.lookahead
+            && !self.rules.draw_claim
+            && (self.is_draw(BoardId::Board1) || self.is_draw(BoardId::Board2))
+        {
+            self.winner = Winner::D;
+        }
+        let over = self.winner != Winner::N;
+        if let Some(c) = self.clock.as_mut() {
+            if over {
+                c.stop();
+            } else {
+                c.press(board1, white);
+            }
+        }
+    }
 
-                //check if pawn is moved
-                if self.chess_board1.board[i_old][j_old]==Piece::P {
+    ///Returns the position hash of a board
+    /// # Arguments
+    /// * `board` - the board
+    ///
+    ///The hash covers the pieces, the player to move, castling, en passant and the pools of both players of the board.
+    ///Equal positions have equal hashes, the hash is updated by every move and deploy.
+    pub fn get_hash(&self, board: BoardId) -> u64 {
+        if board.is_board1() {
+            self.hash1
+        } else {
+            self.hash2
+        }
+    }
 
-                    self.pawn_in_last_turn_b1 = Some((i,j));
-                    self.half_moves_last_capture1=0;
+    ///Computes the position hashes from scratch, needed after the fields of the game are changed directly
+    pub fn rehash(&mut self) {
+        let s = self.snapshot();
+        self.hash1 = zobrist::hash_board(&s, true);
+        self.hash2 = zobrist::hash_board(&s, false);
+    }
 
-                }else if self.chess_board1.board[i_old][j_old]==Piece::p {
+    ///Updates the position hashes with the changes of an action
+    /// # Arguments
+    /// * `before` - the state before the action
+    fn update_hashes(&mut self, before: &Snapshot) {
+        let after = self.snapshot();
+        self.hash1 = zobrist::update(before.hash1, before, &after, true);
+        self.hash2 = zobrist::update(before.hash2, before, &after, false);
+    }
 
-                    self.pawn_in_last_turn_b1 = Some((i,j));
-                    self.half_moves_last_capture1=0;
+    ///Returns how often the current position of a board has occurred in the game, 1 if it is new
+    /// # Arguments
+    /// * `board` - the board
+    ///
+    ///An action on the other board that leaves the board untouched does not repeat the position,
+    ///but a capture there changes the pools and so the position.
+    pub fn repetition_count(&self, board: BoardId) -> usize {
+        let board1 = board.is_board1();
+        let current = self.get_hash(board);
+        let hashes = self
+            .history
+            .get_entries()
+            .iter()
+            .map(|e| {
+                if board1 {
+                    e.get_before().hash1
+                } else {
+                    e.get_before().hash2
+                }
+            })
+            .chain(std::iter::once(current));
+        let mut last = None;
+        let mut count = 0;
+        for h in hashes {
+            if last != Some(h) && h == current {
+                count += 1;
+            }
+            last = Some(h);
+        }
+        count
+    }
 
-                //if the king has moved change king has moved flag
-                }else if self.chess_board1.board[i_old][j_old]==Piece::K{
-                    
-                    self.pawn_in_last_turn_b1 = None;
-                    self.chess_board1.white_k_moved = true;
-                
-                }else if self.chess_board1.board[i_old][j_old]==Piece::k{
+    ///Returns true if a board is drawn by repetition_draw or move_rule of the rules
+    /// # Arguments
+    /// * `board` - the board
+    ///
+    ///With draw_claim the draw has to be claimed by claim_draw, else the game ends at once.
+    pub fn is_draw(&self, board: BoardId) -> bool {
+        let repetition = match self.rules.repetition_draw {
+            Some(n) => self.repetition_count(board) >= n,
+            None => false,
+        };
+        let moves = match self.rules.move_rule {
+            Some(n) => self.get_half_moves(board.is_board1()) >= 2 * n,
+            None => false,
+        };
+        repetition || moves
+    }
 
-                    self.pawn_in_last_turn_b1 = None;
-                    self.chess_board1.black_k_moved = true;
-           
-                }else{
-                    //no king no pawn movement -> pawn in last turn = None
-                    self.pawn_in_last_turn_b1 = None;
-                }
+    ///Claims a draw for a player, returns true if the claim is valid and the game is drawn
+    /// # Arguments
+    /// * `seat` - the player that claims the draw
+    ///
+    ///Both players of a board can claim a draw if the board is drawn by repetition_draw or move_rule, see is_draw.
+    pub fn claim_draw(&mut self, seat: Seat) -> bool {
+        if self.winner != Winner::N || !self.is_draw(seat.board) {
+            return false;
+        }
+        self.set_draw();
+        true
+    }
 
-              
+    ///Returns the current state of the game without the clocks
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            chess_board1: self.chess_board1,
+            chess_board2: self.chess_board2,
+            pawn_in_last_turn_b1: self.pawn_in_last_turn_b1,
+            pawn_in_last_turn_b2: self.pawn_in_last_turn_b2,
+            white_active_1: self.white_active_1,
+            white_active_2: self.white_active_2,
+            upgrade_to1: self.upgrade_to1,
+            upgrade_to2: self.upgrade_to2,
+            half_moves_last_capture1: self.half_moves_last_capture1,
+            half_moves_last_capture2: self.half_moves_last_capture2,
+            movectr1: self.movectr1,
+            movectr2: self.movectr2,
+            board1_white_capture: self.board1_white_capture,
+            board1_black_capture: self.board1_black_capture,
+            board2_white_capture: self.board2_white_capture,
+            board2_black_capture: self.board2_black_capture,
+            winner: self.winner,
+            hash1: self.hash1,
+            hash2: self.hash2,
+        }
+    }
 
-                //check if black has moved
-                if !self.is_white(board1,i_old,j_old){
-                    self.movectr1+=1;
-                }
+    ///Sets the state of the game to a snapshot, the clocks and the history are not changed
+    /// # Arguments
+    /// * `s` - the state to restore, e.g. one of snapshot
+    pub fn restore(&mut self, s: &Snapshot) {
+        self.chess_board1 = s.chess_board1;
+        self.chess_board2 = s.chess_board2;
+        self.pawn_in_last_turn_b1 = s.pawn_in_last_turn_b1;
+        self.pawn_in_last_turn_b2 = s.pawn_in_last_turn_b2;
+        self.white_active_1 = s.white_active_1;
+        self.white_active_2 = s.white_active_2;
+        self.upgrade_to1 = s.upgrade_to1;
+        self.upgrade_to2 = s.upgrade_to2;
+        self.half_moves_last_capture1 = s.half_moves_last_capture1;
+        self.half_moves_last_capture2 = s.half_moves_last_capture2;
+        self.movectr1 = s.movectr1;
+        self.movectr2 = s.movectr2;
+        self.board1_white_capture = s.board1_white_capture;
+        self.board1_black_capture = s.board1_black_capture;
+        self.board2_white_capture = s.board2_white_capture;
+        self.board2_black_capture = s.board2_black_capture;
+        self.winner = s.winner;
+        self.hash1 = s.hash1;
+        self.hash2 = s.hash2;
+        self.reset_bitboards(true);
+        self.reset_bitboards(false);
+    }
 
-                //check if game  should end
-                if tmp==Piece::K || tmp==Piece::k  {
-                    self.finish_up(tmp,board1);
-                }
+    ///Takes back the latest move or deploy of the game, returns the action that was taken back
+    ///
+    /// Both boards share one history, so the latest action of either board is taken back.
+    /// Everything the action changed is restored, including the piece a capture sent to the teammate's pool
+    /// and the winner if the action ended the game. The clock of the board is given to the player to move.
+    pub fn undo(&mut self) -> Option<Action> {
+        let (action, before) = match self.history.undo() {
+            None => return None,
+            Some(e) => (e.action, *e.get_before()),
+        };
+        self.restore(&before);
+        self.after_takeback(action.get_board().is_board1());
+        Some(action)
+    }
 
+    ///Plays the latest action that was taken back with undo again, returns the action
+    ///
+    /// Any new move or deploy clears the actions that can be redone.
+    pub fn redo(&mut self) -> Option<Action> {
+        let (action, after) = match self.history.redo() {
+            None => return None,
+            Some(e) => (e.action, *e.get_after()),
+        };
+        self.restore(&after);
+        self.after_takeback(action.get_board().is_board1());
+        Some(action)
+    }
 
-                if self.check_patt(board1,true) || self.check_patt(board1,false) {
-                    self.winner = Winner::P;
-                    return Err(MoveError::AlreadyOver)
-                }
+    ///Gives the clock of the board to the active player after an undo or redo
+    fn after_takeback(&mut self, board1: bool) {
+        let white = self.get_white_active(board1);
+        let over = self.winner != Winner::N;
+        if let Some(c) = self.clock.as_mut() {
+            if over {
+                c.stop();
+            } else {
+                c.restart(board1, white);
+            }
+        }
+    }
 
+    ///Returns the played moves and deploys of both boards, the latest is the last
+    pub fn get_history(&self) -> &Vec<HistoryEntry> {
+        self.history.get_entries()
+    }
 
-                //send captured piece to your ally
-                match self.box_index(tmp) {
-                    None => (), //nothing to di
-                    Some(a) => {
-                        if self.is_white(board1,i,j) {
-                            self.board2_white_capture[a]+=1;
-                        }else{
-                            self.board2_black_capture[a]+=1;
-                        }
-                    },
-                }
+    ///Replays the first actions of the history on a new game, returns the game as it was after the action with the number seq
+    /// # Arguments
+    /// * `seq` - the number of the action, 0 for the state before the first action
+    ///
+    /// The new game has the rules and the history up to seq but no clock. Resignations and fallen flags are not actions,
+    /// so they are not replayed. Returns None if no action of the history has the number seq, e.g. it was taken back.
+    pub fn replay(&self, seq: usize) -> Option<ChessLogic> {
+        let log = self.history.get_entries();
+        let start = match log.first() {
+            Some(e) => *e.get_before(),
+            None => self.snapshot(),
+        };
+        let len = if seq == 0 {
+            0
+        } else {
+            log.iter().position(|e| e.get_seq() == seq)? + 1
+        };
+        ChessLogic::replay_log(&start, self.rules, &log[..len]).ok()
+    }
 
-                //apply move
-                self.chess_board1.board[i][j]=self.chess_board1.board[i_old][j_old];
-                self.chess_board1.board[i_old][j_old]=Piece::E;
-                self.white_active_1 = !self.white_active_1;
-                return Ok(true)
-
-            }else{
-                match self.chess_board2.board[i_old][j_old] {
-                    Piece::R => {
-                        if !self.white_active_2 {
-                            return Err(MoveError::NotTurn)
-                        }else{
-                            if i_old == 7 && j_old == 7 {
-                                self.chess_board2.white_rook_k_moved=true;
-                            }
-                            if i_old == 7 && j_old == 0 {
-                                self.chess_board2.white_rook_q_moved=true;
-                            }
-                        }
-                    },
-                    Piece::r => {
-                        if self.white_active_2 {
-                            return Err(MoveError::NotTurn) 
-                        }else{
-                            if i_old == 0 && j_old == 7 {
-                                self.chess_board2.black_rook_k_moved=true;
-                            }
-                            if i_old == 0 && j_old == 0 {
-                                self.chess_board2.black_rook_q_moved=true;
-                            }
-                        }
-                    },
-
-                    Piece::K => 
-                    {
-                        if !self.white_active_2 {
-                            return Err(MoveError::NotTurn)
-                        }else{
-                            self.pawn_in_last_turn_b2 = None;
-
-                            if i_old==7 && j_old==4
-                            && i== 7 && (j==6 || j==2)
-                            && !self.chess_board2.white_k_moved
-                            {
-                                //we are going castle
-                                self.chess_board2.board[i_old][j_old]=Piece::E;
-                                self.chess_board2.board[i][j] = Piece::K;
-                                if j==6 {
-                                    self.chess_board2.board[i][j-1] = Piece::R;
-                                    self.chess_board2.board[7][7] = Piece::E;
-                                }else{
-                                    self.chess_board2.board[i][j+1] = Piece::R;
-                                    self.chess_board2.board[7][0] = Piece::E;
-                                }
-                                self.chess_board2.white_k_moved = true;
-                                self.white_active_2 = !self.white_active_2;
+    ///Creates a game from a start position and plays the actions of a log in the order of their numbers
+    /// # Arguments
+    /// * `start` - the state before the first action
+    /// * `rules` - the rules the game was played with
+    /// * `log` - the actions, e.g. a part of get_history of another game
+    ///
+    /// The remaining times and the timestamps of the log are kept. Returns the error of the first action that cannot be played.
+    pub fn replay_log(start: &Snapshot, rules: Rules, log: &[HistoryEntry]) -> Result<ChessLogic, ActionError> {
+        let mut cl = ChessLogic::from_snapshot(start);
+        cl.rules = rules;
+        let mut entries: Vec<&HistoryEntry> = log.iter().collect();
+        entries.sort_by_key(|e| e.get_seq());
+        for e in entries {
+            cl.try_play(e.action)?;
+            cl.history.set_last_remaining(e.get_remaining());
+            cl.history.set_last_timestamp(e.get_timestamp());
+        }
+        cl.take_events();
+        Ok(cl)
+    }
 
-                                self.half_moves_last_capture2 += 1;
-                                return Ok(true)
-                            }
-                            self.chess_board2.white_k_moved = true;
-                        }
-                    },
-                    Piece::k => 
-                    {
-                        if self.white_active_2 {
-                            return Err(MoveError::NotTurn)
-                        }else{
-                            self.pawn_in_last_turn_b2 = None;
-                            if i_old==0 && j_old==4
-                            && i== 0 && (j==6 || j==2)
-                            && !self.chess_board2.black_k_moved
-                            {
-                                //we are going castle
-                                self.chess_board2.board[i_old][j_old]=Piece::E;
-                                self.chess_board2.board[i][j] = Piece::K;
-                                if j==6 {
-                                    self.chess_board2.board[i][j-1] = Piece::R;
-                                    self.chess_board2.board[0][7] = Piece::E;
-                                }else{
-                                    self.chess_board2.board[i][j+1] = Piece::R;
-                                    self.chess_board2.board[0][0] = Piece::E;
-                                }
-                                self.chess_board2.black_k_moved = true;
-                                self.white_active_2 = !self.white_active_2;
+    ///Sets the remaining time of the latest action in the history, needed if the times are read from a game record
+    /// # Arguments
+    /// * `remaining` - the remaining time of the player after the action
+    pub(crate) fn set_last_remaining(&mut self, remaining: Option<Duration>) {
+        self.history.set_last_remaining(remaining);
+    }
 
-                                self.half_moves_last_capture2 += 1;
-                                self.movectr2+=1;
-                                return Ok(true)
-                        }
-                        
-                        self.chess_board2.black_k_moved = true;
-                        }
-                    },
-                    Piece::P => 
-                    {
-                        if !self.white_active_2 {
-                            return Err(MoveError::NotTurn)
-                        }else{
-                            if i==0 {
-                                self.pawn_in_last_turn_b2 = Some((i,j));
-                                if self.upgrade_to2 == Piece::Q ||  self.upgrade_to2 == Piece::R  
-                                || self.upgrade_to2 == Piece::B || self.upgrade_to2 == Piece::N {
-                                    self.half_moves_last_capture2=0;
-                                    match self.box_index(self.chess_board2.board[i][j]) {
-                                        None => {},
-                                        Some(x) => {self.board1_black_capture[x] += 1},
-                                    }
-                                }else{
-                                    return Err(MoveError::PromotionProblem);
-                                }
-
-                                match self.upgrade_to2 {
-                                    Piece::Q => { self.upgrade_to2 = Piece::E;self.chess_board2.board[i][j] = Piece::UQ;
-                                        self.chess_board2.board[i_old][j_old] = Piece::E;
-                                        self.white_active_2 = !self.white_active_2;return Ok(true)},
-                                    Piece::R => { self.upgrade_to2 = Piece::E;self.chess_board2.board[i][j] = Piece::UR;
-                                        self.chess_board2.board[i_old][j_old] = Piece::E;
-                                        self.white_active_2 = !self.white_active_2;return Ok(true)},
-                                    Piece::B => { self.upgrade_to2 = Piece::E;self.chess_board2.board[i][j] = Piece::UB;
-                                        self.chess_board2.board[i_old][j_old] = Piece::E;
-                                        self.white_active_2 = !self.white_active_2;return Ok(true)},
-                                    Piece::N => { self.upgrade_to2 = Piece::E;self.chess_board2.board[i][j] = Piece::UN;
-                                        self.chess_board2.board[i_old][j_old] = Piece::E;
-                                        self.white_active_2 = !self.white_active_2;return Ok(true)},
-                                    _ =>  {return Err(MoveError::PromotionProblem)},
-                            } 
-                            }
-                        }
-                    },
-                    Piece::p => 
-                    {
-                        if self.white_active_2 {
-                            return Err(MoveError::NotTurn)
-                        }else{
-                            self.pawn_in_last_turn_b2 = Some((i,j));
-                            if i==7 {
-                                if self.upgrade_to2 == Piece::q || self.upgrade_to2 == Piece::r 
-                                || self.upgrade_to2 == Piece::b || self.upgrade_to2 == Piece::n {
-                                    self.half_moves_last_capture2=0;
-                                    match self.box_index(self.chess_board2.board[i][j]) {
-                                        None => {},
-                                        Some(x) => {self.board1_white_capture[x] += 1},
-                                    }
-                                }else{
-                                    return Err(MoveError::PromotionProblem);
-                                }
-
-                                match self.upgrade_to2 {
-                                    Piece::q => { self.upgrade_to2 = Piece::E;self.chess_board2.board[i][j] = Piece::Uq; 
-                                        self.chess_board2.board[i_old][j_old] = Piece::E;
-                                        self.white_active_2 = !self.white_active_2;return Ok(true)},
-                                    Piece::r => { self.upgrade_to2 = Piece::E;self.chess_board2.board[i][j] = Piece::Ur; 
-                                            self.chess_board2.board[i_old][j_old] = Piece::E;
-                                            self.white_active_2 = !self.white_active_2;return Ok(true)},
-                                    Piece::b => { self.upgrade_to2 = Piece::E;self.chess_board2.board[i][j] = Piece::Ub; 
-                                        self.chess_board2.board[i_old][j_old] = Piece::E;
-                                        self.white_active_2 = !self.white_active_2;return Ok(true)},
-                                    Piece::n => { self.upgrade_to2 = Piece::E;self.chess_board2.board[i][j] = Piece::Un;
-                                        self.chess_board2.board[i_old][j_old] = Piece::E; 
-                                        self.white_active_2 = !self.white_active_2;return Ok(true)},
-                                    _ =>  {return Err(MoveError::PromotionProblem)},
-                                } 
-                        }
-                    }
-                    },
-                    _ => {},
-                }
+    ///Plays the game with a clock, every player gets the same time control
+    /// # Arguments
+    /// * `tc` - time control of the players
+    /// * `source` - the source of the time, e.g. a SystemClock
+    ///
+    /// The clocks are stopped until start_clock is called or until the first move on a board is made.
+    pub fn set_clock(&mut self, tc: TimeControl, source: Rc<dyn ClockSource>) {
+        self.clock = Some(GameClock::new(tc, source));
+    }
 
-                if !( (self.white_active_2 && self.is_white(board1,i_old,j_old)) 
-                || (!self.white_active_2 && !self.is_white(board1,i_old,j_old))) {
-                    //not your turn!!!
-                    //println!("Siktir git hamlen degil!");
-                    return Err(MoveError::NotTurn)
-                }
+    ///Returns the clocks of the game, None if the game is played without a clock
+    pub fn get_clock(&self) -> Option<&GameClock> {
+        self.clock.as_ref()
+    }
 
+    ///Same as get_clock but the clocks can be changed, e.g. to start a single board
+    pub fn get_clock_mut(&mut self) -> Option<&mut GameClock> {
+        self.clock.as_mut()
+    }
 
-                let tmp = self.chess_board2.board[i][j];
+    ///Sets the clocks of the game, e.g. clocks created from a saved state
+    /// # Arguments
+    /// * `clock` - the clocks of all players
+    pub fn set_game_clock(&mut self, clock: GameClock) {
+        self.clock = Some(clock);
+    }
 
-                //check if any piece is captured 
-                if tmp!=Piece::E {
-                    self.half_moves_last_capture2=0;
-                }else {
-                    self.half_moves_last_capture2+=1;
-                }
+    ///Starts the clocks of the active players on both boards
+    pub fn start_clock(&mut self) {
+        let w1 = self.white_active_1;
+        let w2 = self.white_active_2;
+        if let Some(c) = self.clock.as_mut() {
+            c.start(true, w1);
+            c.start(false, w2);
+        }
+    }
 
-                //check if pawn is moved
-                if self.chess_board2.board[i_old][j_old]==Piece::P {
-                    self.pawn_in_last_turn_b2 = Some((i,j));
-                    self.half_moves_last_capture2=0;
-                }else if self.chess_board2.board[i_old][j_old]==Piece::K {
-                    self.pawn_in_last_turn_b2 = None;
-                    self.chess_board2.white_k_moved = true;
-                }else if self.chess_board2.board[i_old][j_old]==Piece::k {
-                    self.pawn_in_last_turn_b2 = None;
-                    self.chess_board2.black_k_moved = true;
-                }else{
-                    self.pawn_in_last_turn_b2 = None;
-                }
+    ///Stops the clocks of both boards, e.g. to adjourn the game, start_clock starts them again
+    pub fn stop_clock(&mut self) {
+        if let Some(c) = self.clock.as_mut() {
+            c.stop();
+        }
+    }
 
-                //check if black has moved
-                if !self.is_white(board1,i_old,j_old){
-                    self.movectr2+=1;
-                }
+    ///Ends the game as a draw, e.g. if the players agree to a draw, the winner is set to D and the clocks are stopped
+    pub fn set_draw(&mut self) {
+        if self.winner != Winner::D {
+            self.winner = Winner::D;
+            self.emit(vec![Event::GameEnded { winner: Winner::D }]);
+        }
+        self.stop_clock();
+    }
 
- 
+    ///Returns the events since the last call and removes them from the game
+    ///
+    /// Every successful move and deploy adds its events, e.g. a capture adds Captured, Moved, SentToPool and TurnChanged.
+    pub fn take_events(&mut self) -> Vec<Event> {
+        std::mem::take(&mut self.events)
+    }
 
-                //check if game  should end
-                if tmp==Piece::K || tmp==Piece::k  {
-                    self.finish_up(tmp,board1);
-                }
+    ///Registers a callback that receives every event when it happens, the events are also collected for take_events
+    /// # Arguments
+    /// * `observer` - the callback
+    pub fn subscribe(&mut self, observer: Box<dyn FnMut(&Event)>) {
+        self.observers.push(observer);
+    }
 
-                if self.check_patt(board1,true) || self.check_patt(board1,false) {
-                    self.winner = Winner::P;
-                    return Err(MoveError::AlreadyOver)
-                }
-                
-                match self.box_index(tmp) {
-                    None => {}, //nothing to di
-                    Some(a) => {
-                        if self.is_white(board1,i,j) {
-                            self.board1_white_capture[a]+=1;
-                        }else{
-                            self.board1_black_capture[a]+=1;
-                        }
-                    },
-                }
-                self.chess_board2.board[i][j]=self.chess_board2.board[i_old][j_old];
-                self.chess_board2.board[i_old][j_old]=Piece::E;
-                self.white_active_2 = !self.white_active_2;
-                return Ok(true)
+    ///Sends events to the observers and collects them, the copies made to look ahead have no events
+    fn emit(&mut self, events: Vec<Event>) {
+        if self.lookahead {
+            return;
+        }
+        for e in events.iter() {
+            for o in self.observers.iter_mut() {
+                o(e);
             }
-        }else{
-            println!("Move not legal!");
-            return Err(MoveError::NotLegal)
         }
+        self.events.extend(events);
+    }
+
+    ///Returns the remaining time of a player, None if there is no clock
+    /// # Arguments
+    /// * `board1` - true if board1, false if board2
+    /// * `white` - true if white, false if black
+    pub fn get_remaining_time(&self, board1: bool, white: bool) -> Option<Duration> {
+        self.clock.as_ref().map(|c| c.remaining(board1, white))
+    }
+
+    ///Checks if a flag has fallen on any board, if so the opponent of the player wins
+    ///
+    /// Returns true if the game is over. It is called by movemaker and deploy_piece,
+    /// but a server should also call it periodically since a flag falls without a move.
+    pub fn check_flags(&mut self) -> bool {
+        if self.winner != Winner::N {
+            return true;
+        }
+        let flags = match self.clock.as_ref() {
+            None => return false,
+            Some(c) => (c.flagged(true), c.flagged(false)),
+        };
+        match flags {
+            (Some(white), _) => self.resign(true, white),
+            (None, Some(white)) => self.resign(false, white),
+            (None, None) => return false,
+        }
+        if let Some(c) = self.clock.as_mut() {
+            c.stop();
+        }
+        true
     }
 
     ///Get the index of the piece on the captured pieces array
-    /// # Arguments 
+    /// # Arguments
     /// * `piece` - the index of the given piece
-    /// 
-    /// The upgraded pieces will be sent over as pawns,
+    ///
+    /// The upgraded pieces will be sent over as pawns, unless the rules say that promoted pieces do not revert
     /// Did not want to import hashtable for such a trivial example
-    pub fn box_index(&self, piece:Piece) -> Option<usize> {
+    pub fn box_index(&self, piece: Piece) -> Option<usize> {
         //P-R-N-B-Q
         match piece {
-            Piece::P | Piece::p  => Some(0),
-            Piece::K | Piece::k | Piece::L  | Piece::E => None ,
-            Piece::N | Piece::n  => Some(2),
-            Piece::R | Piece::r  => Some(1),
-            Piece::B | Piece::b  => Some(3),
-            Piece::Q | Piece::q  => Some(4),
-            Piece::Un | Piece::UN
-            | Piece::Ur | Piece::UR
-            | Piece::Ub | Piece::UB
-            | Piece::Uq | Piece::UQ => Some(0),
-
+            Piece::P | Piece::p => Some(0),
+            Piece::K | Piece::k | Piece::L | Piece::E => None,
+            Piece::N | Piece::n => Some(2),
+            Piece::R | Piece::r => Some(1),
+            Piece::B | Piece::b => Some(3),
+            Piece::Q | Piece::q => Some(4),
+            //passed as the piece itself if the house rules say so
+            Piece::Un | Piece::UN if !self.rules.promoted_revert => Some(2),
+            Piece::Ur | Piece::UR if !self.rules.promoted_revert => Some(1),
+            Piece::Ub | Piece::UB if !self.rules.promoted_revert => Some(3),
+            Piece::Uq | Piece::UQ if !self.rules.promoted_revert => Some(4),
+            Piece::Un | Piece::UN | Piece::Ur | Piece::UR | Piece::Ub | Piece::UB | Piece::Uq | Piece::UQ => Some(0),
         }
     }
 
-
     ///Sets the winner, should only be called through movemaker/deploy piece
-    /// # Arguments 
-    /// * `p` - a Piece to indicate which King is captures
+    /// # Arguments
+    /// * `piece` - a Piece to indicate which King is captures
     /// * `board1` - true if board1 else false
-    fn finish_up(&mut self, p:Piece, board1:bool){
-        if p==Piece::K {
+    fn finish_up(&mut self, piece: Piece, board1: bool) {
+        if piece == Piece::K {
             if board1 {
-                self.winner=Winner::B1;
-            }else{
-                self.winner=Winner::B2;
+                self.winner = Winner::B1;
+            } else {
+                self.winner = Winner::B2;
             }
-        }else if p==Piece::k {
+        } else if piece == Piece::k {
             if board1 {
-                self.winner=Winner::W1;
-            }else{
-                self.winner=Winner::W2;
+                self.winner = Winner::W1;
+            } else {
+                self.winner = Winner::W2;
             }
         }
     }
@@ -2119,81 +2665,89 @@ impl ChessLogic {
     /// # Arguments
     /// * `board1` - true if a player from board1 resigns else false
     /// * `white` - true if white resigns, else false
-    pub fn resign(&mut self,board1:bool,white:bool){
+    pub fn resign(&mut self, board1: bool, white: bool) {
+        let over = self.winner != Winner::N;
         if board1 {
             if white {
-                self.winner=Winner::B1;
-            }else{
-                self.winner=Winner::W1;
+                self.winner = Winner::B1;
+            } else {
+                self.winner = Winner::W1;
             }
-        }else{
+        } else {
             if white {
-                self.winner=Winner::B2;
-            }else{
-                self.winner=Winner::W2;
+                self.winner = Winner::B2;
+            } else {
+                self.winner = Winner::W2;
             }
         }
+        if !over {
+            self.emit(vec![Event::GameEnded { winner: self.winner }]);
+        }
     }
 
     ///A function to check if there is a stalemate and sets the winner to P(att)
     /// # Arguments
-    /// * `board1` - true if a  board1 else false 
+    /// * `board1` - true if a  board1 else false
     /// * `white` - true if white  else false
-    pub fn check_patt(&mut self,board1:bool, white:bool) -> bool {
-        let mut pic = Piece::E;
-        if white { pic = Piece::K; } else { pic = Piece::k; }
-
-        for i in 0..8 {
-            for j in 0..8 {
-                if white {
-                    if self.is_white(board1,i,j) && self.get_board(board1).board[i][j]!=Piece::K {
-                        if self.get_legal_moves(board1,i,j)!=Vec::new() {
-                            return false
-                        }
-                        
-                    }
-                }else{
-                    if !self.is_white(board1,i,j) &&  self.get_board(board1).board[i][j]!=Piece::k {
-                        if self.get_legal_moves(board1,i,j)!=Vec::new() {
-                            return false
-                        }
-                    }
-                }
+    pub fn check_patt(&mut self, board1: bool, white: bool) -> bool {
+        let pic = if white { Piece::K } else { Piece::k };
+
+        let bb = self.bitboards(board1);
+        let others = bb.own(white) & !bb.pieces(Kind::King, white);
+        let mut moves = Vec::new();
+        for (i, j) in bitboard::squares(others) {
+            self.moves_with(&bb, board1, i, j, &mut moves);
+            if !moves.is_empty() {
+                return false;
+            }
+        }
+        //the king can only move to squares that are not attacked, without the king on its square
+        for (i, j) in bitboard::squares(bb.pieces(Kind::King, white)) {
+            let mut without = bb;
+            without.occupied &= !bitboard::bit(i, j);
+            if bitboard::squares(bb.targets(Kind::King, white, i, j)).any(|(a, b)| !without.attacked(a, b, !white)) {
+                return false;
             }
         }
 
-        match self.find_piece(pic,board1) {
+        match self.find_piece(pic, board1) {
             None => return false,
-            Some((i,j)) => {
-                if !self.is_attacked(board1,white,i,j) && 
-                self.pool_empty(board1,white){
-                    self.winner=Winner::P;
-                    return true
+            Some((i, j)) => {
+                if !self.is_attacked(board1, white, i, j) && self.pool_empty(board1, white) {
+                    self.winner = Winner::P;
+                    return true;
                 }
-            },
+            }
         }
-        return false
+        false
     }
 
-
-    ///A function to check if the pool for a player is empty 
+    ///A function to check if the pool for a player is empty
     ///Needed for stalemate check
     /// # Arguments
-    /// * `board1` - true if a  board1 else false 
+    /// * `board1` - true if a  board1 else false
     /// * `white` - true if white  else false
-    fn pool_empty(&self, board1:bool,white:bool) -> bool {
+    fn pool_empty(&self, board1: bool, white: bool) -> bool {
         for i in 0..5 {
             if board1 {
                 if white {
-                   if self.board1_white_capture[i]!=0 {return false}
-                }else{
-                   if self.board1_black_capture[i]!=0 {return false}
+                    if self.board1_white_capture[i] != 0 {
+                        return false;
+                    }
+                } else {
+                    if self.board1_black_capture[i] != 0 {
+                        return false;
+                    }
                 }
-            }else{
+            } else {
                 if white {
-                    if self.board2_white_capture[i]!=0 {return false}
-                }else{
-                    if self.board2_black_capture[i]!=0 {return false}
+                    if self.board2_white_capture[i] != 0 {
+                        return false;
+                    }
+                } else {
+                    if self.board2_black_capture[i] != 0 {
+                        return false;
+                    }
                 }
             }
         }
@@ -2203,103 +2757,106 @@ impl ChessLogic {
     ///A function to send a piece from one teammate to another
     ///Needed for stalemate check
     /// # Arguments
-    /// * `board1` - true if the player is from board1 else false 
+    /// * `board1` - true if the player is from board1 else false
     /// * `white` - true if player is white  else false
-    fn recv_piece(&mut self, board1:bool, white:bool,p:Piece){
-        let i = self.box_index(p);
-        if let Some(x) = i { 
+    #[cfg(test)]
+    fn recv_piece(&mut self, board1: bool, white: bool, piece: Piece) {
+        let i = self.box_index(piece);
+        if let Some(x) = i {
+            let board = BoardId::from_board1(board1);
+            self.emit(vec![Event::SentToPool {
+                board,
+                color: Color::from_white(white),
+                piece: self.pool_piece(x, white),
+            }]);
             if board1 {
-                if white { 
-                    self.board1_white_capture[x]+=1;
-                }else{
-                    self.board1_black_capture[x]+=1;
+                if white {
+                    self.board1_white_capture[x] += 1;
+                } else {
+                    self.board1_black_capture[x] += 1;
                 }
-            }else{
+            } else {
                 if white {
-                    self.board2_white_capture[x]+=1;
-                }else{
-                    self.board2_black_capture[x]+=1;
+                    self.board2_white_capture[x] += 1;
+                } else {
+                    self.board2_black_capture[x] += 1;
                 }
             }
         }
+        self.rehash();
     }
 
     ///A function to find a piece on a board, returns the first piece found if there are more than 1 of the same type
     ///Inteded use is to find the kings
     /// # Arguments
-    /// * `p` - the piece to be searched
-    /// * `board1` - true if the player is from board1 else false 
-    pub fn find_piece(&self, p: Piece, board1:bool) -> Option<(usize,usize)> {
+    /// * `piece` - the piece to be searched
+    /// * `board1` - true if the player is from board1 else false
+    pub fn find_piece(&self, piece: Piece, board1: bool) -> Option<(usize, usize)> {
         for i in 0..8 {
             for j in 0..8 {
                 if board1 {
-                    if self.chess_board1.board[i][j] == p {
-                        return Some((i,j))
-                     }
-                }else{
-                    if self.chess_board2.board[i][j] == p {
-                        return Some((i,j))
+                    if self.chess_board1.board[i][j] == piece {
+                        return Some((i, j));
+                    }
+                } else {
+                    if self.chess_board2.board[i][j] == piece {
+                        return Some((i, j));
                     }
                 }
-              
             }
         }
-        None 
+        None
     }
 
     ///Returns the castling rights
     /// # Arguments
-    /// * `board1` - true if board1 else false 
-    pub fn get_castling_rights(&self,board1:bool) -> [bool;4] {
-        let mut x = [false;4];
+    /// * `board1` - true if board1 else false
+    pub fn get_castling_rights(&self, board1: bool) -> [bool; 4] {
+        let mut x = [false; 4];
         x[0] = !self.get_board_n(board1).white_rook_k_moved && !self.get_board_n(board1).white_k_moved;
         x[1] = !self.get_board_n(board1).white_rook_q_moved && !self.get_board_n(board1).black_k_moved;
         x[2] = !self.get_board_n(board1).black_rook_k_moved && !self.get_board_n(board1).white_k_moved;
         x[3] = !self.get_board_n(board1).black_rook_q_moved && !self.get_board_n(board1).black_k_moved;
-        return x
+        x
     }
 
     ///A function the set the promotion field (to_upgrade1-2), corrects type into some extent
     /// # Arguments
-    /// * `board1` - true if board1 else false 
-    /// * `p` - the piece to be promoted to pawn -> p:Piece
-    pub fn set_promotion(&mut self, board1:bool, p:Piece) -> bool {
+    /// * `board1` - true if board1 else false
+    /// * `piece` - the piece to be promoted to pawn -> piece:Piece
+    ///
+    ///The colour of the piece does not matter, the pawn is promoted to a piece of its own colour.
+    pub fn set_promotion(&mut self, board1: bool, piece: Piece) -> bool {
+        let ind = match piece {
+            Piece::R | Piece::UR | Piece::r | Piece::Ur => 1,
+            Piece::N | Piece::UN | Piece::n | Piece::Un => 2,
+            Piece::B | Piece::UB | Piece::b | Piece::Ub => 3,
+            Piece::Q | Piece::UQ | Piece::q | Piece::Uq => 4,
+            _ => return false,
+        };
+        let promotion = self.pool_piece(ind, piece.is_white());
         if board1 {
-            match p {
-                Piece::Q | Piece::UQ => self.upgrade_to1 = Piece::Q, 
-                Piece::B | Piece::UB => self.upgrade_to1 = Piece::B, 
-                Piece::R | Piece::UR => self.upgrade_to1 = Piece::R, 
-                Piece::N | Piece::UN => self.upgrade_to1 = Piece::N, 
-                _ => {return false},
-            }
-        }else{
-            match p {
-                Piece::q | Piece::Uq => self.upgrade_to2 = Piece::q, 
-                Piece::b | Piece::Ub => self.upgrade_to2 = Piece::b, 
-                Piece::r | Piece::Ur => self.upgrade_to2 = Piece::r, 
-                Piece::n | Piece::Un => self.upgrade_to2 = Piece::n, 
-                _ => {return false},
-            }
+            self.upgrade_to1 = promotion;
+        } else {
+            self.upgrade_to2 = promotion;
         }
-        return true 
+        true
     }
 
     /// A function to reset the promotion to Piece::E
     /// # Arguments
-    /// * `board1` - true if board1 else false 
-    pub fn reset_promotion(&mut self, board1:bool) {
+    /// * `board1` - true if board1 else false
+    pub fn reset_promotion(&mut self, board1: bool) {
         if board1 {
-            self.upgrade_to1=Piece::E;
-        }else{
-            self.upgrade_to2=Piece::E;
+            self.upgrade_to1 = Piece::E;
+        } else {
+            self.upgrade_to2 = Piece::E;
         }
     }
-    
 }
 
-
-
-
-
-
-
+impl Default for ChessLogic {
+    fn default() -> ChessLogic {
+        ChessLogic::new()
+    }
+}
@@ -0,0 +1,81 @@
+use crate::infoCourier::infoCourier::read_bfen;
+use crate::logic::history::Action;
+use crate::logic::rules::{Rules, Termination};
+use crate::logic::types::{BoardId, Color, Seat};
+use crate::logic::ChessLogic;
+
+///The BFEN of the initial position, used for the board that is not counted
+const START: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR[] w KQkq - 0 1";
+
+///Creates a game with a position on board1 and the initial position on board2
+/// # Arguments
+/// * `bfen` - the BFEN or FEN of board1, e.g. "2k5/8/8/8/8/8/8/4K3[QRBNPqrbnp] w - - 0 1"
+/// * `rules` - the rules the moves are counted with
+///
+/// Returns None if the input is not legal.
+pub fn position(bfen: &str, rules: Rules) -> Option<ChessLogic> {
+    position_on(bfen, BoardId::Board1, rules)
+}
+
+///Same as position, but the position is on the given board and the other board has the initial position
+/// # Arguments
+/// * `bfen` - the BFEN or FEN of the board
+/// * `board` - the board of the position
+/// * `rules` - the rules the moves are counted with
+pub fn position_on(bfen: &str, board: BoardId, rules: Rules) -> Option<ChessLogic> {
+    let (b1, b2) = match board {
+        BoardId::Board1 => (bfen.to_string(), START.to_string()),
+        BoardId::Board2 => (START.to_string(), bfen.to_string()),
+    };
+    let mut cl = read_bfen(&b1, &b2)?;
+    cl.set_rules(rules);
+    Some(cl)
+}
+
+///Returns the number of positions after depth actions on a board, the players of the board take turns
+/// # Arguments
+/// * `cl` - the game
+/// * `board` - the board, the other board does not move
+/// * `depth` - the number of actions
+///
+/// Moves and deploys are counted like get_all_legal_actions creates them, so a promotion counts once per piece.
+/// With Termination::Checkmate actions that leave the own king in check are not counted.
+/// Captured pieces go to the other board, the pools of the board only shrink.
+pub fn perft(cl: &ChessLogic, board: BoardId, depth: usize) -> u64 {
+    count(&cl.preview_base(), board, depth)
+}
+
+///Same as perft, but the number is split by the first action, to find the action whose numbers are wrong
+/// # Arguments
+/// * `cl` - the game
+/// * `board` - the board
+/// * `depth` - the number of actions, at least 1
+pub fn divide(cl: &ChessLogic, board: BoardId, depth: usize) -> Vec<(Action, u64)> {
+    let depth = depth.max(1);
+    children(&cl.preview_base(), board)
+        .map(|(action, child)| (action, count(&child, board, depth - 1)))
+        .collect()
+}
+
+///Counts the positions below a game
+fn count(cl: &ChessLogic, board: BoardId, depth: usize) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    children(cl, board)
+        .map(|(_, child)| count(&child, board, depth - 1))
+        .sum()
+}
+
+///Returns the legal actions of the player to move on a board with the game after each action
+fn children<'a>(cl: &'a ChessLogic, board: BoardId) -> impl Iterator<Item = (Action, ChessLogic)> + 'a {
+    let seat = Seat::new(board, Color::from_white(cl.get_white_active(board.is_board1())));
+    let checkmate = cl.get_rules().termination == Termination::Checkmate;
+    cl.get_all_legal_actions(seat).into_iter().filter_map(move |action| {
+        let mut child = cl.preview_base();
+        match child.try_play(action) {
+            Ok(_) if !(checkmate && child.is_in_check(seat)) => Some((action, child)),
+            _ => None,
+        }
+    })
+}
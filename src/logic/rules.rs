@@ -0,0 +1,80 @@
+#[derive(Clone, Copy, PartialEq, Debug)]
+///Enum class for the way a board ends
+///
+///KingCapture -> pinned pieces can move, the board ends when a king is captured
+///Checkmate -> a move or deploy that leaves the own king in check is not legal, the board ends with a checkmate
+pub enum Termination {
+    KingCapture,
+    Checkmate,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+///Enum class for the deploys that reset the counter of the N-move rule
+///
+///Never -> a deploy never resets the counter
+///Pawns -> a pawn deploy resets the counter like a pawn move
+///Always -> every deploy resets the counter
+pub enum DropReset {
+    Never,
+    Pawns,
+    Always,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+///House rules of a bughouse game, the default rules are the rules of the README
+///
+///A check that can be blocked by a deploy is never a checkmate, since the teammate can still send a piece.
+pub struct Rules {
+    ///How a board ends, default is KingCapture
+    pub termination: Termination,
+    ///True if pawns can be deployed on the first rank of their colour, default is true
+    pub pawn_drop_first_rank: bool,
+    ///True if a deploy can checkmate, default is true
+    pub drop_mate: bool,
+    ///True if a captured promoted piece is sent over as a pawn, default is true
+    pub promoted_revert: bool,
+    ///True if a rook deployed on its home square restores castling, default is true
+    pub rook_drop_castling: bool,
+    ///True if a pawn reaching the last rank without a promotion piece becomes a queen, default is false
+    pub auto_queen: bool,
+    ///Number of times a position has to occur on a board to draw the game, None if repetitions do not end the game, default is None
+    pub repetition_draw: Option<usize>,
+    ///Number of moves of both players on a board without capture or pawn move that draw the game, e.g. 50, None if the game is not drawn, default is None
+    pub move_rule: Option<usize>,
+    ///The deploys that reset the counter of move_rule, default is Pawns
+    pub drop_reset: DropReset,
+    ///True if the draws of repetition_draw and move_rule have to be claimed by a player, false if they end the game at once, default is false
+    pub draw_claim: bool,
+}
+
+impl Rules {
+    ///Creates the default rules
+    pub fn new() -> Rules {
+        Rules {
+            termination: Termination::KingCapture,
+            pawn_drop_first_rank: true,
+            drop_mate: true,
+            promoted_revert: true,
+            rook_drop_castling: true,
+            auto_queen: false,
+            repetition_draw: None,
+            move_rule: None,
+            drop_reset: DropReset::Pawns,
+            draw_claim: false,
+        }
+    }
+
+    ///Creates the rules of classical bughouse tournaments, only legal moves and the board ends with a checkmate
+    pub fn classical() -> Rules {
+        Rules {
+            termination: Termination::Checkmate,
+            ..Rules::new()
+        }
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Rules {
+        Rules::new()
+    }
+}
@@ -1,13 +1,13 @@
-#[cfg(test)]
-use crate::logic::*;
-use crate::parse::parser;
 use crate::infoCourier::infoCourier::gen_fen;
 use crate::infoCourier::infoCourier::*;
-use crate::util::contains;
-use crate::logic::MoveError;
 use crate::logic::board::get_init_array;
-
-
+use crate::logic::history::Action;
+use crate::logic::types::{BoardId, Color, Drop, Move, Seat, Square, Team, TeamResult};
+use crate::logic::MoveError;
+use crate::logic::*;
+use crate::parse::parser;
+use crate::parse::san;
+use crate::util::contains;
 
 #[test]
 fn it_works() {
@@ -15,942 +15,2469 @@ fn it_works() {
 }
 
 #[test]
-fn deploying_pieces_W_fen(){
-    let mut cl =ChessLogic::new();
-    cl.recv_piece(true,true,Piece::P);
-    assert!(cl.deploy_piece(true,true,Piece::P,4,4)==Ok(true));
-    let mut a = gen_fen(&mut cl);
+fn deploying_pieces_w_fen() {
+    let mut cl = ChessLogic::new();
+    cl.recv_piece(true, true, Piece::P);
+    assert!(cl.deploy_piece(true, true, Piece::P, 4, 4) == Ok(true));
+    let a = gen_fen(&cl);
     cl.print(true);
-    let mut st = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPPPPPP/RNBQKBNR b KQkq e3 0 1".to_string();
-    match a {
-        (b,c) => {
-            println!("{}",b);println!("{}",c);
-            assert_eq!(st,b); 
-            },
-        _ => (),
-    }
-    cl.recv_piece(true,false,Piece::r);
-    assert!(cl.deploy_piece(true,false,Piece::r,3,4)==Ok(true));
-    let mut a = gen_fen(&mut cl);
+    //a deployed pawn cannot be captured en passant
+    let st = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1".to_string();
+    let (b, c) = a;
+    println!("{}", b);
+    println!("{}", c);
+    assert_eq!(st, b);
+    cl.recv_piece(true, false, Piece::r);
+    assert!(cl.deploy_piece(true, false, Piece::r, 3, 4) == Ok(true));
+    let a = gen_fen(&cl);
     cl.print(true);
-    let mut st = "rnbqkbnr/pppppppp/8/4r3/4P3/8/PPPPPPPP/RNBQKBNR w KQkq - 1 2".to_string();
-    match a {
-        (b,c) => {
-            println!("{}",b);println!("{}",c);
-            assert_eq!(st,b); 
-            },
-        _ => (),
-    }
+    let st = "rnbqkbnr/pppppppp/8/4r3/4P3/8/PPPPPPPP/RNBQKBNR w KQkq - 1 2".to_string();
+    let (b, c) = a;
+    println!("{}", b);
+    println!("{}", c);
+    assert_eq!(st, b);
 }
 
 #[test]
-fn read_fn(){
+fn read_fn() {
     let mut x = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq e3 0 1".to_string();
     let mut arr = "".to_string();
-    let mut a = read_fen(&x,&"".to_string(),&arr,&arr,&arr,&arr);
+    assert!(read_fen(&x, "", &arr, &arr, &arr, &arr).is_none());
     x = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b ---- - 0 1".to_string();
     arr = "".to_string();
-    if let Some(mut a) = read_fen(&x,&x.to_string(),&arr,&arr,&arr,&arr){
-        a.chess_board1.print_board();
-        assert!(a.chess_board1.board==get_init_array());
-        assert!(a.chess_board2.board==get_init_array());
-    }else{
-        assert!(false);
-    }
-  
+    let a = read_fen(&x, &x, &arr, &arr, &arr, &arr).unwrap();
+    a.chess_board1.print_board();
+    assert!(a.chess_board1.board == get_init_array());
+    assert!(a.chess_board2.board == get_init_array());
 }
 
 #[test]
-fn is_move_legal(){
+fn is_move_legal() {
     let mut cl = ChessLogic::new();
     cl.white_active_1 = false;
-    assert!(cl.movemaker(true,1,1,3,1)==Ok(true));
+    assert!(cl.movemaker(true, 1, 1, 3, 1) == Ok(true));
     cl.white_active_1 = false;
-    assert!(cl.movemaker(true,1,7,3,7)==Ok(true));
+    assert!(cl.movemaker(true, 1, 7, 3, 7) == Ok(true));
     cl.white_active_1 = false;
-    assert!(cl.movemaker(true,0,1,2,2)==Ok(true));
+    assert!(cl.movemaker(true, 0, 1, 2, 2) == Ok(true));
     cl.white_active_1 = false;
-    assert!(cl.movemaker(true,1,1,4,1)==Err(MoveError::NotLegal));
+    assert!(cl.movemaker(true, 1, 1, 4, 1) == Err(MoveError::NotLegal));
     cl.white_active_1 = false;
-    assert!(cl.movemaker(true,0,0,1,0)==Err(MoveError::NotLegal));
+    assert!(cl.movemaker(true, 0, 0, 1, 0) == Err(MoveError::NotLegal));
     cl.white_active_1 = false;
-    assert!(cl.movemaker(true,0,0,2,0)==Err(MoveError::NotLegal));
+    assert!(cl.movemaker(true, 0, 0, 2, 0) == Err(MoveError::NotLegal));
 }
 
-
 #[test]
-fn pinned_piece(){
+fn pinned_piece() {
     let mut cl = ChessLogic::new();
     cl.all_empty(true);
-    cl.set_piece(true,Piece::R,0,0);
-    cl.set_piece(true,Piece::r,4,0);
-    cl.set_piece(true,Piece::k,5,0);
+    cl.set_piece(true, Piece::R, 0, 0);
+    cl.set_piece(true, Piece::r, 4, 0);
+    cl.set_piece(true, Piece::k, 5, 0);
     //is allowed bcs we are playing tandem
     cl.white_active_1 = false;
-    assert!(cl.movemaker(true,4,0,4,7)==Ok(true));
+    assert!(cl.movemaker(true, 4, 0, 4, 7) == Ok(true));
     cl.all_empty(true);
-    cl.set_piece(true,Piece::R,0,0);
-    cl.set_piece(true,Piece::r,4,0);
-    cl.set_piece(true,Piece::k,5,0);
+    cl.set_piece(true, Piece::R, 0, 0);
+    cl.set_piece(true, Piece::r, 4, 0);
+    cl.set_piece(true, Piece::k, 5, 0);
     cl.white_active_1 = false;
-    assert!(cl.movemaker(true,5,0,5,1)==Ok(true));
-    let vec = cl.get_legal_moves(true,5,0);
-    cl.print_w_legal(true,&vec);
+    assert!(cl.movemaker(true, 5, 0, 5, 1) == Ok(true));
+    let vec = cl.get_legal_moves(true, 5, 0);
+    cl.print_w_legal(true, &vec);
     cl.all_empty(true);
-    cl.set_piece(true,Piece::R,0,0);
-    cl.set_piece(true,Piece::r,4,0);
-    cl.set_piece(true,Piece::k,5,0);
+    cl.set_piece(true, Piece::R, 0, 0);
+    cl.set_piece(true, Piece::r, 4, 0);
+    cl.set_piece(true, Piece::k, 5, 0);
     cl.white_active_1 = false;
-    assert!(cl.movemaker(true,5,0,6,1)==Ok(true));
+    assert!(cl.movemaker(true, 5, 0, 6, 1) == Ok(true));
     cl.all_empty(true);
-    cl.set_piece(true,Piece::R,0,0);
-    cl.set_piece(true,Piece::r,4,0);
-    cl.set_piece(true,Piece::k,5,0);
+    cl.set_piece(true, Piece::R, 0, 0);
+    cl.set_piece(true, Piece::r, 4, 0);
+    cl.set_piece(true, Piece::k, 5, 0);
     cl.white_active_1 = false;
-    assert!(cl.movemaker(true,5,0,4,0)==Err(MoveError::NotLegal));
+    assert!(cl.movemaker(true, 5, 0, 4, 0) == Err(MoveError::NotLegal));
 }
 
-
 #[test]
 fn promotion() {
     let mut cl = ChessLogic::new();
     cl.all_empty(true);
-    cl.set_piece(true,Piece::P,1,7);
+    cl.set_piece(true, Piece::P, 1, 7);
     cl.upgrade_to1 = Piece::Q;
-    cl.movemaker(true,1,7,0,7);
+    assert_eq!(cl.movemaker(true, 1, 7, 0, 7), Ok(true));
     cl.print(true);
-    assert!(cl.chess_board1.board[0][7]==Piece::UQ);
-    assert!(cl.upgrade_to1==Piece::E);
+    assert!(cl.chess_board1.board[0][7] == Piece::UQ);
+    assert!(cl.upgrade_to1 == Piece::E);
 
     cl.all_empty(false);
-    cl.set_piece(false,Piece::p,6,7);
+    cl.set_piece(false, Piece::p, 6, 7);
     cl.upgrade_to2 = Piece::r;
     cl.white_active_2 = false;
-    cl.movemaker(false,6,7,7,7);
+    assert_eq!(cl.movemaker(false, 6, 7, 7, 7), Ok(true));
     cl.print(false);
-    assert!(cl.chess_board2.board[7][7]==Piece::Ur);
-    assert!(cl.upgrade_to2==Piece::E);
+    assert!(cl.chess_board2.board[7][7] == Piece::Ur);
+    assert!(cl.upgrade_to2 == Piece::E);
 
     let mut cl = ChessLogic::new();
     cl.all_empty(true);
-    cl.set_piece(true,Piece::P,1,0);
+    cl.set_piece(true, Piece::P, 1, 0);
     cl.upgrade_to1 = Piece::N;
-    cl.movemaker(true,1,0,0,0);
+    assert_eq!(cl.movemaker(true, 1, 0, 0, 0), Ok(true));
     cl.print(true);
-    assert!(cl.chess_board1.board[0][0]==Piece::UN);
-    assert!(cl.upgrade_to1==Piece::E);
+    assert!(cl.chess_board1.board[0][0] == Piece::UN);
+    assert!(cl.upgrade_to1 == Piece::E);
 }
 
 #[test]
-fn castling_white_side_effect4(){
+fn castling_white_side_effect4() {
     let mut cl = ChessLogic::new();
     cl.all_empty(false);
-    cl.set_piece(false,Piece::k,0,4);
-    cl.set_piece(false,Piece::r,0,7);
-    cl.set_piece(false,Piece::r,0,0);
-    let vec = cl.get_legal_moves(false,0,4);
-    cl.print_w_legal(false,&vec);
-    assert!(contains(&vec,(0,6)));
-    assert!(contains(&vec,(0,2)));
+    cl.set_piece(false, Piece::k, 0, 4);
+    cl.set_piece(false, Piece::r, 0, 7);
+    cl.set_piece(false, Piece::r, 0, 0);
+    let vec = cl.get_legal_moves(false, 0, 4);
+    cl.print_w_legal(false, &vec);
+    assert!(contains(&vec, (0, 6)));
+    assert!(contains(&vec, (0, 2)));
     cl.white_active_2 = false;
-    cl.movemaker(false,0,4,0,2);
-    assert!(cl.chess_board2.board[0][0]==Piece::E);
-    assert!(cl.chess_board2.board[0][4]==Piece::E);
-    assert!(cl.chess_board2.board[0][2]==Piece::K);
-    assert!(cl.chess_board2.board[0][3]==Piece::R);
+    assert_eq!(cl.movemaker(false, 0, 4, 0, 2), Ok(true));
+    assert!(cl.chess_board2.board[0][0] == Piece::E);
+    assert!(cl.chess_board2.board[0][4] == Piece::E);
+    assert!(cl.chess_board2.board[0][2] == Piece::k);
+    assert!(cl.chess_board2.board[0][3] == Piece::r);
 }
 
 #[test]
-fn capture_piece(){
-     //the order is P-R-N-B-Q
+fn capture_piece() {
+    //the order is P-R-N-B-Q
     let mut cl = ChessLogic::new();
     cl.all_empty(true);
-    cl.set_piece(true,Piece::R,0,0);
-    cl.set_piece(true,Piece::r,4,0);
-    cl.white_active_1 = false; 
-    assert!(cl.movemaker(true,4,0,0,0)==Ok(true));
-    println!("{}== num of capture rooks on board1 which are white",
-    cl.get_captured_piece(false,true,1));
-    assert!(cl.get_captured_piece(false,true,1)==1);
-    cl.set_piece(true,Piece::Q,0,7);
-    let vec = cl.get_legal_moves(true,0,0);
-    cl.print_w_legal(true,&vec);
-    cl.white_active_1 = false; 
-    assert!(cl.movemaker(true,0,0,0,7)==Ok(true));
-    
-    assert!(cl.get_captured_piece(false,true,4)==1);
-    cl.set_piece(true,Piece::Q,7,7);
-    cl.white_active_1 = false; 
-    assert!(cl.movemaker(true,0,7,7,7)==Ok(true));
-    assert!(cl.get_captured_piece(false,true,4)==2);
-    cl.set_piece(true,Piece::K,6,7);
-    cl.movemaker(true,6,7,7,7);
+    cl.set_piece(true, Piece::R, 0, 0);
+    cl.set_piece(true, Piece::r, 4, 0);
+    cl.white_active_1 = false;
+    assert!(cl.movemaker(true, 4, 0, 0, 0) == Ok(true));
+    println!(
+        "{}== num of capture rooks on board1 which are white",
+        cl.get_captured_piece(false, true, 1)
+    );
+    assert!(cl.get_captured_piece(false, true, 1) == 1);
+    cl.set_piece(true, Piece::Q, 0, 7);
+    let vec = cl.get_legal_moves(true, 0, 0);
+    cl.print_w_legal(true, &vec);
+    cl.white_active_1 = false;
+    assert!(cl.movemaker(true, 0, 0, 0, 7) == Ok(true));
+
+    assert!(cl.get_captured_piece(false, true, 4) == 1);
+    cl.set_piece(true, Piece::Q, 7, 7);
+    cl.white_active_1 = false;
+    assert!(cl.movemaker(true, 0, 7, 7, 7) == Ok(true));
+    assert!(cl.get_captured_piece(false, true, 4) == 2);
+    cl.set_piece(true, Piece::K, 6, 7);
+    assert_eq!(cl.movemaker(true, 6, 7, 7, 7), Ok(true));
 }
 
-   
 #[test]
-fn capture_piece2(){
+fn capture_piece2() {
     let mut cl = ChessLogic::new();
     cl.all_empty(false);
-    cl.set_piece(false,Piece::R,0,0);
-    cl.set_piece(false,Piece::r,4,0);
-    cl.white_active_2 = false; 
-    let vec2 = cl.get_legal_moves(false,4,0);
+    cl.set_piece(false, Piece::R, 0, 0);
+    cl.set_piece(false, Piece::r, 4, 0);
+    cl.white_active_2 = false;
+    let vec2 = cl.get_legal_moves(false, 4, 0);
 
-    cl.print_w_legal(false,&vec2);
-    assert!(cl.movemaker(false,4,0,0,0)==Ok(true));
-    println!("{}",cl.get_captured_piece(true,true,1));
-    assert!(cl.get_captured_piece(true,true,1)==1);
+    cl.print_w_legal(false, &vec2);
+    assert!(cl.movemaker(false, 4, 0, 0, 0) == Ok(true));
+    println!("{}", cl.get_captured_piece(true, true, 1));
+    assert!(cl.get_captured_piece(true, true, 1) == 1);
 
     cl.all_empty(true);
-    cl.set_piece(true,Piece::R,0,0);
-    cl.set_piece(true,Piece::r,4,0);
+    cl.set_piece(true, Piece::R, 0, 0);
+    cl.set_piece(true, Piece::r, 4, 0);
     assert!(cl.white_active_2);
-    let vec3 = cl.get_legal_moves(true,0,0);
-    cl.print_w_legal(true,&vec3);
-    assert!(cl.movemaker(true,0,0,4,0)==Ok(true));
-    assert!(cl.get_captured_piece(false,false,1)==1);
+    let vec3 = cl.get_legal_moves(true, 0, 0);
+    cl.print_w_legal(true, &vec3);
+    assert!(cl.movemaker(true, 0, 0, 4, 0) == Ok(true));
+    assert!(cl.get_captured_piece(false, false, 1) == 1);
 
     cl.all_empty(false);
-    cl.set_piece(false,Piece::R,0,0);
-    cl.set_piece(false,Piece::r,4,0);
+    cl.set_piece(false, Piece::R, 0, 0);
+    cl.set_piece(false, Piece::r, 4, 0);
     assert!(cl.white_active_2);
-    let vec = cl.get_legal_moves(false,0,0);
-    cl.print_w_legal(false,&vec);
-    assert!(cl.movemaker(false,0,0,4,0)==Ok(true));
-    assert!(cl.get_captured_piece(true,false,1)==1);
+    let vec = cl.get_legal_moves(false, 0, 0);
+    cl.print_w_legal(false, &vec);
+    assert!(cl.movemaker(false, 0, 0, 4, 0) == Ok(true));
+    assert!(cl.get_captured_piece(true, false, 1) == 1);
 }
 
 #[test]
-fn king1(){
+fn king1() {
     let mut cl = ChessLogic::new();
 
     cl.all_empty(true);
-    cl.set_piece(true,Piece::q,7,7);
-    cl.set_piece(true,Piece::r,0,3);
-    cl.set_piece(true,Piece::K,6,5);
-    let vec = cl.get_legal_moves(true,6,5);
-    cl.print_w_legal(true,&vec);
-    let mut vecbyhand = Vec::new();
-
-    vecbyhand.push((5,6));
-    vecbyhand.push((5,4));
-    vecbyhand.push((5,5));
-    vecbyhand.push((6,4));
-    vecbyhand.push((6,6));
-    vecbyhand.push((7,4));
-    vecbyhand.push((7,5));
-    vecbyhand.push((7,6));
+    cl.set_piece(true, Piece::q, 7, 7);
+    cl.set_piece(true, Piece::r, 0, 3);
+    cl.set_piece(true, Piece::K, 6, 5);
+    let vec = cl.get_legal_moves(true, 6, 5);
+    cl.print_w_legal(true, &vec);
+    let vecbyhand = vec![(5, 6), (5, 4), (5, 5), (6, 4), (6, 6), (7, 4), (7, 5), (7, 6)];
     let mut vec_norm = normalize(&vec);
     let mut vecbyhand_norm = normalize(&vecbyhand);
     vec_norm.sort();
     vecbyhand_norm.sort();
 
-
-    assert_eq!(vec_norm,vecbyhand_norm);
+    assert_eq!(vec_norm, vecbyhand_norm);
 }
 
 #[test]
-fn illegal_rook(){
+fn illegal_rook() {
     let mut cl = ChessLogic::new();
     cl.all_empty(true);
-    cl.set_piece(true,Piece::K,7,4);
-    cl.set_piece(true,Piece::R,7,7);
-    cl.set_piece(true,Piece::r,0,0);
+    cl.set_piece(true, Piece::K, 7, 4);
+    cl.set_piece(true, Piece::R, 7, 7);
+    cl.set_piece(true, Piece::r, 0, 0);
     let board1 = true;
-    assert!(cl.movemaker(board1,7,7,6,7)==Ok(true));
-    assert!(cl.movemaker(board1,0,0,1,0)==Ok(true));
-    assert!(cl.movemaker(board1,6,7,7,7)==Ok(true));
-    assert!(cl.movemaker(board1,1,0,1,1)==Ok(true));
-    assert!(cl.movemaker(board1,7,4,7,6)==Err(MoveError::NotLegal));
+    assert!(cl.movemaker(board1, 7, 7, 6, 7) == Ok(true));
+    assert!(cl.movemaker(board1, 0, 0, 1, 0) == Ok(true));
+    assert!(cl.movemaker(board1, 6, 7, 7, 7) == Ok(true));
+    assert!(cl.movemaker(board1, 1, 0, 1, 1) == Ok(true));
+    assert!(cl.movemaker(board1, 7, 4, 7, 6) == Err(MoveError::NotLegal));
 }
 #[test]
-fn rook2(){
+fn rook2() {
     let mut cl = ChessLogic::new();
     cl.all_empty(true);
-    cl.set_piece(true,Piece::q,5,0);
-    cl.set_piece(true,Piece::k,3,0);
-    cl.set_piece(true,Piece::Ur,4,0);
-    cl.set_piece(true,Piece::b,4,1);
+    cl.set_piece(true, Piece::q, 5, 0);
+    cl.set_piece(true, Piece::k, 3, 0);
+    cl.set_piece(true, Piece::Ur, 4, 0);
+    cl.set_piece(true, Piece::b, 4, 1);
 
-    assert_eq!(
-        cl.get_legal_moves(true,4,0),Vec::new()
-    );
+    assert_eq!(cl.get_legal_moves(true, 4, 0), Vec::new());
 
     cl.all_empty(true);
-    cl.set_piece(true,Piece::UQ,5,0);
-    cl.set_piece(true,Piece::K,3,0);
-    cl.set_piece(true,Piece::r,4,0);
-    cl.set_piece(true,Piece::B,4,1);
+    cl.set_piece(true, Piece::UQ, 5, 0);
+    cl.set_piece(true, Piece::K, 3, 0);
+    cl.set_piece(true, Piece::r, 4, 0);
+    cl.set_piece(true, Piece::B, 4, 1);
 
-    let vec = cl.get_legal_moves(true,4,0);
-    let mut vecbyhand = Vec::new();
-
-    vecbyhand.push((5,0));
-    vecbyhand.push((3,0));
-    vecbyhand.push((4,1));
+    let vec = cl.get_legal_moves(true, 4, 0);
+    let vecbyhand = vec![(5, 0), (3, 0), (4, 1)];
 
     let mut vec_norm = normalize(&vec);
     let mut vecbyhand_norm = normalize(&vecbyhand);
     vec_norm.sort();
     vecbyhand_norm.sort();
 
+    assert_eq!(vec_norm, vecbyhand_norm)
+}
 
-    assert_eq!(vec_norm,vecbyhand_norm)
+pub fn sq(i: usize, j: usize) -> Square {
+    Square::new(i, j).unwrap()
 }
 
-pub fn normalize(vec: &Vec<(usize,usize)>) -> Vec<usize> {
+pub fn normalize(vec: &[(usize, usize)]) -> Vec<usize> {
     let mut ret = Vec::new();
-    for (a,b) in vec.iter() {  
-        ret.push(a*10+b);
+    for (a, b) in vec.iter() {
+        ret.push(a * 10 + b);
     }
     ret
 }
 
 #[test]
-fn attacks1(){
+fn attacks1() {
     let mut cl = ChessLogic::new();
-  
-    cl.set_piece(true,Piece::P,5,4);
-    cl.set_piece(true,Piece::p,5,5);
-    cl.set_piece(true,Piece::R,4,2);
-    cl.set_piece(true,Piece::q,4,3);
-    cl.set_piece(true,Piece::E,1,7);
+
+    cl.set_piece(true, Piece::P, 5, 4);
+    cl.set_piece(true, Piece::p, 5, 5);
+    cl.set_piece(true, Piece::R, 4, 2);
+    cl.set_piece(true, Piece::q, 4, 3);
+    cl.set_piece(true, Piece::E, 1, 7);
     for i in 0..8 {
-        cl.set_piece(true,Piece::E,6,i);
+        cl.set_piece(true, Piece::E, 6, i);
     }
-    
+
     cl.print(true);
-    assert_eq!(true,cl.is_attacked(true,true,6,6));
-    assert_eq!(true,cl.is_attacked(true,true,7,7));
-    assert_eq!(true,cl.is_attacked(true,true,3,4));
-    assert_eq!(true,cl.is_attacked(true,true,2,3));
-    assert_eq!(true,cl.is_attacked(true,true,0,5));
-    assert_eq!(true,cl.is_attacked(true,true,6,4));
-    assert_eq!(true,cl.is_attacked(true,true,4,7));
-    assert_eq!(true,cl.is_attacked(true,true,7,0));
-    assert_eq!(false,cl.is_attacked(true,true,5,1))
+    assert!(cl.is_attacked(true, true, 6, 6));
+    assert!(cl.is_attacked(true, true, 7, 7));
+    assert!(cl.is_attacked(true, true, 3, 4));
+    assert!(cl.is_attacked(true, true, 2, 3));
+    assert!(cl.is_attacked(true, true, 0, 5));
+    assert!(cl.is_attacked(true, true, 6, 4));
+    assert!(cl.is_attacked(true, true, 4, 7));
+    assert!(cl.is_attacked(true, true, 7, 0));
+    assert!(!cl.is_attacked(true, true, 5, 1))
 }
 
 #[test]
-fn checkmate1(){
+fn checkmate1() {
     let mut cl = ChessLogic::new();
     cl.all_empty(true);
-    cl.set_piece(true,Piece::N,1,4);
-    cl.set_piece(true,Piece::R,5,7);
-    cl.set_piece(true,Piece::p,1,6);
-    cl.set_piece(true,Piece::k,1,7);
-
-    let vec = cl.get_legal_moves(true,1,7);
-    cl.print_w_legal(true,&vec);
-    assert_eq!(vec,[(0, 6), (0, 7), (2, 6), (2, 7)])
-
+    cl.set_piece(true, Piece::N, 1, 4);
+    cl.set_piece(true, Piece::R, 5, 7);
+    cl.set_piece(true, Piece::p, 1, 6);
+    cl.set_piece(true, Piece::k, 1, 7);
+
+    let vec = cl.get_legal_moves(true, 1, 7);
+    cl.print_w_legal(true, &vec);
+    assert_eq!(vec, [(0, 6), (0, 7), (2, 6), (2, 7)])
 }
 
 #[test]
-fn checkmate2(){
+fn checkmate2() {
     let mut cl = ChessLogic::new();
     cl.all_empty(true);
-    cl.chess_board1.board[0][0]=Piece::Q;
-    cl.chess_board1.board[0][7]=Piece::k;
-    cl.chess_board1.board[1][7]=Piece::p;
-    cl.chess_board1.board[1][6]=Piece::p;
-
-    let vec = cl.get_legal_moves(true,0,7);
-    cl.print_w_legal(true,&vec);
-    assert_eq!(vec,[(0,6)])
+    cl.chess_board1.board[0][0] = Piece::Q;
+    cl.chess_board1.board[0][7] = Piece::k;
+    cl.chess_board1.board[1][7] = Piece::p;
+    cl.chess_board1.board[1][6] = Piece::p;
+
+    let vec = cl.get_legal_moves(true, 0, 7);
+    cl.print_w_legal(true, &vec);
+    assert_eq!(vec, [(0, 6)])
 }
 
 #[test]
-fn fools_mate(){
+fn fools_mate() {
     let mut cl = ChessLogic::new();
-    cl.chess_board1.board[6][6]=Piece::E;
-    cl.chess_board1.board[6][5]=Piece::E;
-    cl.chess_board1.board[4][6]=Piece::P;
-    cl.chess_board1.board[5][5]=Piece::P;
-    cl.chess_board1.board[4][7]=Piece::Uq;
-
-    let vec = cl.get_legal_moves(true,7,4);
-    cl.print_w_legal(true,&vec);
-    assert_eq!(vec,[(6, 5)])
+    cl.chess_board1.board[6][6] = Piece::E;
+    cl.chess_board1.board[6][5] = Piece::E;
+    cl.chess_board1.board[4][6] = Piece::P;
+    cl.chess_board1.board[5][5] = Piece::P;
+    cl.chess_board1.board[4][7] = Piece::Uq;
+
+    let vec = cl.get_legal_moves(true, 7, 4);
+    cl.print_w_legal(true, &vec);
+    assert_eq!(vec, [(6, 5)])
 }
 
 #[test]
-fn rook_move(){
+fn rook_move() {
     let mut cl = ChessLogic::new();
 
     cl.all_empty(true);
-    cl.set_piece(true,Piece::R,4,4);
-    cl.set_piece(true,Piece::R,4,6);
-    let vec = cl.get_legal_moves(true,4,4);
-    cl.print_w_legal(true,&vec);
-    let mut vecbyhand = Vec::new();
-
-    vecbyhand.push((4,5));
-    vecbyhand.push((3,4));
-    vecbyhand.push((2,4));
-    vecbyhand.push((1,4));
-    vecbyhand.push((0,4));
-    vecbyhand.push((5,4));
-    vecbyhand.push((6,4));
-    vecbyhand.push((7,4));
-    vecbyhand.push((4,3));
-    vecbyhand.push((4,2));
-    vecbyhand.push((4,1));
-    vecbyhand.push((4,0));
+    cl.set_piece(true, Piece::R, 4, 4);
+    cl.set_piece(true, Piece::R, 4, 6);
+    let vec = cl.get_legal_moves(true, 4, 4);
+    cl.print_w_legal(true, &vec);
+    let vecbyhand = vec![
+        (4, 5),
+        (3, 4),
+        (2, 4),
+        (1, 4),
+        (0, 4),
+        (5, 4),
+        (6, 4),
+        (7, 4),
+        (4, 3),
+        (4, 2),
+        (4, 1),
+        (4, 0),
+    ];
 
     let mut vec_norm = normalize(&vec);
     let mut vecbyhand_norm = normalize(&vecbyhand);
     vec_norm.sort();
     vecbyhand_norm.sort();
-    
-    assert_eq!(vec_norm,vecbyhand_norm);
+
+    assert_eq!(vec_norm, vecbyhand_norm);
 
     cl.all_empty(true);
-    cl.set_piece(true,Piece::r,4,4);
-    cl.set_piece(true,Piece::r,4,6);
-    let vec2 = cl.get_legal_moves(true,4,4);
-    cl.print_w_legal(true,&vec2);
+    cl.set_piece(true, Piece::r, 4, 4);
+    cl.set_piece(true, Piece::r, 4, 6);
+    let vec2 = cl.get_legal_moves(true, 4, 4);
+    cl.print_w_legal(true, &vec2);
     let mut vec_norm2 = normalize(&vec2);
     vec_norm2.sort();
-    assert_eq!(vec_norm2,vecbyhand_norm);
+    assert_eq!(vec_norm2, vecbyhand_norm);
 }
 
 #[test]
-fn bishop_move(){
+fn bishop_move() {
     let mut cl = ChessLogic::new();
 
     cl.all_empty(true);
-    cl.set_piece(true,Piece::b,4,4);
-    cl.set_piece(true,Piece::b,5,5);
-    let mut vec = cl.get_legal_moves(true,4,4);
-    cl.print_w_legal(true,&vec);
-    let mut vecbyhand = Vec::new();
-    vecbyhand.push((0,0));
-    vecbyhand.push((1,1));
-    vecbyhand.push((2,2));
-    vecbyhand.push((3,3));
+    cl.set_piece(true, Piece::b, 4, 4);
+    cl.set_piece(true, Piece::b, 5, 5);
+    let mut vec = cl.get_legal_moves(true, 4, 4);
+    cl.print_w_legal(true, &vec);
+    let mut vecbyhand = vec![(0, 0), (1, 1), (2, 2), (3, 3)];
 
-    vecbyhand.push((5,3));
-    vecbyhand.push((6,2));
-    vecbyhand.push((7,1));
+    vecbyhand.push((5, 3));
+    vecbyhand.push((6, 2));
+    vecbyhand.push((7, 1));
 
-    vecbyhand.push((3,5));
-    vecbyhand.push((2,6));
-    vecbyhand.push((1,7));
+    vecbyhand.push((3, 5));
+    vecbyhand.push((2, 6));
+    vecbyhand.push((1, 7));
 
     let mut vec_norm = normalize(&vec);
     let mut vecbyhand_norm = normalize(&vecbyhand);
     vec_norm.sort();
     vecbyhand_norm.sort();
-    
-    assert_eq!(vec_norm,vecbyhand_norm);
+
+    assert_eq!(vec_norm, vecbyhand_norm);
 
     cl.all_empty(true);
-    cl.set_piece(true,Piece::B,4,4);
-    cl.set_piece(true,Piece::B,5,5);
-    vec = cl.get_legal_moves(true,4,4);
-    cl.print_w_legal(true,&vec);
+    cl.set_piece(true, Piece::B, 4, 4);
+    cl.set_piece(true, Piece::B, 5, 5);
+    vec = cl.get_legal_moves(true, 4, 4);
+    cl.print_w_legal(true, &vec);
 
     let mut vec_norm = normalize(&vec);
     let mut vecbyhand_norm = normalize(&vecbyhand);
     vec_norm.sort();
     vecbyhand_norm.sort();
-    
-    assert_eq!(vec_norm,vecbyhand_norm);
+
+    assert_eq!(vec_norm, vecbyhand_norm);
 }
 
 #[test]
-fn queen_move(){
+fn queen_move() {
     let mut cl = ChessLogic::new();
     cl.all_empty(true);
-    cl.set_piece(true,Piece::Q,0,0);
-    cl.set_piece(true,Piece::Uq,7,7);
-    let mut vec_queen = cl.get_legal_moves(true,7,7);
-    let mut vecbyhand_queen = Vec::new();
-
-
-    vecbyhand_queen.push((0,0));
-    vecbyhand_queen.push((1,1));
-    vecbyhand_queen.push((2,2));
-    vecbyhand_queen.push((3,3));
-    vecbyhand_queen.push((4,4));
-    vecbyhand_queen.push((5,5));
-    vecbyhand_queen.push((6,6));
-
-    vecbyhand_queen.push((6,7));
-    vecbyhand_queen.push((5,7));
-    vecbyhand_queen.push((4,7));
-    vecbyhand_queen.push((3,7));
-    vecbyhand_queen.push((2,7));
-    vecbyhand_queen.push((1,7));
-    vecbyhand_queen.push((0,7));
-
-    vecbyhand_queen.push((7,6));
-    vecbyhand_queen.push((7,5));
-    vecbyhand_queen.push((7,4));
-    vecbyhand_queen.push((7,3));
-    vecbyhand_queen.push((7,2));
-    vecbyhand_queen.push((7,1));
-    vecbyhand_queen.push((7,0));
+    cl.set_piece(true, Piece::Q, 0, 0);
+    cl.set_piece(true, Piece::Uq, 7, 7);
+    let vec_queen = cl.get_legal_moves(true, 7, 7);
+    let mut vecbyhand_queen = vec![(0, 0), (1, 1), (2, 2), (3, 3), (4, 4), (5, 5), (6, 6)];
+
+    vecbyhand_queen.push((6, 7));
+    vecbyhand_queen.push((5, 7));
+    vecbyhand_queen.push((4, 7));
+    vecbyhand_queen.push((3, 7));
+    vecbyhand_queen.push((2, 7));
+    vecbyhand_queen.push((1, 7));
+    vecbyhand_queen.push((0, 7));
+
+    vecbyhand_queen.push((7, 6));
+    vecbyhand_queen.push((7, 5));
+    vecbyhand_queen.push((7, 4));
+    vecbyhand_queen.push((7, 3));
+    vecbyhand_queen.push((7, 2));
+    vecbyhand_queen.push((7, 1));
+    vecbyhand_queen.push((7, 0));
 
     let mut vec_norm = normalize(&vec_queen);
     let mut vecbyhand_norm = normalize(&vecbyhand_queen);
     vec_norm.sort();
     vecbyhand_norm.sort();
-    
-    assert_eq!(vec_norm,vecbyhand_norm);
+
+    assert_eq!(vec_norm, vecbyhand_norm);
 }
 
 #[test]
-fn cool_checkmate1(){
+fn cool_checkmate1() {
     let mut cl = ChessLogic::new();
     cl.all_empty(true);
-    cl.set_piece(true,Piece::k,2,4);
-    cl.set_piece(true,Piece::Q,3,2);
-    cl.set_piece(true,Piece::P,2,2);
-    cl.set_piece(true,Piece::K,4,5);
-    cl.set_piece(true,Piece::n,0,0);
-    cl.set_piece(true,Piece::R,0,5);
+    cl.set_piece(true, Piece::k, 2, 4);
+    cl.set_piece(true, Piece::Q, 3, 2);
+    cl.set_piece(true, Piece::P, 2, 2);
+    cl.set_piece(true, Piece::K, 4, 5);
+    cl.set_piece(true, Piece::n, 0, 0);
+    cl.set_piece(true, Piece::R, 0, 5);
+
+    let vec = cl.get_legal_moves(true, 2, 4);
+    let vecbyhand = [(1, 3), (1, 4), (1, 5), (2, 3), (2, 5), (3, 3), (3, 4), (3, 5)];
+    cl.print_w_legal(true, &vec);
+    assert_eq!(vec, vecbyhand);
+}
 
-    let mut vec= cl.get_legal_moves(true,2,4);
-    let mut vecbyhand = [(1, 3), (1, 4), (1, 5), (2, 3), (2, 5), (3, 3), (3, 4), (3, 5)];
-    cl.print_w_legal(true,&vec);
-    assert_eq!(vec,vecbyhand);
+#[test]
+fn pawn_attacks() {
+    let mut cl = ChessLogic::new();
+    let vec = cl.get_legal_moves(true, 6, 7);
+    cl.print_w_legal(true, &vec);
+    assert!(!cl.is_attacked(true, true, 5, 7));
+    assert!(!cl.is_attacked(true, true, 5, 6));
+    assert!(!cl.is_attacked(true, true, 5, 0));
+
+    assert!(cl.is_attacked(true, false, 5, 7));
+    assert!(cl.is_attacked(true, false, 5, 6));
+    assert!(cl.is_attacked(true, false, 5, 0));
+
+    assert!(cl.is_attacked(true, true, 2, 1));
+    assert!(cl.is_attacked(true, true, 2, 0));
+    assert!(cl.is_attacked(true, true, 2, 7));
+    assert!(cl.is_attacked(true, true, 2, 3));
+
+    assert!(!cl.is_attacked(true, true, 4, 0));
+    assert!(!cl.is_attacked(true, true, 4, 7));
+    assert!(!cl.is_attacked(true, true, 3, 7));
+    assert!(!cl.is_attacked(true, true, 3, 0));
+    assert!(!cl.is_attacked(true, true, 3, 4));
 }
 
 #[test]
-fn pawn_attacks(){
+fn enpassant() {
     let mut cl = ChessLogic::new();
-    let vec = cl.get_legal_moves(true,6,7);
-    cl.print_w_legal(true,&vec);
-    assert_eq!(false,cl.is_attacked(true,true,5,7));
-    assert_eq!(false,cl.is_attacked(true,true,5,6));
-    assert_eq!(false,cl.is_attacked(true,true,5,0));
+    cl.all_empty(true);
+    cl.set_piece(true, Piece::p, 4, 0);
+    cl.set_piece(true, Piece::P, 4, 1);
+    cl.set_pawn_in_last_turn(true, Some((4, 1)));
+    let mut vec = cl.get_legal_moves(true, 4, 0);
 
-    assert_eq!(true,cl.is_attacked(true,false,5,7));
-    assert_eq!(true,cl.is_attacked(true,false,5,6));
-    assert_eq!(true,cl.is_attacked(true,false,5,0));
+    let mut vecbyhand = vec![(5, 0), (5, 1)];
+    assert_eq!(vec, vecbyhand);
 
-    assert_eq!(true,cl.is_attacked(true,true,2,1));
-    assert_eq!(true,cl.is_attacked(true,true,2,0));
-    assert_eq!(true,cl.is_attacked(true,true,2,7));
-    assert_eq!(true,cl.is_attacked(true,true,2,3));
+    cl.all_empty(true);
+    cl.set_piece(true, Piece::p, 4, 7);
+    cl.set_piece(true, Piece::P, 4, 6);
+    cl.set_pawn_in_last_turn(true, Some((4, 6)));
+    vec = cl.get_legal_moves(true, 4, 7);
 
-    assert_eq!(false,cl.is_attacked(true,true,4,0));
-    assert_eq!(false,cl.is_attacked(true,true,4,7));
-    assert_eq!(false,cl.is_attacked(true,true,3,7));
-    assert_eq!(false,cl.is_attacked(true,true,3,0));
-    assert_eq!(false,cl.is_attacked(true,true,3,4));
-}
+    vecbyhand = vec![(5, 7), (5, 6)];
+    assert_eq!(vec, vecbyhand);
 
-#[test]
-fn enpassant(){
-    let mut cl = ChessLogic::new();
     cl.all_empty(true);
-    cl.set_piece(true,Piece::p,4,0);
-    cl.set_piece(true,Piece::P,4,1);
-    cl.set_pawn_in_last_turn(true, Some((4,1)));
-    let mut vec = cl.get_legal_moves(true,4,0);
+    cl.set_piece(true, Piece::p, 4, 6);
+    cl.set_piece(true, Piece::P, 4, 7);
+    cl.set_pawn_in_last_turn(true, Some((4, 7)));
+    vec = cl.get_legal_moves(true, 4, 6);
 
-    let mut vecbyhand = Vec::new();
-    vecbyhand.push((5,0));
-    vecbyhand.push((5,1));
-    assert_eq!(vec,vecbyhand);
+    vecbyhand = vec![(5, 6), (5, 7)];
+    assert_eq!(vec, vecbyhand);
 
     cl.all_empty(true);
-    cl.set_piece(true,Piece::p,4,7);
-    cl.set_piece(true,Piece::P,4,6);
-    cl.set_pawn_in_last_turn(true, Some((4,6)));
-    vec = cl.get_legal_moves(true,4,7);
+    cl.set_piece(true, Piece::p, 3, 6);
+    cl.set_piece(true, Piece::P, 3, 7);
+    cl.set_pawn_in_last_turn(true, Some((3, 6)));
+    vec = cl.get_legal_moves(true, 3, 7);
+
+    vecbyhand = vec![(2, 7), (2, 6)];
+    assert_eq!(vec, vecbyhand);
 
-    vecbyhand = Vec::new();
-    vecbyhand.push((5,7));
-    vecbyhand.push((5,6));
-    assert_eq!(vec,vecbyhand);      
-    
     cl.all_empty(true);
-    cl.set_piece(true,Piece::p,4,6);
-    cl.set_piece(true,Piece::P,4,7);
-    cl.set_pawn_in_last_turn(true, Some((4,7)));
-    vec = cl.get_legal_moves(true,4,6);
+    cl.set_piece(true, Piece::p, 3, 0);
+    cl.set_piece(true, Piece::P, 3, 1);
+    cl.set_pawn_in_last_turn(true, Some((3, 0)));
+    vec = cl.get_legal_moves(true, 3, 1);
 
-    vecbyhand = Vec::new();
-    vecbyhand.push((5,6));
-    vecbyhand.push((5,7));
-    assert_eq!(vec,vecbyhand);
+    vecbyhand = vec![(2, 1), (2, 0)];
+    assert_eq!(vec, vecbyhand);
 
     cl.all_empty(true);
-    cl.set_piece(true,Piece::p,3,6);
-    cl.set_piece(true,Piece::P,3,7);
-    cl.set_pawn_in_last_turn(true,Some((3,6)));
-    vec = cl.get_legal_moves(true,3,7);
+    cl.set_piece(true, Piece::p, 3, 0);
+    cl.set_piece(true, Piece::P, 3, 1);
+    cl.set_pawn_in_last_turn(true, None);
+    vec = cl.get_legal_moves(true, 3, 1);
 
-    vecbyhand = Vec::new();
-    vecbyhand.push((2,7));
-    vecbyhand.push((2,6));
-    assert_eq!(vec,vecbyhand);
+    vecbyhand = vec![(2, 1)];
+    assert_eq!(vec, vecbyhand);
+}
+
+#[test]
+fn enpassant_capture() {
+    let mut cl = ChessLogic::new();
+    assert!(cl.movemaker(true, 6, 4, 4, 4) == Ok(true));
+    assert!(cl.movemaker(true, 1, 0, 2, 0) == Ok(true));
+    assert!(cl.movemaker(true, 4, 4, 3, 4) == Ok(true));
+    assert!(cl.movemaker(true, 1, 3, 3, 3) == Ok(true));
+    assert!(cl.movemaker(true, 3, 4, 2, 3) == Ok(true));
+    //the captured pawn leaves the board and goes to the partner
+    assert!(cl.chess_board1.board[2][3] == Piece::P);
+    assert!(cl.chess_board1.board[3][3] == Piece::E);
+    assert!(cl.get_captured_piece(false, false, 0) == 1);
+}
 
+#[test]
+fn enpassant_last_pawn_only() {
+    //only the pawn that moved in the last turn can be captured
+    let mut cl = ChessLogic::new();
     cl.all_empty(true);
-    cl.set_piece(true,Piece::p,3,0);
-    cl.set_piece(true,Piece::P,3,1);
-    cl.set_pawn_in_last_turn(true,Some((3,0)));
-    vec = cl.get_legal_moves(true,3,1);
+    cl.set_piece(true, Piece::P, 3, 4);
+    cl.set_piece(true, Piece::p, 3, 3);
+    cl.set_piece(true, Piece::p, 3, 7);
+    cl.set_pawn_in_last_turn(true, Some((3, 7)));
+    assert_eq!(cl.get_legal_moves(true, 3, 4), vec![(2, 4)]);
+
+    //a pawn that moved one square cannot be captured
+    let mut cl = ChessLogic::new();
+    for (a, b, c, d) in [
+        (6, 4, 4, 4),
+        (1, 3, 2, 3),
+        (4, 4, 3, 4),
+        (1, 0, 2, 0),
+        (6, 0, 5, 0),
+        (2, 3, 3, 3),
+    ]
+    .iter()
+    {
+        assert!(cl.movemaker(true, *a, *b, *c, *d) == Ok(true));
+    }
+    assert!(!contains(&cl.get_legal_moves(true, 3, 4), (2, 3)));
+}
 
-    vecbyhand = Vec::new();
-    vecbyhand.push((2,1));
-    vecbyhand.push((2,0));
-    assert_eq!(vec,vecbyhand);
+#[test]
+fn pawn_captures_own_colour() {
+    //a black pawn on the fifth rank only captures white pieces
+    let mut cl = ChessLogic::new();
+    cl.all_empty(true);
+    cl.set_piece(true, Piece::p, 4, 3);
+    cl.set_piece(true, Piece::n, 5, 2);
+    cl.set_piece(true, Piece::N, 5, 4);
+    assert_eq!(cl.get_legal_moves(true, 4, 3), vec![(5, 3), (5, 4)]);
+}
 
+#[test]
+fn stalemate_king_moves() {
+    //a lone king that can move is not stalemated
+    let mut cl = ChessLogic::new();
     cl.all_empty(true);
-    cl.set_piece(true,Piece::p,3,0);
-    cl.set_piece(true,Piece::P,3,1);
-    cl.set_pawn_in_last_turn(true,None);
-    vec = cl.get_legal_moves(true,3,1);
+    cl.set_piece(true, Piece::K, 7, 4);
+    cl.set_piece(true, Piece::k, 0, 7);
+    assert!(!cl.check_patt(true, true));
+    assert!(cl.get_winner(true) == Winner::N);
+
+    //every square of the king is attacked
+    cl.set_piece(true, Piece::E, 7, 4);
+    cl.set_piece(true, Piece::K, 7, 0);
+    cl.set_piece(true, Piece::q, 5, 1);
+    assert!(cl.check_patt(true, true));
+}
 
-    vecbyhand = Vec::new();
-    vecbyhand.push((2,1));
-    assert_eq!(vec,vecbyhand);
+#[test]
+fn castling_black_keeps_colour() {
+    let mut cl = ChessLogic::new();
+    cl.all_empty(true);
+    cl.set_piece(true, Piece::k, 0, 4);
+    cl.set_piece(true, Piece::r, 0, 7);
+    cl.set_piece(true, Piece::K, 7, 4);
+    cl.white_active_1 = false;
+    assert!(cl.movemaker(true, 0, 4, 0, 6).is_ok());
+    assert!(cl.chess_board1.board[0][6] == Piece::k);
+    assert!(cl.chess_board1.board[0][5] == Piece::r);
+    assert!(cl.chess_board1.board[0][7] == Piece::E);
 }
 
 #[test]
-fn horse_bishop_checkmate(){
+fn promoted_knight_attacks_board2() {
     let mut cl = ChessLogic::new();
+    cl.all_empty(false);
+    cl.set_piece(false, Piece::Un, 5, 2);
+    assert!(cl.is_attacked(false, true, 7, 3));
+    assert!(!cl.is_attacked(false, true, 7, 4));
+    //a knight on the same square of board1 does not attack board2
     cl.all_empty(true);
-    cl.set_piece(true,Piece::k,1,7);
-    cl.set_piece(true,Piece::K,1,5);
-    cl.set_piece(true,Piece::UB,0,5);
-    cl.set_piece(true,Piece::N,3,4);
+    cl.set_piece(false, Piece::E, 5, 2);
+    cl.set_piece(true, Piece::Un, 5, 2);
+    assert!(!cl.is_attacked(false, true, 7, 3));
+}
 
-    let vec = cl.get_legal_moves(true,1,7);
-    cl.print_w_legal(true,&vec);
-    assert_eq!(vec,[(0, 6), (0, 7), (1, 6), (2, 6), (2, 7)]);
+#[test]
+fn king_attacks_own_board() {
+    let mut cl = ChessLogic::new();
+    cl.all_empty(true);
+    cl.all_empty(false);
+    cl.set_piece(false, Piece::k, 3, 3);
+    assert!(cl.is_attacked(false, true, 4, 4));
+    assert!(!cl.is_attacked(true, true, 4, 4));
+}
 
+#[test]
+fn horse_bishop_checkmate() {
     let mut cl = ChessLogic::new();
     cl.all_empty(true);
-    cl.set_piece(true,Piece::k,0,0);
-    cl.set_piece(true,Piece::K,2,1);
-    cl.set_piece(true,Piece::UB,2,2);
-    cl.set_piece(true,Piece::N,2,0);
+    cl.set_piece(true, Piece::k, 1, 7);
+    cl.set_piece(true, Piece::K, 1, 5);
+    cl.set_piece(true, Piece::UB, 0, 5);
+    cl.set_piece(true, Piece::N, 3, 4);
+
+    let vec = cl.get_legal_moves(true, 1, 7);
+    cl.print_w_legal(true, &vec);
+    assert_eq!(vec, [(0, 6), (0, 7), (1, 6), (2, 6), (2, 7)]);
 
-    let vec = cl.get_legal_moves(true,0,0);
-    assert_eq!(vec,[(0, 1), (1, 0), (1, 1)]);
+    let mut cl = ChessLogic::new();
+    cl.all_empty(true);
+    cl.set_piece(true, Piece::k, 0, 0);
+    cl.set_piece(true, Piece::K, 2, 1);
+    cl.set_piece(true, Piece::UB, 2, 2);
+    cl.set_piece(true, Piece::N, 2, 0);
+
+    let vec = cl.get_legal_moves(true, 0, 0);
+    assert_eq!(vec, [(0, 1), (1, 0), (1, 1)]);
 }
 
 #[test]
-fn legality_check(){
+fn legality_check() {
     let mut cl = ChessLogic::new();
     //pawns
-    assert_eq!(true,cl.legality_check(true, 1,1,3,1));
-    assert_eq!(true,cl.legality_check(true, 1,1,2,1));
-    assert_eq!(false,cl.legality_check(true, 1,1,4,1));
-    assert_eq!(true,cl.legality_check(true, 6,7,5,7));
-    assert_eq!(false,cl.legality_check(true, 6,7,6,7));
-    assert_eq!(false,cl.legality_check(true, 6,7,5,6));
+    assert!(cl.legality_check(true, 1, 1, 3, 1));
+    assert!(cl.legality_check(true, 1, 1, 2, 1));
+    assert!(!cl.legality_check(true, 1, 1, 4, 1));
+    assert!(cl.legality_check(true, 6, 7, 5, 7));
+    assert!(!cl.legality_check(true, 6, 7, 6, 7));
+    assert!(!cl.legality_check(true, 6, 7, 5, 6));
 }
 
 #[test]
-fn board2_1(){
+fn board2_1() {
     let mut cl = ChessLogic::new();
     cl.all_empty(false);
-    cl.set_piece(false,Piece::q,5,0);
-    cl.set_piece(false,Piece::k,3,0);
-    cl.set_piece(false,Piece::r,4,0);
-    cl.set_piece(false,Piece::b,4,1);
-    let vecc = cl.get_legal_moves(false,4,0);
-    cl.print_w_legal(false,&vecc);
-    assert_eq!(
-        vecc,Vec::new()
-    );
+    cl.set_piece(false, Piece::q, 5, 0);
+    cl.set_piece(false, Piece::k, 3, 0);
+    cl.set_piece(false, Piece::r, 4, 0);
+    cl.set_piece(false, Piece::b, 4, 1);
+    let vecc = cl.get_legal_moves(false, 4, 0);
+    cl.print_w_legal(false, &vecc);
+    assert_eq!(vecc, Vec::new());
 
     cl.all_empty(false);
-    cl.set_piece(false,Piece::Q,5,0);
-    cl.set_piece(false,Piece::K,3,0);
-    cl.set_piece(false,Piece::r,4,0);
-    cl.set_piece(false,Piece::B,4,1);
-
-    let vec = cl.get_legal_moves(false,4,0);
-    let mut vecbyhand = Vec::new();
+    cl.set_piece(false, Piece::Q, 5, 0);
+    cl.set_piece(false, Piece::K, 3, 0);
+    cl.set_piece(false, Piece::r, 4, 0);
+    cl.set_piece(false, Piece::B, 4, 1);
 
-    vecbyhand.push((5,0));
-    vecbyhand.push((3,0));
-    vecbyhand.push((4,1));
+    let vec = cl.get_legal_moves(false, 4, 0);
+    let vecbyhand = vec![(5, 0), (3, 0), (4, 1)];
 
     let mut vec_norm = normalize(&vec);
     let mut vecbyhand_norm = normalize(&vecbyhand);
     vec_norm.sort();
     vecbyhand_norm.sort();
 
-
-    assert_eq!(vec_norm,vecbyhand_norm)
+    assert_eq!(vec_norm, vecbyhand_norm)
 }
 
 #[test]
-fn board2_2(){
+fn board2_2() {
     let mut cl = ChessLogic::new();
     cl.all_empty(false);
-    cl.set_piece(false,Piece::k,2,4);
-    cl.set_piece(false,Piece::Q,3,2);
-    cl.set_piece(false,Piece::P,2,2);
-    cl.set_piece(false,Piece::K,4,5);
-    cl.set_piece(false,Piece::n,0,0);
-    cl.set_piece(false,Piece::R,0,5);
+    cl.set_piece(false, Piece::k, 2, 4);
+    cl.set_piece(false, Piece::Q, 3, 2);
+    cl.set_piece(false, Piece::P, 2, 2);
+    cl.set_piece(false, Piece::K, 4, 5);
+    cl.set_piece(false, Piece::n, 0, 0);
+    cl.set_piece(false, Piece::R, 0, 5);
+
+    let vec = cl.get_legal_moves(false, 2, 4);
+    let vecbyhand = vec![(1, 3), (1, 4), (1, 5), (2, 3), (2, 5), (3, 3), (3, 4), (3, 5)];
+    cl.print_w_legal(false, &vec);
+    assert_eq!(vec, vecbyhand);
+}
 
-    let mut vec= cl.get_legal_moves(false,2,4);
-    let mut vecbyhand = Vec::new();
-    vecbyhand.push((1,3));
-    vecbyhand.push((1,4));
-    vecbyhand.push((1,5));
-    vecbyhand.push((2,3));
-    vecbyhand.push((2,5));
-    vecbyhand.push((3,3));
-    vecbyhand.push((3,4));
-    vecbyhand.push((3,5));
-    cl.print_w_legal(false,&vec);
-    assert_eq!(vec,vecbyhand);
+#[test]
+fn cool_checkmate2() {
+    let mut cl = ChessLogic::new();
+    cl.all_empty(true);
+    cl.set_piece(true, Piece::k, 2, 4);
+    cl.set_piece(true, Piece::P, 2, 3);
+    cl.set_piece(true, Piece::P, 3, 4);
+    cl.set_piece(true, Piece::B, 4, 2);
+    cl.set_piece(true, Piece::p, 3, 5);
+    cl.set_piece(true, Piece::Un, 1, 3);
+
+    let vec = cl.get_legal_moves(true, 2, 4);
+    let vecbyhand = [(1, 4), (1, 5), (2, 3), (2, 5), (3, 3), (3, 4)];
+    cl.print_w_legal(true, &vec);
+    assert_eq!(vec, vecbyhand);
 }
 
 #[test]
-fn cool_checkmate2(){
+fn chincough() {
     let mut cl = ChessLogic::new();
     cl.all_empty(true);
-    cl.set_piece(true,Piece::k,2,4);
-    cl.set_piece(true,Piece::P,2,3);
-    cl.set_piece(true,Piece::P,3,4);
-    cl.set_piece(true,Piece::B,4,2);
-    cl.set_piece(true,Piece::p,3,5);
-    cl.set_piece(true,Piece::Un,1,3);
-    
-    let mut vec= cl.get_legal_moves(true,2,4);
-    let mut vecbyhand = [(1, 4), (1, 5), (2, 3), (2, 5), (3, 3), (3, 4)];
-    cl.print_w_legal(true,&vec);
-    assert_eq!(vec,vecbyhand);
+    cl.set_piece(true, Piece::K, 0, 0);
+    cl.set_piece(true, Piece::P, 0, 1);
+    cl.set_piece(true, Piece::P, 1, 0);
+    cl.set_piece(true, Piece::P, 1, 1);
+    cl.set_piece(true, Piece::n, 1, 2);
+
+    let vec = cl.get_legal_moves(true, 0, 0);
+    cl.print_w_legal(true, &vec);
+    assert_eq!(vec, Vec::new());
+    let vec2 = cl.get_legal_moves(true, 1, 0);
+    cl.print_w_legal(true, &vec2);
+    assert_eq!(vec2, Vec::new());
+    let vec3 = cl.get_legal_moves(true, 0, 1);
+    cl.print_w_legal(true, &vec3);
+    assert_eq!(vec3, Vec::new());
 }
 
-#[test] 
-fn chincough(){
+#[test]
+fn parse() {
+    assert_eq!(parser::parse("e2-e4"), Some([6, 4, 4, 4]));
+
+    assert_eq!(parser::parse("a1-a3"), Some([7, 0, 5, 0]));
+    assert_eq!(parser::parse("d2-d4"), Some([6, 3, 4, 3]));
+    assert_eq!(parser::parse("h8-h1"), Some([0, 7, 7, 7]));
+}
+
+#[test]
+fn castling_black() {
     let mut cl = ChessLogic::new();
     cl.all_empty(true);
-    cl.set_piece(true,Piece::K,0,0);
-    cl.set_piece(true,Piece::P,0,1);
-    cl.set_piece(true,Piece::P,1,0);
-    cl.set_piece(true,Piece::P,1,1);
-    cl.set_piece(true,Piece::n,1,2);
+    cl.set_piece(true, Piece::k, 0, 4);
+    cl.set_piece(true, Piece::r, 0, 7);
+    cl.set_piece(true, Piece::r, 0, 0);
+    let vec = cl.get_legal_moves(true, 0, 4);
+    cl.print_w_legal(true, &vec);
+    assert!(contains(&vec, (0, 6)));
+    assert!(contains(&vec, (0, 2)));
+
+    cl.set_piece(true, Piece::R, 7, 2);
+    let vec = cl.get_legal_moves(true, 0, 4);
+    assert!(contains(&vec, (0, 6)));
+    assert!(!contains(&vec, (0, 2)));
+
+    cl.set_piece(true, Piece::R, 7, 6);
+    let vec = cl.get_legal_moves(true, 0, 4);
+    assert!(!contains(&vec, (0, 6)));
+    assert!(!contains(&vec, (0, 2)));
+
+    cl.set_piece(true, Piece::E, 7, 2);
+    cl.set_piece(true, Piece::E, 7, 6);
+    cl.set_piece(true, Piece::Q, 7, 4);
+    assert!(!contains(&vec, (0, 6)));
+    assert!(!contains(&vec, (0, 2)));
+}
 
-    let mut vec= cl.get_legal_moves(true,0,0);
-    cl.print_w_legal(true,&vec);
-    assert_eq!(vec,Vec::new());
-    let mut vec2=cl.get_legal_moves(true,1,0);
-    cl.print_w_legal(true,&vec2);
-    assert_eq!(vec2,Vec::new());
-    let mut vec3=cl.get_legal_moves(true,0,1);
-    cl.print_w_legal(true,&vec3);
-    assert_eq!(vec3,Vec::new());
+#[test]
+fn castling_white() {
+    let mut cl = ChessLogic::new();
+    cl.all_empty(true);
+    cl.set_piece(true, Piece::K, 7, 4);
+    cl.set_piece(true, Piece::R, 7, 7);
+    cl.set_piece(true, Piece::R, 7, 0);
+    let vec = cl.get_legal_moves(true, 7, 4);
+    cl.print_w_legal(true, &vec);
+    assert!(contains(&vec, (7, 6)));
+    assert!(contains(&vec, (7, 2)));
+
+    //the rook may pass an attacked square, only the squares of the king have to be safe
+    cl.set_piece(true, Piece::r, 0, 1);
+    let vec = cl.get_legal_moves(true, 7, 4);
+    assert!(contains(&vec, (7, 2)));
+
+    cl.set_piece(true, Piece::E, 0, 1);
+    cl.set_piece(true, Piece::r, 0, 2);
+    let vec = cl.get_legal_moves(true, 7, 4);
+    assert!(contains(&vec, (7, 6)));
+    assert!(!contains(&vec, (7, 2)));
+
+    cl.set_piece(true, Piece::r, 0, 6);
+    let vec = cl.get_legal_moves(true, 7, 4);
+    assert!(!contains(&vec, (7, 6)));
+    assert!(!contains(&vec, (7, 2)));
+
+    cl.set_piece(true, Piece::E, 0, 2);
+    cl.set_piece(true, Piece::E, 0, 6);
+    cl.set_piece(true, Piece::q, 0, 4);
+    assert!(!contains(&vec, (7, 6)));
+    assert!(!contains(&vec, (7, 2)));
 }
 
+#[test]
+fn castling_white_side_effect1() {
+    let mut cl = ChessLogic::new();
+    cl.all_empty(true);
+    cl.set_piece(true, Piece::K, 7, 4);
+    cl.set_piece(true, Piece::R, 7, 7);
+    cl.set_piece(true, Piece::R, 7, 0);
+    let vec = cl.get_legal_moves(true, 7, 4);
+    cl.print_w_legal(true, &vec);
+    assert!(contains(&vec, (7, 6)));
+    assert!(contains(&vec, (7, 2)));
+
+    assert_eq!(cl.movemaker(true, 7, 4, 7, 6), Ok(true));
+    cl.print(true);
+    assert!(cl.chess_board1.board[7][7] == Piece::E);
+    assert!(cl.chess_board1.board[7][4] == Piece::E);
+    assert!(cl.chess_board1.board[7][6] == Piece::K);
+    assert!(cl.chess_board1.board[7][5] == Piece::R);
+}
 
+#[test]
+fn castling_white_side_effect2() {
+    let mut cl = ChessLogic::new();
+    cl.all_empty(true);
+    cl.set_piece(true, Piece::K, 7, 4);
+    cl.set_piece(true, Piece::R, 7, 7);
+    cl.set_piece(true, Piece::R, 7, 0);
+    let vec = cl.get_legal_moves(true, 7, 4);
+    cl.print_w_legal(true, &vec);
+    assert!(contains(&vec, (7, 6)));
+    assert!(contains(&vec, (7, 2)));
+
+    assert_eq!(cl.movemaker(true, 7, 4, 7, 2), Ok(true));
+    assert!(cl.chess_board1.board[7][0] == Piece::E);
+    assert!(cl.chess_board1.board[7][4] == Piece::E);
+    assert!(cl.chess_board1.board[7][2] == Piece::K);
+    assert!(cl.chess_board1.board[7][3] == Piece::R);
+}
 
+#[test]
+fn castling_white_side_effect3() {
+    let mut cl = ChessLogic::new();
+    cl.all_empty(false);
+    cl.set_piece(false, Piece::K, 7, 4);
+    cl.set_piece(false, Piece::R, 7, 7);
+    cl.set_piece(false, Piece::R, 7, 0);
+    let vec = cl.get_legal_moves(false, 7, 4);
+    cl.print_w_legal(false, &vec);
+    assert!(contains(&vec, (7, 6)));
+    assert!(contains(&vec, (7, 2)));
+
+    assert_eq!(cl.movemaker(false, 7, 4, 7, 2), Ok(true));
+    assert!(cl.chess_board2.board[7][0] == Piece::E);
+    assert!(cl.chess_board2.board[7][4] == Piece::E);
+    assert!(cl.chess_board2.board[7][2] == Piece::K);
+    assert!(cl.chess_board2.board[7][3] == Piece::R);
+}
 
 #[test]
-fn parse(){
-    if let Some(vec) = parser::parse(&String::from("e2-e4")) {
-        assert_eq!([6,4,4,4],vec);
-    }else{
-        assert!(false);
-    }
+fn up() {
+    let mut cl = ChessLogic::new();
+    cl.all_empty(true);
+    cl.set_piece(true, Piece::Ur, 0, 7);
+    let vec = cl.get_legal_moves(true, 0, 7);
+    cl.print_w_legal(true, &vec);
+    assert!(!contains(&vec, (1, 1)));
+}
 
-    if let  Some(vec) = parser::parse(&String::from("a1-a3")) {
-        assert_eq!([7,0,5,0],vec);
-    }else{
-        assert!(false);
-    }
-    if let Some(vec) = parser::parse(&String::from("d2-d4")) {
-        assert_eq!([6,3,4,3],vec);
-    }else{
-        assert!(false);
-    }
-    if let Some(vec) = parser::parse(&String::from("h8-h1")) {
-        assert_eq!([0,7,7,7],vec);
-    }else{
-        assert!(false);
-    }
- 
+#[test]
+fn trivial_yfen() {
+    let mut cl = ChessLogic::new();
+    let mut a = gen_fen(&cl);
+    let mut st = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string();
+    let (b, c) = a;
+    println!("{}", b);
+    println!("{}", c);
+    assert_eq!(st, b);
+    assert_eq!(st, c);
+
+    assert_eq!(cl.movemaker(true, 6, 4, 4, 4), Ok(true));
+    a = gen_fen(&cl);
+    st = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1".to_string();
+    let (b, c) = a;
+    println!("{}", b);
+    println!("{}", c);
+    println!("{:?}", cl.get_pawn_in_last_turn(true));
+    assert_eq!(st, b);
+
+    assert_eq!(cl.movemaker(true, 1, 2, 3, 2), Ok(true));
+    a = gen_fen(&cl);
+    st = "rnbqkbnr/pp1ppppp/8/2p5/4P3/8/PPPP1PPP/RNBQKBNR w KQkq c6 0 2".to_string();
+    let (b, c) = a;
+    println!("{}", b);
+    println!("{}", c);
+    println!("{:?}", cl.get_pawn_in_last_turn(true));
+    assert_eq!(st, b);
+
+    assert_eq!(cl.movemaker(true, 7, 6, 5, 5), Ok(true));
+    a = gen_fen(&cl);
+    st = "rnbqkbnr/pp1ppppp/8/2p5/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2".to_string();
+    let (b, c) = a;
+    println!("{}", b);
+    println!("{}", c);
+    println!("{:?}", cl.get_pawn_in_last_turn(true));
+    assert_eq!(st, b);
+}
+
+#[test]
+fn clock_fischer() {
+    use crate::logic::clock::*;
+    use std::rc::Rc;
+    use std::time::Duration;
+    let mut cl = ChessLogic::new();
+    let src = ManualClock::new();
+    cl.set_clock(
+        TimeControl::new(Duration::from_secs(60), Increment::Fischer(Duration::from_secs(2))),
+        Rc::new(src.clone()),
+    );
+    cl.start_clock();
+    src.advance(Duration::from_secs(10));
+    assert!(cl.get_remaining_time(true, true) == Some(Duration::from_secs(50)));
+    assert!(cl.get_remaining_time(false, true) == Some(Duration::from_secs(50)));
+    assert!(cl.movemaker(true, 6, 4, 4, 4) == Ok(true));
+    assert!(cl.get_remaining_time(true, true) == Some(Duration::from_secs(52)));
+    src.advance(Duration::from_secs(5));
+    assert!(cl.get_remaining_time(true, true) == Some(Duration::from_secs(52)));
+    assert!(cl.get_remaining_time(true, false) == Some(Duration::from_secs(55)));
+    assert!(cl.get_remaining_time(false, true) == Some(Duration::from_secs(45)));
+}
+
+#[test]
+fn clock_delays() {
+    use crate::logic::clock::*;
+    use std::rc::Rc;
+    use std::time::Duration;
+    let mut cl = ChessLogic::new();
+    let src = ManualClock::new();
+    cl.set_clock(
+        TimeControl::new(Duration::from_secs(60), Increment::Bronstein(Duration::from_secs(3))),
+        Rc::new(src.clone()),
+    );
+    cl.start_clock();
+    src.advance(Duration::from_secs(2));
+    assert!(cl.movemaker(true, 6, 4, 4, 4) == Ok(true));
+    assert!(cl.get_remaining_time(true, true) == Some(Duration::from_secs(60)));
+    src.advance(Duration::from_secs(10));
+    assert!(cl.movemaker(true, 1, 4, 3, 4) == Ok(true));
+    assert!(cl.get_remaining_time(true, false) == Some(Duration::from_secs(53)));
+
+    let mut cl = ChessLogic::new();
+    let src = ManualClock::new();
+    cl.set_clock(
+        TimeControl::new(Duration::from_secs(60), Increment::Delay(Duration::from_secs(5))),
+        Rc::new(src.clone()),
+    );
+    cl.start_clock();
+    src.advance(Duration::from_secs(4));
+    assert!(cl.get_remaining_time(true, true) == Some(Duration::from_secs(60)));
+    src.advance(Duration::from_secs(4));
+    assert!(cl.get_remaining_time(true, true) == Some(Duration::from_secs(57)));
+    assert!(cl.movemaker(true, 6, 4, 4, 4) == Ok(true));
+    assert!(cl.get_remaining_time(true, true) == Some(Duration::from_secs(57)));
+}
+
+#[test]
+fn clock_flag_fall() {
+    use crate::logic::clock::*;
+    use std::rc::Rc;
+    use std::time::Duration;
+    let mut cl = ChessLogic::new();
+    let src = ManualClock::new();
+    cl.set_clock(
+        TimeControl::new(Duration::from_secs(30), Increment::None),
+        Rc::new(src.clone()),
+    );
+    assert!(cl.movemaker(false, 6, 4, 4, 4) == Ok(true));
+    src.advance(Duration::from_secs(29));
+    assert!(!cl.check_flags());
+    //the clock of board1 has not started yet
+    assert!(cl.get_remaining_time(true, true) == Some(Duration::from_secs(30)));
+    src.advance(Duration::from_secs(1));
+    assert!(cl.check_flags());
+    assert!(cl.get_winner(false) == Winner::W2);
+    assert!(cl.movemaker(true, 6, 4, 4, 4) == Err(MoveError::AlreadyOver));
 }
 
 #[test]
-fn castling_black(){
+fn undo_redo_capture() {
     let mut cl = ChessLogic::new();
     cl.all_empty(true);
-    cl.set_piece(true,Piece::k,0,4);
-    cl.set_piece(true,Piece::r,0,7);
-    cl.set_piece(true,Piece::r,0,0);
-    let vec = cl.get_legal_moves(true,0,4);
-    cl.print_w_legal(true,&vec);
-    assert!(contains(&vec,(0,6)));
-    assert!(contains(&vec,(0,2)));
+    cl.set_piece(true, Piece::R, 0, 0);
+    cl.set_piece(true, Piece::r, 4, 0);
+    cl.white_active_1 = false;
+    let before = gen_fen(&cl);
+    assert!(cl.movemaker(true, 4, 0, 0, 0) == Ok(true));
+    assert!(cl.get_captured_piece(false, true, 1) == 1);
+    let after = gen_fen(&cl);
+
+    assert!(
+        cl.undo()
+            == Some(Action::Move {
+                board: BoardId::Board1,
+                mv: Move::new(sq(4, 0), sq(0, 0))
+            })
+    );
+    assert!(cl.get_captured_piece(false, true, 1) == 0);
+    assert!(cl.chess_board1.board[0][0] == Piece::R);
+    assert!(cl.chess_board1.board[4][0] == Piece::r);
+    assert_eq!(gen_fen(&cl), before);
+    assert!(cl.undo().is_none());
+
+    assert!(cl.redo().is_some());
+    assert!(cl.get_captured_piece(false, true, 1) == 1);
+    assert_eq!(gen_fen(&cl), after);
+    assert!(cl.redo().is_none());
+}
 
-    cl.set_piece(true,Piece::R,7,1);
-    let vec = cl.get_legal_moves(true,0,4);
-    assert!(contains(&vec,(0,6)));
-    assert!(!contains(&vec,(0,2)));
+#[test]
+fn undo_both_boards() {
+    let mut cl = ChessLogic::new();
+    let start = gen_fen(&cl);
+    assert!(cl.movemaker(true, 6, 4, 4, 4) == Ok(true));
+    assert!(cl.movemaker(false, 6, 3, 4, 3) == Ok(true));
+    assert!(cl.movemaker(true, 1, 4, 3, 4) == Ok(true));
+    assert_eq!(cl.get_history().len(), 3);
+
+    //the latest action is taken back first, regardless of the board
+    assert!(cl.undo().unwrap().get_board().is_board1());
+    assert!(!cl.undo().unwrap().get_board().is_board1());
+    assert!(!cl.get_white_active(true));
+    assert!(cl.get_white_active(false));
+    assert!(cl.undo().unwrap().get_board().is_board1());
+    assert_eq!(gen_fen(&cl), start);
+    assert_eq!(cl.get_movectr(true), 1);
+
+    //a new move clears the redo stack
+    assert!(cl.redo().is_some());
+    assert!(cl.movemaker(false, 6, 3, 4, 3) == Ok(true));
+    assert!(cl.redo().is_none());
+}
 
-    cl.set_piece(true,Piece::R,7,6);
-    let vec = cl.get_legal_moves(true,0,4);
-    assert!(!contains(&vec,(0,6)));
-    assert!(!contains(&vec,(0,2)));
+#[test]
+fn undo_promotion_and_drop() {
+    let mut cl = ChessLogic::new();
+    cl.all_empty(true);
+    cl.set_piece(true, Piece::P, 1, 7);
+    cl.set_piece(true, Piece::n, 0, 6);
+    cl.upgrade_to1 = Piece::Q;
+    assert!(cl.movemaker(true, 1, 7, 0, 6) == Ok(true));
+    assert!(cl.chess_board1.board[0][6] == Piece::UQ);
+    assert!(cl.get_captured_piece(false, false, 2) == 1);
+    assert!(
+        cl.undo()
+            == Some(Action::Move {
+                board: BoardId::Board1,
+                mv: Move::with_promotion(sq(1, 7), sq(0, 6), Piece::Q)
+            })
+    );
+    assert!(cl.upgrade_to1 == Piece::Q);
+    assert!(cl.chess_board1.board[1][7] == Piece::P);
+    assert!(cl.chess_board1.board[0][6] == Piece::n);
+    assert!(cl.get_captured_piece(false, false, 2) == 0);
+
+    cl.recv_piece(false, true, Piece::N);
+    assert!(cl.deploy_piece(false, true, Piece::N, 4, 4) == Ok(true));
+    assert!(cl.get_captured_piece(false, true, 2) == 0);
+    cl.undo();
+    assert!(cl.get_captured_piece(false, true, 2) == 1);
+    assert!(cl.chess_board2.board[4][4] == Piece::E);
+}
+
+#[test]
+fn all_legal_actions() {
+    let cl = ChessLogic::new();
+    let borrowed = &cl;
+    assert_eq!(
+        borrowed
+            .get_all_legal_actions(Seat::new(BoardId::Board1, Color::White))
+            .len(),
+        20
+    );
+    assert_eq!(
+        borrowed
+            .get_all_legal_actions(Seat::new(BoardId::Board2, Color::Black))
+            .len(),
+        20
+    );
 
-    cl.set_piece(true,Piece::E,7,1);
-    cl.set_piece(true,Piece::E,7,6);
-    cl.set_piece(true,Piece::Q,7,4);
-    assert!(!contains(&vec,(0,6)));
-    assert!(!contains(&vec,(0,2)));
+    let mut cl = ChessLogic::new();
+    cl.all_empty(true);
+    cl.set_piece(true, Piece::K, 7, 4);
+    cl.set_piece(true, Piece::R, 7, 7);
+    cl.set_piece(true, Piece::P, 1, 0);
+    cl.set_piece(true, Piece::k, 0, 7);
+    cl.recv_piece(true, true, Piece::P);
+    cl.recv_piece(true, true, Piece::N);
+    let vec = cl.get_all_legal_actions(Seat::new(BoardId::Board1, Color::White));
+
+    //castling
+    assert!(vec.contains(&Action::Move {
+        board: BoardId::Board1,
+        mv: Move::new(sq(7, 4), sq(7, 6))
+    }));
+    //every promotion
+    for promotion in [Piece::Q, Piece::R, Piece::B, Piece::N].iter() {
+        assert!(vec.contains(&Action::Move {
+            board: BoardId::Board1,
+            mv: Move::with_promotion(sq(1, 0), sq(0, 0), *promotion)
+        }));
+    }
+    assert!(!vec.contains(&Action::Move {
+        board: BoardId::Board1,
+        mv: Move::new(sq(1, 0), sq(0, 0))
+    }));
+    //60 empty squares for the knight, the last rank is not allowed for the pawn
+    let drops = cl.get_legal_drops(Seat::new(BoardId::Board1, Color::White));
+    assert_eq!(drops.len(), 60 + 53);
+    assert!(drops.contains(&Action::Drop {
+        board: BoardId::Board1,
+        drop: Drop::new(Piece::N, sq(0, 0))
+    }));
+    assert!(!drops.contains(&Action::Drop {
+        board: BoardId::Board1,
+        drop: Drop::new(Piece::P, sq(0, 0))
+    }));
+    assert!(drops.contains(&Action::Drop {
+        board: BoardId::Board1,
+        drop: Drop::new(Piece::P, sq(7, 0))
+    }));
+    assert!(cl.get_legal_drops(Seat::new(BoardId::Board1, Color::Black)).is_empty());
+
+    //en passant
+    let mut cl = ChessLogic::new();
+    cl.all_empty(true);
+    cl.set_piece(true, Piece::p, 4, 0);
+    cl.set_piece(true, Piece::P, 4, 1);
+    cl.set_pawn_in_last_turn(true, Some((4, 1)));
+    let vec = cl.get_all_legal_actions(Seat::new(BoardId::Board1, Color::Black));
+    assert!(vec.contains(&Action::Move {
+        board: BoardId::Board1,
+        mv: Move::new(sq(4, 0), sq(5, 1))
+    }));
+}
+
+#[test]
+fn typed_squares() {
+    assert!(Square::from_name("e4") == Square::new(4, 4));
+    assert!(Square::from_name("a8") == Square::new(0, 0));
+    assert!(Square::from_name("h1") == Square::new(7, 7));
+    assert!(Square::from_name("i1").is_none());
+    assert!(Square::from_name("e44").is_none());
+    assert!(Square::new(8, 0).is_none());
+    assert_eq!(sq(6, 4).name(), "e2");
+
+    assert!(Move::from_name("e2-e4") == Some(Move::new(sq(6, 4), sq(4, 4))));
+    assert!(Move::from_name("e2e4") == Some(Move::new(sq(6, 4), sq(4, 4))));
+    assert!(Move::from_name("a7a8q") == Some(Move::with_promotion(sq(1, 0), sq(0, 0), Piece::Q)));
+    assert!(Move::from_name("e2-e9").is_none());
+    assert_eq!(
+        Move::with_promotion(sq(1, 0), sq(0, 0), Piece::n).to_string(),
+        "a7-a8=N"
+    );
+
+    assert!(Drop::from_name("N@f3", false) == Some(Drop::new(Piece::n, sq(5, 5))));
+    assert!(Drop::from_name("@f3", true) == Some(Drop::new(Piece::P, sq(5, 5))));
+    assert!(Drop::from_name("K@f3", true).is_none());
+    assert_eq!(Drop::new(Piece::q, sq(5, 5)).to_string(), "Q@f3");
+}
+
+#[test]
+fn typed_api() {
+    let mut cl = ChessLogic::new();
+    assert!(cl.make_move(BoardId::Board1, Move::from_name("e2-e4").unwrap()) == Ok(true));
+    assert!(cl.piece_at(BoardId::Board1, sq(4, 4)) == Piece::P);
+    assert!(cl.make_move(BoardId::Board1, Move::from_name("e2-e4").unwrap()) == Err(MoveError::NotLegal));
+    assert_eq!(
+        cl.legal_moves_from(BoardId::Board2, Square::from_name("g1").unwrap())
+            .len(),
+        2
+    );
+    assert!(cl.is_square_attacked(BoardId::Board1, Color::Black, Square::from_name("d6").unwrap()));
+    assert!(cl.find_square(Piece::k, BoardId::Board1) == Square::from_name("e8"));
+
+    cl.recv_piece(true, false, Piece::n);
+    assert!(cl.drop_piece(BoardId::Board1, Drop::from_name("N@f3", false).unwrap()) == Ok(true));
+    assert!(cl.piece_at(BoardId::Board1, sq(5, 5)) == Piece::n);
+
+    let mut cl = ChessLogic::new();
+    cl.all_empty(false);
+    cl.set_piece(false, Piece::p, 6, 7);
+    cl.white_active_2 = false;
+    assert!(cl.make_move(BoardId::Board2, Move::with_promotion(sq(6, 7), sq(7, 7), Piece::R)) == Ok(true));
+    assert!(cl.chess_board2.board[7][7] == Piece::Ur);
+}
+
+#[test]
+fn seats_and_teams() {
+    let w1 = Seat::new(BoardId::Board1, Color::White);
+    assert!(w1.partner() == Seat::new(BoardId::Board2, Color::Black));
+    assert!(w1.partner().partner() == w1);
+    assert!(w1.opponent().team() == Team::Team2);
+    assert!(w1.partner().team() == Team::Team1);
+    assert!(Team::Team2.seats()[1] == Seat::new(BoardId::Board2, Color::White));
+    assert!(Winner::from_seat(w1.opponent()) == Winner::B1);
+    assert!(Winner::W2.team() == Some(Team::Team2));
+    assert!(Winner::P.team().is_none());
+
+    //a capture goes to the pool of the partner
+    let mut cl = ChessLogic::new();
+    cl.set_piece(true, Piece::n, 5, 5);
+    assert!(cl.is_turn(w1));
+    assert!(cl.make_move(BoardId::Board1, Move::from_name("e2-f3").unwrap()) == Ok(true));
+    assert!(cl.get_pool(w1.partner()) == [0, 0, 1, 0, 0]);
+    assert!(cl.get_pool(w1) == [0; 5]);
+    assert!(!cl.is_turn(w1));
+
+    assert!(cl.get_team_result() == TeamResult::Ongoing);
+    cl.resign_seat(Seat::new(BoardId::Board2, Color::White));
+    assert!(cl.get_team_result() == TeamResult::Won(Team::Team1));
 }
 
 #[test]
-fn castling_white(){
+fn san_scholars_mate() {
     let mut cl = ChessLogic::new();
+    let b = BoardId::Board1;
+    for (m, expected) in [
+        ("e4", "e4"),
+        ("e5", "e5"),
+        ("Bc4", "Bc4"),
+        ("Nc6", "Nc6"),
+        ("Qh5", "Qh5"),
+        ("Nf6", "Nf6"),
+    ]
+    .iter()
+    {
+        let action = san::from_san(&cl, b, m).unwrap();
+        assert_eq!(san::to_san(&cl, &action).unwrap(), *expected);
+        assert!(cl.play(action) == Ok(true));
+    }
+    let action = san::from_san(&cl, b, "Qxf7").unwrap();
+    assert_eq!(san::to_san(&cl, &action).unwrap(), "Qxf7#");
+    //black is not active
+    assert!(san::from_san(&cl, b, "a6").is_none());
+    assert!(san::from_san(&cl, b, "Qxf8").is_none());
+    //board2 is untouched
+    assert!(cl.play_san(BoardId::Board2, "Nf3") == Ok(true));
+    assert!(cl.play_san(BoardId::Board2, "Nf3") == Err(MoveError::NotLegal));
+}
+
+#[test]
+fn san_special_moves() {
+    let mut cl = ChessLogic::new();
+    let b = BoardId::Board1;
     cl.all_empty(true);
-    cl.set_piece(true,Piece::K,7,4);
-    cl.set_piece(true,Piece::R,7,7);
-    cl.set_piece(true,Piece::R,7,0);
-    let vec = cl.get_legal_moves(true,7,4);
-    cl.print_w_legal(true,&vec);
-    assert!(contains(&vec,(7,6)));
-    assert!(contains(&vec,(7,2)));
+    cl.set_piece(true, Piece::K, 7, 4);
+    cl.set_piece(true, Piece::R, 7, 7);
+    cl.set_piece(true, Piece::N, 7, 1);
+    cl.set_piece(true, Piece::N, 5, 5);
+    cl.set_piece(true, Piece::N, 3, 5);
+    cl.set_piece(true, Piece::P, 1, 1);
+    cl.set_piece(true, Piece::k, 0, 6);
+    cl.set_piece(true, Piece::r, 0, 0);
+
+    //knights on b1, f3 and f5 can reach d4 or d2
+    assert!(san::from_san(&cl, b, "Nd2").is_none());
+    assert!(
+        san::from_san(&cl, b, "Nbd2").unwrap()
+            == Action::Move {
+                board: b,
+                mv: Move::from_name("b1-d2").unwrap()
+            }
+    );
+    assert_eq!(
+        san::to_san(&cl, &san::from_san(&cl, b, "N3d4").unwrap()).unwrap(),
+        "N3d4"
+    );
+    assert_eq!(
+        san::to_san(&cl, &san::from_san(&cl, b, "Nfd2").unwrap()).unwrap(),
+        "Nfd2"
+    );
+
+    //promotion with capture
+    let action = san::from_san(&cl, b, "bxa8=Q").unwrap();
+    assert!(
+        action
+            == Action::Move {
+                board: b,
+                mv: Move::with_promotion(sq(1, 1), sq(0, 0), Piece::Q)
+            }
+    );
+    assert_eq!(san::to_san(&cl, &action).unwrap(), "bxa8=Q+");
+    assert!(san::from_san(&cl, b, "bxa8").is_none());
+    assert!(san::from_san(&cl, b, "b8N").is_some());
+
+    //castling and deploys
+    assert_eq!(san::to_san(&cl, &san::from_san(&cl, b, "0-0").unwrap()).unwrap(), "O-O");
+    assert!(san::from_san(&cl, b, "O-O-O").is_none());
+    assert!(san::from_san(&cl, b, "Q@g7").is_none());
+    cl.recv_piece(true, true, Piece::Q);
+    let action = san::from_san(&cl, b, "Q@g7").unwrap();
+    assert_eq!(san::to_san(&cl, &action).unwrap(), "Q@g7#");
+    cl.recv_piece(true, true, Piece::P);
+    assert_eq!(
+        san::to_san(&cl, &san::from_san(&cl, b, "@e4").unwrap()).unwrap(),
+        "P@e4"
+    );
+    assert!(cl.play_san(b, "O-O").is_ok());
+    assert!(cl.piece_at(b, sq(7, 5)) == Piece::R);
+}
+
+#[test]
+fn bfen_round_trip() {
+    let mut cl = ChessLogic::new();
+    assert_eq!(
+        gen_bfen(&cl).0,
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR[] w KQkq - 0 1"
+    );
+    assert!(cl.play_san(BoardId::Board1, "e4") == Ok(true));
+    assert!(cl.play_san(BoardId::Board1, "d5") == Ok(true));
+    assert!(cl.play_san(BoardId::Board1, "exd5") == Ok(true));
+    assert!(cl.play_san(BoardId::Board2, "Nf3") == Ok(true));
+    //promoted pieces on both boards
+    cl.set_piece(true, Piece::UQ, 2, 0);
+    cl.set_piece(false, Piece::Un, 5, 0);
+    cl.recv_piece(false, true, Piece::Q);
+
+    let s = gen_bfen_combined(&cl);
+    assert_eq!(
+        s,
+        "rnbqkbnr/ppp1pppp/Q~7/3P4/8/8/PPPP1PPP/RNBQKBNR[] b KQkq - 0 2 | \
+rnbqkbnr/pppppppp/8/8/8/n~4N2/PPPPPPPP/RNBQKB1R[Qp] b KQkq - 1 1"
+    );
+    let read = read_bfen_combined(&s).unwrap();
+    assert!(read.chess_board1.board == cl.chess_board1.board);
+    assert!(read.chess_board2.board == cl.chess_board2.board);
+    assert!(read.get_pools() == cl.get_pools());
+    assert_eq!(gen_bfen_combined(&read), s);
+
+    let s = "r3k2r/8/8/3pP3/8/8/8/R3K2R[QRBNPqrbnpp] w Kq d6 3 20".to_string();
+    let read = read_bfen(&s, &s).unwrap();
+    assert!(read.get_pools().1 == [2, 1, 1, 1, 1]);
+    assert!(read.get_pawn_in_last_turn(false) == Some((3, 3)));
+    assert!(read.get_white_active(true));
+    //the pocket is written in the order of the pools
+    assert_eq!(
+        gen_bfen(&read).1,
+        "r3k2r/8/8/3pP3/8/8/8/R3K2R[PRNBQpprnbq] w Kq d6 3 20"
+    );
 
-    cl.set_piece(true,Piece::r,0,1);
-    let vec = cl.get_legal_moves(true,7,4);
-    assert!(contains(&vec,(7,6)));
-    assert!(!contains(&vec,(7,2)));
+    //illegal inputs
+    let ok = "8/8/8/8/8/8/8/8[] w - - 0 1".to_string();
+    assert!(read_bfen(&ok, &ok).is_some());
+    for bad in [
+        "8/8/8/8/8/8/8[] w - - 0 1",
+        "8/8/8/8/8/8/8/9[] w - - 0 1",
+        "8/8/8/8/8/8/8/K~7[] w - - 0 1",
+        "8/8/8/8/8/8/8/8[Qk] w - - 0 1",
+        "8/8/8/8/8/8/8/8[] x - - 0 1",
+        "8/8/8/8/8/8/8/8[] w - e4 0 1",
+        "8/8/8/8/8/8/8/8[] w - - a 1",
+        "8/8/8/8/8/8/8/8[ w - - 0 1",
+    ]
+    .iter()
+    {
+        assert!(read_bfen(bad, &ok).is_none(), "{}", bad);
+    }
+    assert!(read_bfen_combined(&ok).is_none());
 
-    cl.set_piece(true,Piece::r,0,6);
-    let vec = cl.get_legal_moves(true,7,4);
-    assert!(!contains(&vec,(7,6)));
-    assert!(!contains(&vec,(7,2)));
+    //the move number also counts a black promotion
+    let mut cl = ChessLogic::new();
+    for m in ["h3", "a5", "h4", "a4", "h5", "a3", "h6", "axb2", "hxg7", "bxa1=Q"].iter() {
+        assert!(cl.play_san(BoardId::Board2, m) == Ok(true), "{}", m);
+    }
+    let s = gen_bfen(&cl).1;
+    assert!(
+        s.starts_with("rnbqkbnr/1pppppPp/8/8/8/8/P1PPPPP1/q~NBQKBNR[] w ") && s.ends_with(" - 0 6"),
+        "{}",
+        s
+    );
+    assert!(gen_fen(&cl).1.ends_with(" - 0 6"));
+    let read = read_bfen(&gen_bfen(&cl).0, &s).unwrap();
+    assert_eq!(gen_bfen(&read).1, s);
+}
 
-    cl.set_piece(true,Piece::E,0,1);
-    cl.set_piece(true,Piece::E,0,6);
-    cl.set_piece(true,Piece::q,0,4);
-    assert!(!contains(&vec,(7,6)));
-    assert!(!contains(&vec,(7,2)));
+#[test]
+fn rules_checkmate_termination() {
+    use crate::logic::rules::{Rules, Termination};
+    let mut rules = Rules::new();
+    rules.termination = Termination::Checkmate;
+
+    //the bishop is pinned
+    for (r, expected) in [(Rules::new(), Ok(true)), (rules, Err(MoveError::NotLegal))].iter() {
+        let mut cl = ChessLogic::with_rules(*r);
+        cl.all_empty(true);
+        cl.set_piece(true, Piece::K, 7, 4);
+        cl.set_piece(true, Piece::B, 6, 4);
+        cl.set_piece(true, Piece::P, 6, 0);
+        cl.set_piece(true, Piece::r, 0, 4);
+        cl.set_piece(true, Piece::k, 0, 0);
+        let bishop_moves = cl
+            .get_all_legal_actions(Seat::new(BoardId::Board1, Color::White))
+            .iter()
+            .filter(|a| match a {
+                Action::Move { mv, .. } => mv.from == sq(6, 4),
+                _ => false,
+            })
+            .count();
+        assert_eq!(bishop_moves, if *expected == Ok(true) { 9 } else { 0 });
+        assert!(cl.movemaker(true, 6, 4, 5, 3) == *expected);
+    }
+
+    //scholar's mate ends the board, with king capture black can still play on
+    for (r, winner) in [(Rules::new(), Winner::N), (rules, Winner::W1)].iter() {
+        let mut cl = ChessLogic::with_rules(*r);
+        for m in ["e4", "e5", "Bc4", "Nc6", "Qh5", "Nf6", "Qxf7"].iter() {
+            assert!(cl.play_san(BoardId::Board1, m) == Ok(true), "{}", m);
+        }
+        assert!(cl.get_winner(true) == *winner);
+    }
+
+    //a check on the back rank can be blocked by a deploy, black has to wait for a piece
+    let mut cl = ChessLogic::with_rules(rules);
+    cl.all_empty(true);
+    cl.set_piece(true, Piece::K, 7, 4);
+    cl.set_piece(true, Piece::R, 7, 0);
+    cl.set_piece(true, Piece::k, 0, 7);
+    cl.set_piece(true, Piece::p, 1, 6);
+    cl.set_piece(true, Piece::p, 1, 7);
+    cl.set_piece(true, Piece::p, 4, 2);
+    assert!(cl.play_san(BoardId::Board1, "Ra8") == Ok(true));
+    let black = Seat::new(BoardId::Board1, Color::Black);
+    assert!(cl.is_checkmate(black));
+    assert!(cl.get_winner(true) == Winner::N);
+    assert!(cl.play_san(BoardId::Board1, "c3") == Err(MoveError::NotLegal));
+    cl.recv_piece(true, false, Piece::n);
+    assert!(cl.play_san(BoardId::Board1, "N@g8") == Ok(true));
 }
 
 #[test]
-fn castling_white_side_effect1(){
+fn rules_drops_and_pools() {
+    use crate::logic::compact::CompactMove;
+    use crate::logic::rules::Rules;
+    let white = Seat::new(BoardId::Board1, Color::White);
+
+    //pawns on the first rank
+    let mut rules = Rules::new();
+    rules.pawn_drop_first_rank = false;
+    let mut cl = ChessLogic::with_rules(rules);
+    cl.set_piece(true, Piece::E, 7, 1);
+    cl.recv_piece(true, true, Piece::P);
+    assert_eq!(cl.get_legal_drops(white).len(), 32);
+    assert!(cl.deploy_piece(true, true, Piece::P, 7, 1) == Err(MoveError::CannotDeploy));
+    assert!(cl.get_pool(white) == [1, 0, 0, 0, 0]);
     let mut cl = ChessLogic::new();
+    cl.set_piece(true, Piece::E, 7, 1);
+    cl.recv_piece(true, true, Piece::P);
+    assert_eq!(cl.get_legal_drops(white).len(), 33);
+
+    //deploy mate
+    let mut rules = Rules::new();
+    rules.drop_mate = false;
+    for (r, expected) in [(Rules::new(), Ok(true)), (rules, Err(MoveError::CannotDeploy))].iter() {
+        let mut cl = ChessLogic::with_rules(*r);
+        cl.all_empty(true);
+        cl.set_piece(true, Piece::K, 7, 4);
+        cl.set_piece(true, Piece::N, 3, 5);
+        cl.set_piece(true, Piece::k, 0, 6);
+        cl.recv_piece(true, true, Piece::Q);
+        let g7 = Square::new(1, 6).unwrap();
+        let drop = Action::Drop {
+            board: BoardId::Board1,
+            drop: Drop::new(Piece::Q, g7),
+        };
+        assert_eq!(cl.get_all_legal_actions(white).contains(&drop), expected.is_ok());
+        let compact = cl.compact_moves(BoardId::Board1);
+        assert_eq!(compact.contains(&CompactMove::drop(Piece::Q, g7)), expected.is_ok());
+        assert!(compact.contains(&CompactMove::drop(Piece::Q, Square::new(2, 6).unwrap())));
+        assert!(cl.deploy_piece(true, true, Piece::Q, 1, 6) == *expected);
+    }
+    //a check is still allowed
+    let mut cl = ChessLogic::with_rules(rules);
     cl.all_empty(true);
-    cl.set_piece(true,Piece::K,7,4);
-    cl.set_piece(true,Piece::R,7,7);
-    cl.set_piece(true,Piece::R,7,0);
-    let vec = cl.get_legal_moves(true,7,4);
-    cl.print_w_legal(true,&vec);
-    assert!(contains(&vec,(7,6)));
-    assert!(contains(&vec,(7,2)));
+    cl.set_piece(true, Piece::K, 7, 4);
+    cl.set_piece(true, Piece::N, 3, 5);
+    cl.set_piece(true, Piece::k, 0, 6);
+    cl.recv_piece(true, true, Piece::Q);
+    assert!(cl.play_san(BoardId::Board1, "Q@g6") == Ok(true));
+
+    //promoted pieces
+    let mut rules = Rules::new();
+    rules.promoted_revert = false;
+    for (r, pool) in [(Rules::new(), [1, 0, 0, 0, 0]), (rules, [0, 0, 0, 0, 1])].iter() {
+        let mut cl = ChessLogic::with_rules(*r);
+        cl.set_piece(true, Piece::Uq, 5, 5);
+        assert!(cl.play_san(BoardId::Board1, "exf3") == Ok(true));
+        assert!(cl.get_pool(white.partner()) == *pool);
+    }
 
-    cl.movemaker(true,7,4,7,6);
-    cl.print(true);
-    assert!(cl.chess_board1.board[7][7]==Piece::E);
-    assert!(cl.chess_board1.board[7][4]==Piece::E);
-    assert!(cl.chess_board1.board[7][6]==Piece::K);
-    assert!(cl.chess_board1.board[7][5]==Piece::R);
+    //rook deploy on the home square
+    let mut rules = Rules::new();
+    rules.rook_drop_castling = false;
+    for (r, moved) in [(Rules::new(), false), (rules, true)].iter() {
+        let mut cl = ChessLogic::with_rules(*r);
+        cl.set_piece(true, Piece::E, 7, 7);
+        cl.chess_board1.white_rook_k_moved = true;
+        cl.recv_piece(true, true, Piece::R);
+        assert!(cl.deploy_piece(true, true, Piece::R, 7, 7) == Ok(true));
+        assert!(cl.chess_board1.white_rook_k_moved == *moved);
+    }
 }
 
 #[test]
-fn castling_white_side_effect2(){
+fn classify_checks() {
+    use crate::logic::rules::Rules;
+    use crate::logic::types::CheckStatus;
+    let black = Seat::new(BoardId::Board1, Color::Black);
+    let mut cl = ChessLogic::with_rules(Rules::classical());
+    assert!(cl.classify(black) == CheckStatus::Safe);
+
+    //back rank check, black can only wait for a piece to block it
+    cl.all_empty(true);
+    cl.set_piece(true, Piece::K, 7, 4);
+    cl.set_piece(true, Piece::R, 7, 0);
+    cl.set_piece(true, Piece::B, 5, 1);
+    cl.set_piece(true, Piece::k, 0, 7);
+    cl.set_piece(true, Piece::p, 1, 6);
+    cl.set_piece(true, Piece::p, 1, 7);
+    assert!(cl.play_san(BoardId::Board1, "Ra8") == Ok(true));
+    assert!(cl.classify(black) == CheckStatus::Sitting);
+    assert!(cl.get_all_legal_actions(black).is_empty());
+    //with a piece in the pool it is a check
+    cl.recv_piece(true, false, Piece::r);
+    assert!(cl.classify(black) == CheckStatus::Check);
+    assert_eq!(cl.get_all_legal_actions(black).len(), 6);
+    assert!(cl.play_san(BoardId::Board1, "R@e8") == Ok(true));
+    assert!(cl.classify(black) == CheckStatus::Safe);
+
+    //the pinned pawn cannot move, in the default rules it can
+    let mut cl = ChessLogic::with_rules(Rules::classical());
+    cl.all_empty(true);
+    cl.set_piece(true, Piece::K, 7, 4);
+    cl.set_piece(true, Piece::P, 6, 3);
+    cl.set_piece(true, Piece::b, 3, 0);
+    cl.set_piece(true, Piece::k, 0, 4);
+    assert!(cl.legal_moves_from(BoardId::Board1, sq(6, 3)).is_empty());
+    assert!(cl.movemaker(true, 6, 3, 5, 3) == Err(MoveError::NotLegal));
+    cl.set_rules(Rules::new());
+    assert_eq!(cl.legal_moves_from(BoardId::Board1, sq(6, 3)).len(), 2);
+
+    //fool's mate can be blocked on g3 or f2, a contact check with a protected queen cannot
+    let mut cl = ChessLogic::with_rules(Rules::classical());
+    for m in ["f3", "e5", "g4", "Qh4"].iter() {
+        assert!(cl.play_san(BoardId::Board1, m) == Ok(true), "{}", m);
+    }
+    assert!(cl.classify(Seat::new(BoardId::Board1, Color::White)) == CheckStatus::Sitting);
+    assert!(cl.get_winner(true) == Winner::N);
+    let mut cl = ChessLogic::with_rules(Rules::classical());
+    for m in ["e4", "e5", "Bc4", "Nc6", "Qh5", "Nf6", "Qxf7"].iter() {
+        assert!(cl.play_san(BoardId::Board1, m) == Ok(true), "{}", m);
+    }
+    assert!(cl.classify(black) == CheckStatus::Checkmate);
+    assert!(cl.get_team_result() == TeamResult::Won(Team::Team1));
+}
+
+#[test]
+fn rejected_actions_leave_state() {
+    let mut cl = ChessLogic::new();
+    assert!(cl.movemaker(true, 6, 4, 4, 4) == Ok(true));
+    cl.recv_piece(true, false, Piece::n);
+    cl.set_piece(false, Piece::E, 0, 0);
+    cl.set_piece(false, Piece::P, 1, 0);
+    cl.recv_piece(false, true, Piece::P);
+
+    let before = cl.snapshot();
+    let entries = cl.get_history().len();
+    let rejected: Vec<(Result<bool, MoveError>, MoveError)> = vec![
+        (cl.movemaker(true, 6, 3, 4, 3), MoveError::NotTurn),
+        (cl.movemaker(true, 1, 4, 4, 4), MoveError::NotLegal),
+        (cl.movemaker(false, 1, 0, 0, 0), MoveError::PromotionProblem),
+        (cl.deploy_piece(true, false, Piece::n, 1, 4), MoveError::CannotDeploy),
+        (cl.deploy_piece(true, true, Piece::N, 4, 0), MoveError::NotTurn),
+        (cl.deploy_piece(true, false, Piece::q, 4, 0), MoveError::NoPieceInPool),
+        (cl.deploy_piece(true, false, Piece::N, 4, 0), MoveError::CannotDeploy),
+        (cl.deploy_piece(true, false, Piece::Un, 4, 0), MoveError::CannotDeploy),
+        (cl.deploy_piece(true, false, Piece::n, 9, 0), MoveError::NotLegal),
+        (cl.deploy_piece(false, true, Piece::P, 0, 0), MoveError::NotLegal),
+    ];
+    for (i, (res, err)) in rejected.iter().enumerate() {
+        assert!(*res == Err(*err), "{}", i);
+    }
+    assert!(cl.snapshot() == before);
+    assert_eq!(cl.get_history().len(), entries);
+
+    //a finished board rejects moves without changing anything
+    cl.resign(true, false);
+    let before = cl.snapshot();
+    assert!(cl.movemaker(true, 6, 3, 4, 3) == Err(MoveError::AlreadyOver));
+    assert!(cl.snapshot() == before);
+
+    //a stalemating move is played, then the board is over
     let mut cl = ChessLogic::new();
     cl.all_empty(true);
-    cl.set_piece(true,Piece::K,7,4);
-    cl.set_piece(true,Piece::R,7,7);
-    cl.set_piece(true,Piece::R,7,0);
-    let vec = cl.get_legal_moves(true,7,4);
-    cl.print_w_legal(true,&vec);
-    assert!(contains(&vec,(7,6)));
-    assert!(contains(&vec,(7,2)));
+    cl.set_piece(true, Piece::K, 7, 7);
+    cl.set_piece(true, Piece::Q, 7, 2);
+    cl.set_piece(true, Piece::P, 2, 0);
+    cl.set_piece(true, Piece::k, 0, 0);
+    cl.set_piece(true, Piece::p, 1, 0);
+    assert!(cl.movemaker(true, 7, 2, 1, 2) == Ok(true));
+    assert!(cl.get_piece(true, 1, 2) == Piece::Q);
+    assert!(cl.get_winner(true) == Winner::P);
+    assert_eq!(cl.get_history().len(), 1);
+}
+
+#[test]
+fn action_errors() {
+    use crate::logic::error::RejectCause;
+    use crate::logic::rules::Rules;
+    let mut cl = ChessLogic::new();
+    let mv = |s: &str| Action::Move {
+        board: BoardId::Board1,
+        mv: Move::from_name(s).unwrap(),
+    };
+    let cause = |cl: &mut ChessLogic, a: Action| cl.try_play(a).err().unwrap().cause;
+    assert!(cause(&mut cl, mv("a1-a3")) == RejectCause::BlockedPath);
+    assert!(cause(&mut cl, mv("a1-a2")) == RejectCause::OwnPiece);
+    assert!(cause(&mut cl, mv("g1-g3")) == RejectCause::IllegalPieceMove);
+    assert!(cause(&mut cl, mv("e4-e5")) == RejectCause::EmptySquare);
+    assert!(cause(&mut cl, mv("e7-e5")) == RejectCause::WrongColor);
+    assert!(cl.play(mv("e7-e5")) == Err(MoveError::NotTurn));
+
+    let err = cl.try_play(mv("a1-a3")).err().unwrap();
+    assert!(err.from == Some(sq(7, 0)) && err.to == Some(sq(5, 0)) && err.piece == Piece::R);
+    assert_eq!(err.to_string(), "board1 a1-a3: the path is blocked");
+    let boxed: Box<dyn std::error::Error> = Box::new(err);
+    assert_eq!(boxed.to_string(), "board1 a1-a3: the path is blocked");
+
+    //deploys
+    let drop = |s: &str, white: bool| Action::Drop {
+        board: BoardId::Board1,
+        drop: Drop::from_name(s, white).unwrap(),
+    };
+    cl.set_piece(true, Piece::E, 1, 0);
+    cl.recv_piece(true, true, Piece::P);
+    assert!(cause(&mut cl, drop("N@e4", true)) == RejectCause::EmptyPool);
+    assert!(cause(&mut cl, drop("P@e2", true)) == RejectCause::OccupiedSquare);
+    assert!(cause(&mut cl, drop("P@a7", false)) == RejectCause::NotTurn);
+    assert!(cause(&mut cl, drop("P@a8", true)) == RejectCause::OccupiedSquare);
+    cl.set_piece(true, Piece::E, 0, 0);
+    let err = cl.try_play(drop("P@a8", true)).err().unwrap();
+    assert!(err.cause == RejectCause::PawnDropLastRank && err.kind() == MoveError::NotLegal);
+    assert_eq!(
+        err.to_string(),
+        "board1 P@a8: a pawn cannot be deployed on the last rank"
+    );
 
-    cl.movemaker(true,7,4,7,2);
-    assert!(cl.chess_board1.board[7][0]==Piece::E);
-    assert!(cl.chess_board1.board[7][4]==Piece::E);
-    assert!(cl.chess_board1.board[7][2]==Piece::K);
-    assert!(cl.chess_board1.board[7][3]==Piece::R);
+    //promotion
+    cl.set_piece(true, Piece::P, 1, 0);
+    assert!(cause(&mut cl, mv("a7-a8")) == RejectCause::MissingPromotion);
+    let promote = Action::Move {
+        board: BoardId::Board1,
+        mv: Move::with_promotion(sq(1, 0), sq(0, 0), Piece::K),
+    };
+    assert!(cause(&mut cl, promote) == RejectCause::InvalidPromotion);
+
+    //a pinned piece in the classical rules
+    let mut classical = ChessLogic::with_rules(Rules::classical());
+    classical.all_empty(true);
+    classical.set_piece(true, Piece::K, 7, 4);
+    classical.set_piece(true, Piece::P, 6, 3);
+    classical.set_piece(true, Piece::b, 3, 0);
+    classical.set_piece(true, Piece::k, 0, 4);
+    assert!(cause(&mut classical, mv("d2-d3")) == RejectCause::LeavesKingInCheck);
+
+    //game over
+    cl.resign(true, false);
+    let err = cl.try_play(mv("e2-e4")).err().unwrap();
+    assert!(err.cause == RejectCause::GameOver(cl.get_winner(true)));
+    assert!(err.kind() == MoveError::AlreadyOver);
 }
 
 #[test]
-fn castling_white_side_effect3(){
+fn promotion_in_move() {
+    use crate::logic::error::RejectCause;
+    use crate::logic::rules::Rules;
     let mut cl = ChessLogic::new();
     cl.all_empty(false);
-    cl.set_piece(false,Piece::K,7,4);
-    cl.set_piece(false,Piece::R,7,7);
-    cl.set_piece(false,Piece::R,7,0);
-    let vec = cl.get_legal_moves(false,7,4);
-    cl.print_w_legal(false,&vec);
-    assert!(contains(&vec,(7,6)));
-    assert!(contains(&vec,(7,2)));
+    cl.set_piece(false, Piece::K, 7, 4);
+    cl.set_piece(false, Piece::k, 0, 4);
+    cl.set_piece(false, Piece::P, 1, 0);
+    cl.set_piece(false, Piece::P, 1, 1);
+    let mv = |s: &str| Move::from_name(s).unwrap();
+
+    //the choice is part of the move, a stale set_promotion is not used
+    cl.set_promotion(false, Piece::q);
+    let before = cl.snapshot();
+    let err = cl.try_move(BoardId::Board2, mv("a7-a8")).err().unwrap();
+    assert!(err.cause == RejectCause::MissingPromotion);
+    assert!(cl.try_move(BoardId::Board2, mv("e1-e2=Q")).err().unwrap().cause == RejectCause::InvalidPromotion);
+    assert!(
+        cl.make_move(BoardId::Board2, Move::with_promotion(sq(1, 0), sq(0, 0), Piece::K))
+            == Err(MoveError::PromotionProblem)
+    );
+    assert!(cl.snapshot() == before);
+    assert!(cl.make_move(BoardId::Board2, mv("a7-a8=N")) == Ok(true));
+    assert!(cl.get_piece(false, 0, 0) == Piece::UN);
+    let action = cl.get_history().last().unwrap().action;
+    assert!(
+        action
+            == Action::Move {
+                board: BoardId::Board2,
+                mv: Move::with_promotion(sq(1, 0), sq(0, 0), Piece::N)
+            }
+    );
+    cl.undo();
+    assert!(san::to_san(&cl, &action) == Some("a8=N".to_string()));
+
+    //set_promotion works for both colours on both boards
+    assert!(cl.set_promotion(false, Piece::r));
+    assert!(cl.movemaker(false, 1, 1, 0, 1) == Ok(true));
+    assert!(cl.get_piece(false, 0, 1) == Piece::UR);
+
+    //auto queen
+    let mut rules = Rules::new();
+    rules.auto_queen = true;
+    let mut cl = ChessLogic::with_rules(rules);
+    cl.all_empty(true);
+    cl.set_piece(true, Piece::K, 7, 4);
+    cl.set_piece(true, Piece::k, 0, 4);
+    cl.set_piece(true, Piece::P, 1, 0);
+    cl.set_piece(true, Piece::p, 6, 7);
+    assert!(cl.make_move(BoardId::Board1, mv("a7-a8")) == Ok(true));
+    assert!(cl.get_piece(true, 0, 0) == Piece::UQ);
+    assert!(cl.play_san(BoardId::Board1, "h1") == Ok(true));
+    assert!(cl.get_piece(true, 7, 7) == Piece::Uq);
+}
 
-    cl.movemaker(false,7,4,7,2);
-    assert!(cl.chess_board2.board[7][0]==Piece::E);
-    assert!(cl.chess_board2.board[7][4]==Piece::E);
-    assert!(cl.chess_board2.board[7][2]==Piece::K);
-    assert!(cl.chess_board2.board[7][3]==Piece::R);
+#[test]
+fn repetition_and_hashing() {
+    use crate::logic::rules::Rules;
+    let fresh = |cl: &ChessLogic| {
+        let mut c = ChessLogic::from_snapshot(&cl.snapshot());
+        c.rehash();
+        (c.get_hash(BoardId::Board1), c.get_hash(BoardId::Board2))
+    };
+    let hashes = |cl: &ChessLogic| (cl.get_hash(BoardId::Board1), cl.get_hash(BoardId::Board2));
+
+    //the incremental hash is the hash computed from scratch
+    let mut cl = ChessLogic::new();
+    let start = hashes(&cl);
+    for (board, m) in [
+        (BoardId::Board1, "e4"),
+        (BoardId::Board1, "d5"),
+        (BoardId::Board1, "exd5"),
+        (BoardId::Board2, "Nf3"),
+        (BoardId::Board2, "Nf6"),
+        (BoardId::Board2, "e3"),
+        (BoardId::Board2, "P@e4"),
+        (BoardId::Board2, "Be2"),
+        (BoardId::Board2, "a6"),
+        (BoardId::Board2, "O-O"),
+    ]
+    .iter()
+    {
+        assert!(cl.play_san(*board, m) == Ok(true), "{}", m);
+        assert!(hashes(&cl) == fresh(&cl), "{}", m);
+    }
+    assert!(hashes(&cl) != start);
+    cl.undo();
+    assert!(hashes(&cl) == fresh(&cl));
+
+    //moves on the other board do not repeat a position
+    let mut cl = ChessLogic::new();
+    for m in ["e4", "e5", "d4", "d5"].iter() {
+        assert!(cl.play_san(BoardId::Board2, m) == Ok(true));
+    }
+    assert_eq!(cl.repetition_count(BoardId::Board1), 1);
+
+    //a knight shuffle repeats the initial position
+    for (r, winner) in [
+        (Rules::new(), Winner::N),
+        (
+            Rules {
+                repetition_draw: Some(3),
+                ..Rules::new()
+            },
+            Winner::D,
+        ),
+    ]
+    .iter()
+    {
+        let mut cl = ChessLogic::with_rules(*r);
+        for round in 0..2 {
+            for m in ["Nf3", "Nf6", "Ng1", "Ng8"].iter() {
+                assert!(cl.play_san(BoardId::Board1, m) == Ok(true), "{}", m);
+            }
+            assert_eq!(cl.repetition_count(BoardId::Board1), round + 2);
+        }
+        assert!(cl.get_hash(BoardId::Board1) == ChessLogic::new().get_hash(BoardId::Board1));
+        assert!(cl.get_winner(true) == *winner);
+    }
+    let mut cl = ChessLogic::with_rules(Rules {
+        repetition_draw: Some(3),
+        ..Rules::new()
+    });
+    for m in ["Nf3", "Nf6", "Ng1", "Ng8", "Nf3", "Nf6", "Ng1", "Ng8"].iter() {
+        cl.play_san(BoardId::Board1, m).ok();
+    }
+    assert!(cl.get_team_result() == TeamResult::Draw);
+    assert!(cl.play_san(BoardId::Board2, "e4") == Err(MoveError::AlreadyOver));
 }
 
+#[test]
+fn move_rule_draws() {
+    use crate::logic::rules::{DropReset, Rules};
+    let white = Seat::new(BoardId::Board1, Color::White);
+
+    //a pawn deploy on board1 resets the counter of board1
+    let mut cl = ChessLogic::new();
+    assert!(cl.play_san(BoardId::Board2, "Nf3") == Ok(true));
+    assert!(cl.play_san(BoardId::Board1, "Nf3") == Ok(true));
+    assert!(cl.play_san(BoardId::Board1, "Nc6") == Ok(true));
+    cl.recv_piece(true, true, Piece::P);
+    assert!(cl.play_san(BoardId::Board1, "P@e4") == Ok(true));
+    assert_eq!(cl.get_half_moves(true), 0);
+    assert_eq!(cl.get_half_moves(false), 1);
+
+    //the deploys that reset the counter
+    for (reset, pawn, knight) in [
+        (DropReset::Never, 1, 2),
+        (DropReset::Pawns, 0, 1),
+        (DropReset::Always, 0, 0),
+    ]
+    .iter()
+    {
+        let mut cl = ChessLogic::with_rules(Rules {
+            drop_reset: *reset,
+            ..Rules::new()
+        });
+        cl.recv_piece(true, true, Piece::P);
+        cl.recv_piece(true, false, Piece::n);
+        assert!(cl.play_san(BoardId::Board1, "P@e4") == Ok(true));
+        assert_eq!(cl.get_half_moves(true), *pawn);
+        assert!(cl.play_san(BoardId::Board1, "N@f3") == Ok(true));
+        assert_eq!(cl.get_half_moves(true), *knight);
+    }
+
+    //automatic and claimed draws
+    for claim in [false, true].iter() {
+        let mut cl = ChessLogic::with_rules(Rules {
+            move_rule: Some(2),
+            draw_claim: *claim,
+            ..Rules::new()
+        });
+        for m in ["Nf3", "Nf6", "Ng1"].iter() {
+            assert!(cl.play_san(BoardId::Board1, m) == Ok(true));
+        }
+        assert!(!cl.is_draw(BoardId::Board1));
+        assert!(!cl.claim_draw(white));
+        assert!(cl.play_san(BoardId::Board1, "Ng8") == Ok(true));
+        assert!(cl.is_draw(BoardId::Board1) && !cl.is_draw(BoardId::Board2));
+        if *claim {
+            assert!(cl.get_winner(true) == Winner::N);
+            assert!(!cl.claim_draw(Seat::new(BoardId::Board2, Color::White)));
+            assert!(cl.claim_draw(white.opponent()));
+        }
+        assert!(cl.get_winner(true) == Winner::D);
+        assert!(cl.get_team_result() == TeamResult::Draw);
+    }
+
+    //pawn moves of both colours reset the counter on both boards
+    for board in [BoardId::Board1, BoardId::Board2].iter() {
+        let mut cl = ChessLogic::with_rules(Rules {
+            move_rule: Some(2),
+            ..Rules::new()
+        });
+        for m in ["Nf3", "e5", "Ng1", "e4"].iter() {
+            assert!(cl.play_san(*board, m) == Ok(true));
+        }
+        assert_eq!(cl.get_half_moves(board.is_board1()), 0);
+        assert!(cl.get_winner(board.is_board1()) == Winner::N);
+    }
+}
 
 #[test]
-fn up(){
+fn events() {
+    use crate::logic::events::Event;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    let sq = |s: &str| Square::from_name(s).unwrap();
+    let (b1, b2) = (BoardId::Board1, BoardId::Board2);
     let mut cl = ChessLogic::new();
-    cl.all_empty(true);
-    cl.set_piece(true,Piece::Ur,0,7);
-    let vec = cl.get_legal_moves(true,0,7);
-    cl.print_w_legal(true,&vec);
-    assert!(!contains(&vec,(1,1)));
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let s = seen.clone();
+    cl.subscribe(Box::new(move |e| s.borrow_mut().push(*e)));
+
+    //a capture sends the piece to the partner
+    for m in ["e4", "d5"].iter() {
+        assert!(cl.play_san(b1, m) == Ok(true));
+    }
+    cl.take_events();
+    assert!(cl.play_san(b1, "exd5") == Ok(true));
+    assert_eq!(
+        cl.take_events(),
+        vec![
+            Event::Captured {
+                board: b1,
+                piece: Piece::p,
+                square: sq("d5")
+            },
+            Event::Moved {
+                board: b1,
+                piece: Piece::P,
+                from: sq("e4"),
+                to: sq("d5")
+            },
+            Event::SentToPool {
+                board: b2,
+                color: Color::Black,
+                piece: Piece::p
+            },
+            Event::TurnChanged {
+                board: b1,
+                color: Color::Black
+            },
+        ]
+    );
+    assert!(cl.take_events().is_empty());
+    assert_eq!(seen.borrow().len(), 8);
+
+    //rejected actions and look ahead have no events
+    assert!(cl.play_san(b1, "Ke6") == Err(MoveError::NotLegal));
+    assert!(cl.classify(Seat::new(b1, Color::Black)) == CheckStatus::Safe);
+    assert!(cl.take_events().is_empty());
+
+    //deploys and received pieces
+    assert!(cl.play_san(b2, "e4") == Ok(true));
+    cl.take_events();
+    assert!(cl.play_san(b2, "P@e5") == Ok(true));
+    assert_eq!(
+        cl.take_events(),
+        vec![
+            Event::Dropped {
+                board: b2,
+                piece: Piece::p,
+                to: sq("e5")
+            },
+            Event::TurnChanged {
+                board: b2,
+                color: Color::White
+            },
+        ]
+    );
+    cl.recv_piece(true, true, Piece::Q);
+    assert_eq!(
+        cl.take_events(),
+        vec![Event::SentToPool {
+            board: b1,
+            color: Color::White,
+            piece: Piece::Q
+        }]
+    );
+
+    //castling and promotion
+    let mut cl = read_bfen(
+        "r3k3/1P6/8/8/8/8/8/4K2R[] w Kq - 0 1",
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR[] w KQkq - 0 1",
+    )
+    .unwrap();
+    assert!(cl.play_san(b1, "O-O") == Ok(true));
+    assert_eq!(
+        cl.take_events()[..3],
+        [
+            Event::Castled {
+                board: b1,
+                color: Color::White,
+                kingside: true
+            },
+            Event::Moved {
+                board: b1,
+                piece: Piece::K,
+                from: sq("e1"),
+                to: sq("g1")
+            },
+            Event::Moved {
+                board: b1,
+                piece: Piece::R,
+                from: sq("h1"),
+                to: sq("f1")
+            },
+        ]
+    );
+    assert!(cl.play_san(b1, "Kd8") == Ok(true));
+    cl.take_events();
+    assert!(cl.play_san(b1, "bxa8=N") == Ok(true));
+    assert_eq!(
+        cl.take_events(),
+        vec![
+            Event::Captured {
+                board: b1,
+                piece: Piece::r,
+                square: sq("a8")
+            },
+            Event::Moved {
+                board: b1,
+                piece: Piece::P,
+                from: sq("b7"),
+                to: sq("a8")
+            },
+            Event::Promoted {
+                board: b1,
+                square: sq("a8"),
+                piece: Piece::UN
+            },
+            Event::SentToPool {
+                board: b2,
+                color: Color::Black,
+                piece: Piece::r
+            },
+            Event::TurnChanged {
+                board: b1,
+                color: Color::Black
+            },
+        ]
+    );
+
+    //the end of the game
+    cl.resign(false, true);
+    assert_eq!(cl.take_events(), vec![Event::GameEnded { winner: Winner::B2 }]);
+    cl.resign(true, true);
+    assert!(cl.take_events().is_empty());
 }
 
+#[test]
+fn global_log_replay() {
+    use crate::logic::clock::*;
+    use std::rc::Rc;
+    use std::time::Duration;
+    let (b1, b2) = (BoardId::Board1, BoardId::Board2);
+    let mut cl = ChessLogic::new();
+    let src = ManualClock::new();
+    cl.set_clock(
+        TimeControl::new(Duration::from_secs(60), Increment::Fischer(Duration::from_secs(0))),
+        Rc::new(src.clone()),
+    );
+    cl.start_clock();
+    //the pawn captured on board1 is deployed on board2
+    let actions = [
+        (b1, "e4"),
+        (b2, "d4"),
+        (b1, "d5"),
+        (b1, "exd5"),
+        (b2, "P@e5"),
+        (b2, "dxe5"),
+        (b1, "Qxd5"),
+        (b2, "Nc6"),
+    ];
+    for (board, san) in actions.iter() {
+        src.advance(Duration::from_secs(1));
+        assert!(cl.play_san(*board, san) == Ok(true));
+    }
+    let log = cl.get_history();
+    for (n, e) in log.iter().enumerate() {
+        assert_eq!(e.get_seq(), n + 1);
+        assert_eq!(e.get_timestamp(), Some(Duration::from_secs(n as u64 + 1)));
+    }
+    assert!(cl.get_movectr(true) == cl.get_movectr(false));
+
+    //every intermediate state is reconstructed exactly
+    assert!(cl.replay(0).unwrap().snapshot() == *log[0].get_before());
+    for n in 1..=log.len() {
+        let r = cl.replay(n).unwrap();
+        assert!(r.snapshot() == *log[n - 1].get_after());
+        assert_eq!(r.get_history().len(), n);
+        assert_eq!(r.get_history()[n - 1].get_timestamp(), log[n - 1].get_timestamp());
+    }
+    assert!(cl.replay(log.len() + 1).is_none());
+
+    //a log in any order is played by the numbers
+    let mut shuffled = log.clone();
+    shuffled.reverse();
+    let r = ChessLogic::replay_log(log[0].get_before(), cl.get_rules(), &shuffled).unwrap();
+    assert!(r.snapshot() == cl.snapshot());
+
+    //the action after an undo gets a new number, the number of the undone action is not used again
+    let n = actions.len();
+    assert!(cl.undo().is_some());
+    assert!(cl.play_san(b2, "Nf6") == Ok(true));
+    let log = cl.get_history();
+    assert_eq!(
+        log.iter().map(|e| e.get_seq()).collect::<Vec<_>>(),
+        (1..n).chain(Some(n + 1)).collect::<Vec<_>>()
+    );
+    assert!(cl.replay(n).is_none());
+    assert!(cl.replay(n + 1).unwrap().snapshot() == cl.snapshot());
+    assert!(cl.replay(n - 1).unwrap().snapshot() == *log[n - 2].get_after());
+    assert!(cl.redo().is_none());
+    assert!(cl.undo().is_some());
+    assert!(cl.redo().is_some());
+    assert_eq!(cl.get_history().last().unwrap().get_seq(), n + 1);
+}
 
 #[test]
-fn trivial_yfen(){
-    let mut cl =ChessLogic::new();
-    let mut a = gen_fen(&mut cl);
-    let mut st = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string();
-    match a {
-        (b,c) => {
-            println!("{}",b);println!("{}",c);
-            assert_eq!(st,b); 
-            assert_eq!(st,c);},
-        _ => {},
+fn premoves() {
+    use crate::logic::error::RejectCause;
+    use crate::logic::events::Event;
+    let (b1, b2) = (BoardId::Board1, BoardId::Board2);
+    let mv = |board, s: &str| Action::Move {
+        board,
+        mv: Move::from_name(s).unwrap(),
+    };
+    let drop = |board, s: &str, white| Action::Drop {
+        board,
+        drop: Drop::from_name(s, white).unwrap(),
+    };
+    let fired = |events: &Vec<Event>| {
+        events
+            .iter()
+            .filter_map(|e| match e {
+                Event::PremoveFired { action, .. } => Some(*action),
+                _ => None,
+            })
+            .collect::<Vec<Action>>()
+    };
+    let cancelled = |events: &Vec<Event>| {
+        events
+            .iter()
+            .filter_map(|e| match e {
+                Event::PremoveCancelled { action, cause, .. } => Some((*action, *cause)),
+                _ => None,
+            })
+            .collect::<Vec<(Action, Option<RejectCause>)>>()
+    };
+    let (white1, black1) = (Seat::new(b1, Color::White), Seat::new(b1, Color::Black));
+    let black2 = Seat::new(b2, Color::Black);
+
+    //an action of the player to move is played at once, the others wait for their turn
+    let mut cl = ChessLogic::new();
+    assert!(cl.premove(Color::White, mv(b1, "e2-e4")) == Ok(true));
+    assert!(cl.premove(Color::White, mv(b1, "g1-f3")) == Ok(false));
+    assert!(cl.premove(Color::White, mv(b1, "f1-c4")) == Ok(false));
+    assert!(cl.get_premoves(white1) == vec![mv(b1, "g1-f3"), mv(b1, "f1-c4")]);
+    cl.take_events();
+    assert!(cl.play_san(b1, "e5") == Ok(true));
+    assert!(fired(&cl.take_events()) == vec![mv(b1, "g1-f3")]);
+    assert!(!cl.get_white_active(true));
+    //premoves of both players fire one after another
+    assert!(cl.premove(Color::White, mv(b1, "e1-g1")) == Ok(false));
+    assert!(cl.premove(Color::Black, mv(b1, "b8-c6")) == Ok(true));
+    assert!(fired(&cl.take_events()) == vec![mv(b1, "f1-c4")]);
+    assert!(cl.premove(Color::Black, mv(b1, "g8-f6")) == Ok(true));
+    assert!(fired(&cl.take_events()) == vec![mv(b1, "e1-g1")]);
+    assert!(cl.get_premoves(white1).is_empty() && cl.get_premoves(black1).is_empty());
+
+    //a predrop fails if the piece is not in the pool, the rest of the queue is cancelled
+    let mut cl = ChessLogic::new();
+    assert!(cl.premove(Color::Black, drop(b2, "P@e5", false)) == Ok(false));
+    assert!(cl.premove(Color::Black, mv(b2, "d7-d5")) == Ok(false));
+    assert!(cl.play_san(b2, "d4") == Ok(true));
+    let events = cl.take_events();
+    assert!(fired(&events).is_empty());
+    assert!(
+        cancelled(&events)
+            == vec![
+                (drop(b2, "P@e5", false), Some(RejectCause::EmptyPool)),
+                (mv(b2, "d7-d5"), None)
+            ]
+    );
+    assert!(cl.get_premoves(black2).is_empty());
+    assert!(!cl.get_white_active(false));
+
+    //it is played if the teammate sent the piece in time, or fails if the square became occupied
+    for (square, ok) in [("e5", true), ("d4", false)].iter() {
+        let mut cl = ChessLogic::new();
+        for m in ["e4", "d5", "exd5"].iter() {
+            assert!(cl.play_san(b1, m) == Ok(true));
+        }
+        assert!(cl.premove(Color::Black, drop(b2, &format!("P@{}", square), false)) == Ok(false));
+        cl.take_events();
+        assert!(cl.play_san(b2, "d4") == Ok(true));
+        let events = cl.take_events();
+        assert_eq!(fired(&events).len() == 1, *ok);
+        assert_eq!(
+            cancelled(&events)
+                == vec![(
+                    drop(b2, &format!("P@{}", square), false),
+                    Some(RejectCause::OccupiedSquare)
+                )],
+            !*ok
+        );
     }
 
-    cl.movemaker(true,6,4,4,4);
-    a = gen_fen(&mut cl);
-    st = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1".to_string();
-    match a {
-        (b,c) => {
-            println!("{}",b);println!("{}",c);
-            println!("{:?}",cl.get_pawn_in_last_turn(true));
-            assert_eq!(st,b); 
-           },
-        _ => {},
+    //the player can cancel, the end of the game cancels every queue
+    let mut cl = ChessLogic::new();
+    assert!(cl.premove(Color::Black, mv(b1, "e7-e5")) == Ok(false));
+    cl.cancel_premoves(black1);
+    assert!(cancelled(&cl.take_events()) == vec![(mv(b1, "e7-e5"), None)]);
+    for m in ["e4", "f5", "Qh5", "g6", "Qxg6", "a6"].iter() {
+        assert!(cl.play_san(b1, m) == Ok(true));
+    }
+    assert!(cl.premove(Color::Black, mv(b1, "h7-h6")) == Ok(false));
+    assert!(cl.premove(Color::Black, mv(b2, "e7-e5")) == Ok(false));
+    assert!(cl.play_san(b1, "Qe8") == Ok(true));
+    assert!(cancelled(&cl.take_events()).len() == 2);
+}
+
+#[test]
+fn bitboard_movegen() {
+    //a black pawn on the fifth rank cannot capture its own pieces
+    let mut cl = ChessLogic::new();
+    cl.all_empty(true);
+    cl.set_piece(true, Piece::p, 4, 3);
+    cl.set_piece(true, Piece::n, 5, 4);
+    cl.set_piece(true, Piece::N, 5, 2);
+    assert_eq!(cl.get_legal_moves(true, 4, 3), vec![(5, 3), (5, 2)]);
+
+    //only the pawn that moved last can be captured en passant
+    cl.all_empty(true);
+    cl.set_piece(true, Piece::P, 3, 4);
+    cl.set_piece(true, Piece::p, 3, 3);
+    cl.set_piece(true, Piece::p, 3, 5);
+    cl.set_pawn_in_last_turn(true, Some((3, 5)));
+    assert_eq!(cl.get_legal_moves(true, 3, 4), vec![(2, 4), (2, 5)]);
+
+    //the bitboards follow a direct change of the array after reset_bitboards or through get_board
+    cl.chess_board1.board[2][4] = Piece::p;
+    cl.reset_bitboards(true);
+    assert_eq!(cl.get_legal_moves(true, 3, 4), vec![(2, 5)]);
+    cl.get_board(true).board[2][4] = Piece::E;
+    assert_eq!(cl.get_legal_moves(true, 3, 4), vec![(2, 4), (2, 5)]);
+}
+
+#[test]
+fn perft() {
+    use crate::logic::perft;
+    use crate::logic::rules::Rules;
+    //pawns are not deployed on the first rank like in crazyhouse, captured pieces go to the other board,
+    //so positions with empty pools have the numbers of chess
+    let rules = Rules {
+        pawn_drop_first_rank: false,
+        ..Rules::classical()
+    };
+    let positions = [
+        (
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            vec![20, 400, 8902],
+        ),
+        //castling, en passant and promotions
+        (
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            vec![48, 2039],
+        ),
+        ("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", vec![14, 191, 2812, 43238]),
+        (
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            vec![6, 264],
+        ),
+        (
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+            vec![44, 1486],
+        ),
+        //deploys of every piece
+        ("2k5/8/8/8/8/8/8/4K3[QRBNPqrbnp] w - - 0 1", vec![301, 75353]),
+    ];
+    //the code of the boards is not shared, so every position is counted on both
+    for board in [BoardId::Board1, BoardId::Board2].iter() {
+        for (bfen, counts) in positions.iter() {
+            let cl = perft::position_on(bfen, *board, rules).unwrap();
+            for (depth, n) in counts.iter().enumerate() {
+                assert_eq!(
+                    perft::perft(&cl, *board, depth + 1),
+                    *n,
+                    "{} {:?} depth {}",
+                    bfen,
+                    board,
+                    depth + 1
+                );
+            }
+        }
     }
 
-    cl.movemaker(true,1,2,3,2);
-    a = gen_fen(&mut cl);
-    st = "rnbqkbnr/pp1ppppp/8/2p5/4P3/8/PPPP1PPP/RNBQKBNR w KQkq c6 0 2".to_string();
-    match a {
-        (b,c) => {
-            println!("{}",b);println!("{}",c);
-            println!("{:?}",cl.get_pawn_in_last_turn(true));
-            assert_eq!(st,b); 
-           },
-        _ => {},
+    //divide splits the number by the first action
+    let cl = perft::position("2k5/8/8/8/8/8/8/4K3[QRBNPqrbnp] w - - 0 1", rules).unwrap();
+    let split = perft::divide(&cl, BoardId::Board1, 2);
+    assert_eq!(split.len(), 301);
+    assert_eq!(split.iter().map(|(_, n)| n).sum::<u64>(), 75353);
+    let (_, n) = split.iter().find(|(a, _)| a.to_string() == "e1-d1").unwrap();
+    assert_eq!(*n, 301);
+    assert!(perft::position("8/8 w - - 0 1", rules).is_none());
+}
+
+#[test]
+fn make_unmake() {
+    use crate::logic::bitboard::Bitboards;
+    use crate::logic::compact::CompactMove;
+    use crate::logic::perft;
+    use crate::logic::rules::Rules;
+    let rules = Rules {
+        pawn_drop_first_rank: false,
+        ..Rules::classical()
+    };
+    let b1 = BoardId::Board1;
+
+    //the compact move keeps the action
+    let e2 = Square::from_name("e2").unwrap();
+    let e4 = Square::from_name("e4").unwrap();
+    for a in [
+        Action::Move {
+            board: b1,
+            mv: Move::new(e2, e4),
+        },
+        Action::Move {
+            board: b1,
+            mv: Move::with_promotion(e2, e4, Piece::N),
+        },
+        Action::Drop {
+            board: b1,
+            drop: Drop::new(Piece::Q, e4),
+        },
+    ]
+    .iter()
+    {
+        assert!(CompactMove::from_action(a).to_action(b1, true) == *a);
+    }
+    assert!(
+        CompactMove::drop(Piece::n, e4).to_action(b1, false)
+            == Action::Drop {
+                board: b1,
+                drop: Drop::new(Piece::n, e4)
+            }
+    );
+    assert!(CompactMove::drop(Piece::n, e4).is_drop() && CompactMove::drop(Piece::n, e4).get_from().is_none());
+
+    //make is play without the checks and unmake restores the state, also the hashes, the pool of the partner
+    //and the bitboards, which are updated square by square
+    fn check(cl: &mut ChessLogic, board: BoardId, depth: usize) {
+        if depth == 0 {
+            return;
+        }
+        let board1 = board.is_board1();
+        let white = cl.get_white_active(board1);
+        let before = cl.snapshot();
+        let in_sync = |cl: &ChessLogic| cl.bitboards(board1) == Bitboards::new(cl.get_board_n(board1));
+        for m in cl.compact_moves(board) {
+            let action = m.to_action(board, white);
+            let mut played = cl.preview_base();
+            //a move that leaves the own king in check is not played
+            let legal = played.try_play(action).is_ok();
+            assert!(!legal || in_sync(&played), "{}", action);
+            let undo = cl.make(board, m, true);
+            assert!(!legal || cl.snapshot() == played.snapshot(), "{}", action);
+            assert!(in_sync(cl), "{}", action);
+            check(cl, board, depth - 1);
+            cl.unmake(&undo);
+            assert!(cl.snapshot() == before && in_sync(cl), "{}", action);
+        }
+    }
+    //castling, en passant, promotions and deploys
+    for (bfen, depth) in [
+        (
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            2,
+        ),
+        ("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", 3),
+        ("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1", 2),
+        ("2k5/8/8/8/8/8/8/4K3[QRBNPqrbnp] w - - 0 1", 1),
+    ]
+    .iter()
+    {
+        for board in [BoardId::Board1, BoardId::Board2].iter() {
+            let mut cl = perft::position_on(bfen, *board, rules).unwrap();
+            check(&mut cl, *board, *depth);
+        }
     }
 
-    cl.movemaker(true,7,6,5,5);
-    a = gen_fen(&mut cl);
-    st = "rnbqkbnr/pp1ppppp/8/2p5/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2".to_string();
-    match a {
-        (b,c) => {
-            println!("{}",b);println!("{}",c);
-            println!("{:?}",cl.get_pawn_in_last_turn(true));
-            assert_eq!(st,b); 
-           },
-        _ => {},
+    //without the partner pool a capture leaves the pools of the other board
+    let mut cl = ChessLogic::new();
+    for m in ["e4", "d5"].iter() {
+        assert!(cl.play_san(b1, m) == Ok(true));
+    }
+    let pools = cl.get_pools();
+    let capture = CompactMove::new(e4, Square::from_name("d5").unwrap());
+    let undo = cl.make(b1, capture, false);
+    assert!(cl.get_pools() == pools);
+    cl.unmake(&undo);
+    cl.make(b1, capture, true);
+    assert!(cl.get_pools() != pools);
+
+    //perft with make and unmake
+    fn count(cl: &mut ChessLogic, board: BoardId, depth: usize) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        let seat = Seat::new(board, Color::from_white(cl.get_white_active(board.is_board1())));
+        let mut n = 0;
+        for m in cl.compact_moves(board) {
+            let undo = cl.make(board, m, true);
+            if !cl.is_in_check(seat) {
+                n += count(cl, board, depth - 1);
+            }
+            cl.unmake(&undo);
+        }
+        n
+    }
+    for board in [BoardId::Board1, BoardId::Board2].iter() {
+        let mut cl = perft::position_on(
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            *board,
+            rules,
+        )
+        .unwrap();
+        assert_eq!(count(&mut cl, *board, 3), 97862, "{:?}", board);
+        let mut cl = perft::position_on("2k5/8/8/8/8/8/8/4K3[QRBNPqrbnp] w - - 0 1", *board, rules).unwrap();
+        assert_eq!(count(&mut cl, *board, 2), 75353, "{:?}", board);
     }
 }
@@ -0,0 +1,353 @@
+use crate::logic::board::Piece;
+use crate::parse::parser::{char2ind, ind2char, ind2line, line2line};
+use std::fmt;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+///A square of a chess board
+///
+///The row and col are the array indices of the board, (0,0) is a8 and (7,7) is h1.
+///A Square is always within the bounds of the board.
+pub struct Square {
+    row: usize,
+    col: usize,
+}
+
+impl Square {
+    ///Creates a square from array indices, None if the indices are out of bounds
+    /// # Arguments
+    /// * `row` - row index, 0 is the 8th rank
+    /// * `col` - col index, 0 is the a-file
+    pub fn new(row: usize, col: usize) -> Option<Square> {
+        if row < 8 && col < 8 {
+            Some(Square { row, col })
+        } else {
+            None
+        }
+    }
+
+    ///Creates a square from its algebraic name such as "e4", None if the name is not legal
+    /// # Arguments
+    /// * `name` - file and rank of the square
+    pub fn from_name(name: &str) -> Option<Square> {
+        let mut chars = name.chars();
+        let col = char2ind(chars.next()?)?;
+        let row = line2line(chars.next()?)?;
+        if chars.next().is_some() {
+            return None;
+        }
+        Some(Square { row, col })
+    }
+
+    ///Returns the algebraic name of the square such as "e4"
+    pub fn name(&self) -> String {
+        format!("{}", self)
+    }
+
+    ///Returns the row index of the square, 0 is the 8th rank
+    pub fn get_row(&self) -> usize {
+        self.row
+    }
+
+    ///Returns the col index of the square, 0 is the a-file
+    pub fn get_col(&self) -> usize {
+        self.col
+    }
+}
+
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", ind2char(self.col).unwrap(), ind2line(self.row).unwrap())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+///A move of a piece on a board
+///
+///promotion is the piece a pawn is promoted to, the colour of the piece does not matter
+pub struct Move {
+    pub from: Square,
+    pub to: Square,
+    pub promotion: Option<Piece>,
+}
+
+impl Move {
+    ///Creates a move without promotion
+    /// # Arguments
+    /// * `from` - the square of the piece
+    /// * `to` - the square the piece moves to
+    pub fn new(from: Square, to: Square) -> Move {
+        Move {
+            from,
+            to,
+            promotion: None,
+        }
+    }
+
+    ///Creates a promoting move
+    /// # Arguments
+    /// * `from` - the square of the pawn
+    /// * `to` - the square on the last rank
+    /// * `promotion` - the piece to promote to
+    pub fn with_promotion(from: Square, to: Square, promotion: Piece) -> Move {
+        Move {
+            from,
+            to,
+            promotion: Some(promotion),
+        }
+    }
+
+    ///Reads a move in coordinate notation, None if the input is not legal
+    /// # Arguments
+    /// * `name` - a move such as "e2-e4", "e2e4", "e7-e8=Q" or "e7e8q"
+    pub fn from_name(name: &str) -> Option<Move> {
+        let s: String = name.chars().filter(|c| *c != '-' && *c != '=').collect();
+        if s.len() < 4 || !s.is_ascii() {
+            return None;
+        }
+        let from = Square::from_name(&s[0..2])?;
+        let to = Square::from_name(&s[2..4])?;
+        match &s[4..] {
+            "" => Some(Move::new(from, to)),
+            p => {
+                let promotion = match p {
+                    "Q" | "q" => Piece::Q,
+                    "R" | "r" => Piece::R,
+                    "B" | "b" => Piece::B,
+                    "N" | "n" => Piece::N,
+                    _ => return None,
+                };
+                Some(Move::with_promotion(from, to, promotion))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.from, self.to)?;
+        if let Some(promotion) = self.promotion {
+            write!(f, "={}", promotion.to_string().to_uppercase())?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+///A deploy of a piece from the pool, the colour of the piece is the colour of the player
+pub struct Drop {
+    pub piece: Piece,
+    pub to: Square,
+}
+
+impl Drop {
+    ///Creates a deploy
+    /// # Arguments
+    /// * `piece` - the piece to deploy
+    /// * `to` - the square to deploy on
+    pub fn new(piece: Piece, to: Square) -> Drop {
+        Drop { piece, to }
+    }
+
+    ///Reads a deploy in the form "N@f3", None if the input is not legal
+    /// # Arguments
+    /// * `name` - piece letter, '@' and the square
+    /// * `white` - true if the deployed piece is white
+    pub fn from_name(name: &str, white: bool) -> Option<Drop> {
+        let mut splt = name.split('@');
+        let p = splt.next()?;
+        let to = Square::from_name(splt.next()?)?;
+        if splt.next().is_some() {
+            return None;
+        }
+        let piece = match (p, white) {
+            ("P", true) | ("p", true) | ("", true) => Piece::P,
+            ("R", true) | ("r", true) => Piece::R,
+            ("N", true) | ("n", true) => Piece::N,
+            ("B", true) | ("b", true) => Piece::B,
+            ("Q", true) | ("q", true) => Piece::Q,
+            ("P", false) | ("p", false) | ("", false) => Piece::p,
+            ("R", false) | ("r", false) => Piece::r,
+            ("N", false) | ("n", false) => Piece::n,
+            ("B", false) | ("b", false) => Piece::b,
+            ("Q", false) | ("q", false) => Piece::q,
+            _ => return None,
+        };
+        Some(Drop { piece, to })
+    }
+
+    ///Returns true if the deployed piece is white
+    pub fn is_white(&self) -> bool {
+        self.piece.is_white()
+    }
+}
+
+impl fmt::Display for Drop {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{}", self.piece.to_string().to_uppercase(), self.to)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+///Enum class for the two boards of a bughouse game
+pub enum BoardId {
+    Board1,
+    Board2,
+}
+
+impl BoardId {
+    ///Converts the board1 boolean of the ChessLogic functions
+    /// # Arguments
+    /// * `board1` - true if board1, false if board2
+    pub fn from_board1(board1: bool) -> BoardId {
+        if board1 {
+            BoardId::Board1
+        } else {
+            BoardId::Board2
+        }
+    }
+
+    ///Returns the board1 boolean of the ChessLogic functions
+    pub fn is_board1(&self) -> bool {
+        *self == BoardId::Board1
+    }
+
+    ///Returns the other board
+    pub fn other(&self) -> BoardId {
+        match self {
+            BoardId::Board1 => BoardId::Board2,
+            BoardId::Board2 => BoardId::Board1,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+///Enum class for the colour of a player
+pub enum Color {
+    White,
+    Black,
+}
+
+impl Color {
+    ///Converts the white boolean of the ChessLogic functions
+    /// # Arguments
+    /// * `white` - true if white, false if black
+    pub fn from_white(white: bool) -> Color {
+        if white {
+            Color::White
+        } else {
+            Color::Black
+        }
+    }
+
+    ///Returns the white boolean of the ChessLogic functions
+    pub fn is_white(&self) -> bool {
+        *self == Color::White
+    }
+
+    ///Returns the other colour
+    pub fn opposite(&self) -> Color {
+        match self {
+            Color::White => Color::Black,
+            Color::Black => Color::White,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+///One of the four players of a bughouse game
+pub struct Seat {
+    pub board: BoardId,
+    pub color: Color,
+}
+
+impl Seat {
+    ///Creates a seat
+    /// # Arguments
+    /// * `board` - the board of the player
+    /// * `color` - the colour of the player
+    pub fn new(board: BoardId, color: Color) -> Seat {
+        Seat { board, color }
+    }
+
+    ///Returns the teammate, the player on the other board with the other colour
+    ///
+    ///The pieces this player captures are sent to the pool of the teammate.
+    pub fn partner(&self) -> Seat {
+        Seat {
+            board: self.board.other(),
+            color: self.color.opposite(),
+        }
+    }
+
+    ///Returns the player sitting across the same board
+    pub fn opponent(&self) -> Seat {
+        Seat {
+            board: self.board,
+            color: self.color.opposite(),
+        }
+    }
+
+    ///Returns the team of the player
+    pub fn team(&self) -> Team {
+        match (self.board, self.color) {
+            (BoardId::Board1, Color::White) | (BoardId::Board2, Color::Black) => Team::Team1,
+            _ => Team::Team2,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+///Enum class for the two teams
+///
+///Team1 -> white on board1 and black on board2
+///Team2 -> black on board1 and white on board2
+pub enum Team {
+    Team1,
+    Team2,
+}
+
+impl Team {
+    ///Returns both players of the team, the player of board1 first
+    pub fn seats(&self) -> [Seat; 2] {
+        match self {
+            Team::Team1 => [
+                Seat::new(BoardId::Board1, Color::White),
+                Seat::new(BoardId::Board2, Color::Black),
+            ],
+            Team::Team2 => [
+                Seat::new(BoardId::Board1, Color::Black),
+                Seat::new(BoardId::Board2, Color::White),
+            ],
+        }
+    }
+
+    ///Returns the other team
+    pub fn other(&self) -> Team {
+        match self {
+            Team::Team1 => Team::Team2,
+            Team::Team2 => Team::Team1,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+///Enum class for the result of the game from the point of view of the teams
+pub enum TeamResult {
+    Ongoing,
+    Won(Team),
+    Draw,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+///Enum class for the state of the king of a player
+///
+///Safe -> the king is not attacked
+///Check -> the king is attacked, a move or a deploy from the pool ends the check
+///Sitting -> nothing ends the check now, but a deploy could block it once the teammate sends a piece
+///Checkmate -> nothing ends the check and no deploy could block it
+pub enum CheckStatus {
+    Safe,
+    Check,
+    Sitting,
+    Checkmate,
+}
@@ -0,0 +1,248 @@
+use crate::logic::board::{ChessBoard, Piece};
+use crate::logic::history::Snapshot;
+use std::sync::OnceLock;
+
+///Pool counts above this share a key
+const MAX_COUNT: usize = 31;
+
+///Random keys of the position hash, they are the same in every run
+struct Keys {
+    ///A key for each piece on each square
+    pieces: [[u64; 64]; 20],
+    ///Key of white to move
+    white_active: u64,
+    ///A key for each castling flag, see castling_flags
+    castling: [u64; 6],
+    ///A key for each file of an en passant target
+    en_passant: [u64; 8],
+    ///A key for each count of each piece in the pool of each colour
+    pools: [[[u64; MAX_COUNT + 1]; 5]; 2],
+}
+
+///Returns the keys, they are generated at the first call
+fn keys() -> &'static Keys {
+    static KEYS: OnceLock<Keys> = OnceLock::new();
+    KEYS.get_or_init(|| {
+        //splitmix64 with a fixed seed
+        let mut state: u64 = 0x2545_F491_4F6C_DD1D;
+        let mut next = || {
+            state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        };
+        let mut keys = Keys {
+            pieces: [[0; 64]; 20],
+            white_active: 0,
+            castling: [0; 6],
+            en_passant: [0; 8],
+            pools: [[[0; MAX_COUNT + 1]; 5]; 2],
+        };
+        for p in keys.pieces.iter_mut() {
+            for k in p.iter_mut() {
+                *k = next();
+            }
+        }
+        keys.white_active = next();
+        for k in keys.castling.iter_mut() {
+            *k = next();
+        }
+        for k in keys.en_passant.iter_mut() {
+            *k = next();
+        }
+        for colour in keys.pools.iter_mut() {
+            for p in colour.iter_mut() {
+                for k in p.iter_mut() {
+                    *k = next();
+                }
+            }
+        }
+        keys
+    })
+}
+
+///Returns the index of a piece in the keys, None for E and L
+fn piece_index(piece: Piece) -> Option<usize> {
+    match piece {
+        Piece::P => Some(0),
+        Piece::R => Some(1),
+        Piece::N => Some(2),
+        Piece::B => Some(3),
+        Piece::Q => Some(4),
+        Piece::K => Some(5),
+        Piece::p => Some(6),
+        Piece::r => Some(7),
+        Piece::n => Some(8),
+        Piece::b => Some(9),
+        Piece::q => Some(10),
+        Piece::k => Some(11),
+        Piece::UR => Some(12),
+        Piece::UN => Some(13),
+        Piece::UB => Some(14),
+        Piece::UQ => Some(15),
+        Piece::Ur => Some(16),
+        Piece::Un => Some(17),
+        Piece::Ub => Some(18),
+        Piece::Uq => Some(19),
+        Piece::E | Piece::L => None,
+    }
+}
+
+///Returns the flags of a board that decide if castling is possible
+pub(crate) fn castling_flags(b: &ChessBoard) -> [bool; 6] {
+    [
+        b.white_k_moved,
+        b.white_rook_k_moved,
+        b.white_rook_q_moved,
+        b.black_k_moved,
+        b.black_rook_k_moved,
+        b.black_rook_q_moved,
+    ]
+}
+
+///The parts of a snapshot that belong to one board
+struct Position<'a> {
+    board: &'a ChessBoard,
+    white_active: bool,
+    pawn_in_last_turn: Option<(usize, usize)>,
+    pools: [&'a [u8; 5]; 2],
+}
+
+impl<'a> Position<'a> {
+    fn of(s: &'a Snapshot, board1: bool) -> Position<'a> {
+        if board1 {
+            Position {
+                board: &s.chess_board1,
+                white_active: s.white_active_1,
+                pawn_in_last_turn: s.pawn_in_last_turn_b1,
+                pools: [&s.board1_white_capture, &s.board1_black_capture],
+            }
+        } else {
+            Position {
+                board: &s.chess_board2,
+                white_active: s.white_active_2,
+                pawn_in_last_turn: s.pawn_in_last_turn_b2,
+                pools: [&s.board2_white_capture, &s.board2_black_capture],
+            }
+        }
+    }
+
+    ///Returns the key of the en passant target, 0 if a pawn cannot be captured en passant
+    fn en_passant_key(&self) -> u64 {
+        en_passant_key(self.board, self.pawn_in_last_turn)
+    }
+}
+
+///Returns the key of the en passant target of a board, 0 if a pawn cannot be captured en passant
+/// # Arguments
+/// * `b` - the board
+/// * `pawn_in_last_turn` - the pawn that moved last on the board
+pub(crate) fn en_passant_key(b: &ChessBoard, pawn_in_last_turn: Option<(usize, usize)>) -> u64 {
+    match pawn_in_last_turn {
+        Some((i, j)) if (i == 4 && b.board[i][j] == Piece::P) || (i == 3 && b.board[i][j] == Piece::p) => {
+            keys().en_passant[j]
+        }
+        _ => 0,
+    }
+}
+
+///Returns the key of a piece on a square, 0 for an empty square
+pub(crate) fn square_key(piece: Piece, i: usize, j: usize) -> u64 {
+    match piece_index(piece) {
+        Some(x) => keys().pieces[x][i * 8 + j],
+        None => 0,
+    }
+}
+
+///Returns the key of a count of a piece in a pool
+/// # Arguments
+/// * `colour` - 0 for the pool of white, 1 for the pool of black
+/// * `ind` - the index of the piece, the order is P-R-N-B-Q
+/// * `count` - the number of pieces
+pub(crate) fn pool_key(colour: usize, ind: usize, count: u8) -> u64 {
+    keys().pools[colour][ind][(count as usize).min(MAX_COUNT)]
+}
+
+///Returns the key of white to move
+pub(crate) fn white_active_key() -> u64 {
+    keys().white_active
+}
+
+///Returns the key of a castling flag
+/// # Arguments
+/// * `f` - the index of the flag, see castling_flags
+pub(crate) fn castling_key(f: usize) -> u64 {
+    keys().castling[f]
+}
+
+///Computes the hash of a board from scratch
+/// # Arguments
+/// * `s` - the state of the game
+/// * `board1` - true if board1, false if board2
+///
+///The hash covers the pieces, the player to move, the castling flags, the en passant target and the pools of both players of the board.
+pub fn hash_board(s: &Snapshot, board1: bool) -> u64 {
+    let k = keys();
+    let pos = Position::of(s, board1);
+    let mut h = 0;
+    for i in 0..8 {
+        for j in 0..8 {
+            h ^= square_key(pos.board.board[i][j], i, j);
+        }
+    }
+    if pos.white_active {
+        h ^= k.white_active;
+    }
+    for (f, key) in castling_flags(pos.board).iter().zip(k.castling.iter()) {
+        if *f {
+            h ^= key;
+        }
+    }
+    h ^= pos.en_passant_key();
+    for (colour, pool) in pos.pools.iter().enumerate() {
+        for (ind, count) in pool.iter().enumerate() {
+            h ^= pool_key(colour, ind, *count);
+        }
+    }
+    h
+}
+
+///Updates the hash of a board with the parts that changed between two states
+/// # Arguments
+/// * `hash` - the hash of the board in the state before
+/// * `before` - the state before an action
+/// * `after` - the state after the action
+/// * `board1` - true if board1, false if board2
+pub fn update(hash: u64, before: &Snapshot, after: &Snapshot, board1: bool) -> u64 {
+    let k = keys();
+    let (old, new) = (Position::of(before, board1), Position::of(after, board1));
+    let mut h = hash;
+    for i in 0..8 {
+        for j in 0..8 {
+            let (old_piece, new_piece) = (old.board.board[i][j], new.board.board[i][j]);
+            if old_piece != new_piece {
+                h ^= square_key(old_piece, i, j) ^ square_key(new_piece, i, j);
+            }
+        }
+    }
+    if old.white_active != new.white_active {
+        h ^= k.white_active;
+    }
+    let (fa, fb) = (castling_flags(old.board), castling_flags(new.board));
+    for f in 0..6 {
+        if fa[f] != fb[f] {
+            h ^= k.castling[f];
+        }
+    }
+    h ^= old.en_passant_key() ^ new.en_passant_key();
+    for colour in 0..2 {
+        for ind in 0..5 {
+            let (a, b) = (old.pools[colour][ind], new.pools[colour][ind]);
+            if a != b {
+                h ^= pool_key(colour, ind, a) ^ pool_key(colour, ind, b);
+            }
+        }
+    }
+    h
+}
@@ -1,37 +1,34 @@
 pub mod parser {
     ///Parses an input such as "e2-e4" and converts into a form that Chesslogic will understand
-    /// 
+    ///
     /// Input is of form: column index,row index,-,new column index,new row index
-    pub fn parse(input: &String) -> Option<[usize; 4]> {
-
-        let mut rt = [8;4];
+    pub fn parse(input: &str) -> Option<[usize; 4]> {
+        let mut rt = [8; 4];
         let mut rtc = 1;
-        let mut splt = input.split("-");
+        let splt = input.split("-");
 
         for s in splt {
             let mut f = true;
             for c in s.chars() {
-                if c!='\n' {
+                if c != '\n' {
                     if f {
                         if let Some(x) = char2ind(c) {
                             rt[rtc] = x;
-                        }else{
-                            return None
+                        } else {
+                            return None;
                         }
-                        rtc -=1;
+                        rtc -= 1;
                         f = false;
-                    }else{
+                    } else {
                         if let Some(x) = line2line(c) {
                             rt[rtc] = x;
-                        }else{
-                            return None
+                        } else {
+                            return None;
                         }
-                        rtc +=3;
+                        rtc += 3;
                     }
                 }
-               
             }
-
         }
 
         Some(rt)
@@ -41,13 +38,13 @@ pub mod parser {
     pub fn char2ind(a: char) -> Option<usize> {
         match a {
             'a' | 'A' => Some(0),
-            'b' | 'B'  => Some(1),
-            'c' | 'C'  => Some(2),
-            'd' | 'D'  => Some(3),
-            'e' | 'E'  => Some(4),
-            'f' | 'F'  => Some(5),
-            'g' | 'G'  => Some(6),
-            'h' | 'H'  => Some(7),
+            'b' | 'B' => Some(1),
+            'c' | 'C' => Some(2),
+            'd' | 'D' => Some(3),
+            'e' | 'E' => Some(4),
+            'f' | 'F' => Some(5),
+            'g' | 'G' => Some(6),
+            'h' | 'H' => Some(7),
             _ => None,
         }
     }
@@ -78,7 +75,7 @@ pub mod parser {
             '6' => Some(2),
             '7' => Some(1),
             '8' => Some(0),
-            _ =>  None,
+            _ => None,
         }
     }
 
@@ -96,5 +93,217 @@ pub mod parser {
             _ => None,
         }
     }
+}
+pub mod san {
+    use crate::logic::board::Piece;
+    use crate::logic::history::Action;
+    use crate::logic::types::{BoardId, CheckStatus, Color, Drop, Move, Seat, Square};
+    use crate::logic::ChessLogic;
+    use crate::parse::parser::{char2ind, ind2char, ind2line, line2line};
+
+    ///Returns the uppercase SAN letter of a piece, upgraded pieces have the letter of the piece they became
+    pub fn piece_letter(piece: Piece) -> char {
+        piece.to_string().to_uppercase().chars().next().unwrap_or(' ')
+    }
+
+    ///Returns the seat of the active player of a board
+    fn active_seat(cl: &ChessLogic, board: BoardId) -> Seat {
+        Seat::new(board, Color::from_white(cl.get_white_active(board.is_board1())))
+    }
+
+    ///Converts a move or deploy of the active player to SAN, None if the action is not legal
+    /// # Arguments
+    /// * `cl` - the game the action is played in
+    /// * `action` - the action to convert
+    ///
+    /// Captures are marked with x, castling is written as O-O or O-O-O, promotions as e8=Q
+    /// and deploys as N@f3 or P@e4. A check is marked with +, a checkmate with #.
+    /// A check that could be blocked by a deploy of a piece the player does not have yet is marked with +.
+    pub fn to_san(cl: &ChessLogic, action: &Action) -> Option<String> {
+        let board = action.get_board();
+        let seat = active_seat(cl, board);
+        let legal = cl.get_all_legal_actions(seat);
+        if !legal.contains(action) {
+            return None;
+        }
+        let mut san = match action {
+            Action::Drop { drop, .. } => drop.to_string(),
+            Action::Move { mv, .. } => move_body(cl, board, mv, &legal),
+        };
+        let after = cl.preview(*action).ok()?;
+        let opponent = seat.opponent();
+        if after.classify(opponent) == CheckStatus::Checkmate {
+            san.push('#');
+        } else if after.is_in_check(opponent) {
+            san.push('+');
+        }
+        Some(san)
+    }
+
+    ///SAN of a legal move without the check marker
+    fn move_body(cl: &ChessLogic, board: BoardId, mv: &Move, legal: &[Action]) -> String {
+        let piece = cl.piece_at(board, mv.from);
+        let target = cl.piece_at(board, mv.to);
+        if (piece == Piece::K || piece == Piece::k) && mv.from.get_col() == 4 {
+            match mv.to.get_col() {
+                6 if mv.from.get_row() == mv.to.get_row() => return String::from("O-O"),
+                2 if mv.from.get_row() == mv.to.get_row() => return String::from("O-O-O"),
+                _ => {}
+            }
+        }
+        let mut san = String::new();
+        if piece == Piece::P || piece == Piece::p {
+            //a pawn moving to another file always captures, also en passant
+            if mv.from.get_col() != mv.to.get_col() {
+                san.push(ind2char(mv.from.get_col()).unwrap());
+                san.push('x');
+            }
+            san.push_str(&mv.to.name());
+            if let Some(prom) = mv.promotion {
+                san.push('=');
+                san.push(piece_letter(prom));
+            }
+            return san;
+        }
+        let letter = piece_letter(piece);
+        san.push(letter);
+        //other pieces of the same kind that can go to the same square
+        let others: Vec<Square> = legal
+            .iter()
+            .filter_map(|a| match a {
+                Action::Move { mv: m, .. } => Some(*m),
+                _ => None,
+            })
+            .filter(|m| m.to == mv.to && m.from != mv.from)
+            .filter(|m| {
+                let other = cl.piece_at(board, m.from);
+                other != Piece::P && other != Piece::p && piece_letter(other) == letter
+            })
+            .map(|m| m.from)
+            .collect();
+        if !others.is_empty() {
+            let same_col = others.iter().any(|s| s.get_col() == mv.from.get_col());
+            let same_row = others.iter().any(|s| s.get_row() == mv.from.get_row());
+            if !same_col {
+                san.push(ind2char(mv.from.get_col()).unwrap());
+            } else if !same_row {
+                san.push(ind2line(mv.from.get_row()).unwrap());
+            } else {
+                san.push_str(&mv.from.name());
+            }
+        }
+        if target != Piece::E {
+            san.push('x');
+        }
+        san.push_str(&mv.to.name());
+        san
+    }
 
-}
\ No newline at end of file
+    ///Reads a move or deploy in SAN for the active player of a board, None if it is not exactly one legal action
+    /// # Arguments
+    /// * `cl` - the game the action is played in
+    /// * `board` - the board of the action
+    /// * `san` - the action such as "Nbd2", "exd5", "O-O-O", "e8=Q", "N@f3" or "@e4"
+    ///
+    /// Check and annotation markers (+,#,!,?) are ignored and castling can be written with 0 or O.
+    pub fn from_san(cl: &ChessLogic, board: BoardId, san: &str) -> Option<Action> {
+        let seat = active_seat(cl, board);
+        let white = seat.color.is_white();
+        let s = san.trim().trim_end_matches(['+', '#', '!', '?']);
+        if !s.is_ascii() || s.is_empty() {
+            return None;
+        }
+        let legal = cl.get_all_legal_actions(seat);
+
+        if s.contains('@') {
+            let drop = Drop::from_name(s, white)?;
+            let action = Action::Drop { board, drop };
+            return if legal.contains(&action) { Some(action) } else { None };
+        }
+
+        let castle = match s {
+            "O-O" | "0-0" => Some(6),
+            "O-O-O" | "0-0-0" => Some(2),
+            _ => None,
+        };
+        if let Some(col) = castle {
+            let row = if white { 7 } else { 0 };
+            let mv = Move::new(Square::new(row, 4)?, Square::new(row, col)?);
+            let king = if white { Piece::K } else { Piece::k };
+            let action = Action::Move { board, mv };
+            return if cl.piece_at(board, mv.from) == king && legal.contains(&action) {
+                Some(action)
+            } else {
+                None
+            };
+        }
+
+        let mut rest = s;
+        //piece letter, a pawn has none
+        let letter = match rest.chars().next()? {
+            c @ ('K' | 'Q' | 'R' | 'B' | 'N') => {
+                rest = &rest[1..];
+                c
+            }
+            _ => 'P',
+        };
+        //promotion, e8=Q or e8Q
+        let mut promotion = None;
+        if letter == 'P' {
+            if let Some(c) = rest.chars().last() {
+                if "QRBNqrbn".contains(c) {
+                    promotion = Some(piece_letter(match c.to_ascii_uppercase() {
+                        'Q' => Piece::Q,
+                        'R' => Piece::R,
+                        'B' => Piece::B,
+                        _ => Piece::N,
+                    }));
+                    rest = rest[..rest.len() - 1].trim_end_matches('=');
+                }
+            }
+        }
+        if rest.len() < 2 {
+            return None;
+        }
+        let to = Square::from_name(&rest[rest.len() - 2..])?;
+        //without a promotion the pawn becomes a queen if the rules allow it
+        let last = if white { 0 } else { 7 };
+        if letter == 'P' && promotion.is_none() && to.get_row() == last && cl.get_rules().auto_queen {
+            promotion = Some('Q');
+        }
+        //disambiguation and capture marker
+        let mut from_col = None;
+        let mut from_row = None;
+        for c in rest[..rest.len() - 2].chars() {
+            match c {
+                'x' | ':' => {}
+                c if char2ind(c).is_some() && c.is_ascii_lowercase() => from_col = char2ind(c),
+                c if line2line(c).is_some() => from_row = line2line(c),
+                _ => return None,
+            }
+        }
+
+        let mut found = None;
+        for action in legal {
+            if let Action::Move { mv, .. } = action {
+                let piece = cl.piece_at(board, mv.from);
+                let is_pawn = piece == Piece::P || piece == Piece::p;
+                if mv.to != to
+                    || (letter == 'P') != is_pawn
+                    || (!is_pawn && piece_letter(piece) != letter)
+                    || from_col.is_some_and(|c| c != mv.from.get_col())
+                    || from_row.is_some_and(|r| r != mv.from.get_row())
+                    || mv.promotion.map(piece_letter) != promotion
+                {
+                    continue;
+                }
+                if found.is_some() {
+                    //ambiguous
+                    return None;
+                }
+                found = Some(action);
+            }
+        }
+        found
+    }
+}
@@ -0,0 +1,242 @@
+///A module to step through a recorded game
+///
+/// The cursor stands on a global ply, the number of actions played on both boards. Ply 0 is the start position,
+/// ply n is the state after the n-th action of the history.
+pub mod replay {
+    use crate::infoCourier::infoCourier::{gen_bfen, gen_fen};
+    use crate::logic::history::{Action, HistoryEntry, Snapshot};
+    use crate::logic::rules::Rules;
+    use crate::logic::types::{BoardId, Color};
+    use crate::logic::ChessLogic;
+
+    ///A cursor over the actions of a recorded game
+    pub struct Replay {
+        ///The state before the first action
+        start: Snapshot,
+        ///The actions of both boards in the order they were played
+        log: Vec<HistoryEntry>,
+        ///Rules the game was played with
+        rules: Rules,
+        ///The current ply
+        ply: usize,
+        ///The game at the current ply
+        current: ChessLogic,
+    }
+
+    impl Replay {
+        ///Creates a cursor over the history of a game, it stands on the start position
+        /// # Arguments
+        /// * `cl` - the game, e.g. a finished game or a game read by bpgn::import
+        pub fn new(cl: &ChessLogic) -> Replay {
+            let log = cl.get_history().clone();
+            let start = match log.first() {
+                Some(e) => *e.get_before(),
+                None => cl.snapshot(),
+            };
+            let rules = cl.get_rules();
+            Replay {
+                start,
+                log,
+                rules,
+                ply: 0,
+                current: Replay::at(&start, rules),
+            }
+        }
+
+        ///Creates a game without clock and history from a state
+        fn at(s: &Snapshot, rules: Rules) -> ChessLogic {
+            let mut cl = ChessLogic::from_snapshot(s);
+            cl.set_rules(rules);
+            cl
+        }
+
+        ///Returns the current ply
+        pub fn get_ply(&self) -> usize {
+            self.ply
+        }
+
+        ///Returns the number of actions of the game, the last ply
+        pub fn len(&self) -> usize {
+            self.log.len()
+        }
+
+        ///Returns true if the game has no actions
+        pub fn is_empty(&self) -> bool {
+            self.log.is_empty()
+        }
+
+        ///Returns the game at the current ply, it has the boards and the four pools but neither clock nor history
+        pub fn get_logic(&self) -> &ChessLogic {
+            &self.current
+        }
+
+        ///Returns the action that led to the current ply, None at the start position
+        pub fn get_action(&self) -> Option<Action> {
+            self.ply.checked_sub(1).map(|n| self.log[n].action)
+        }
+
+        ///Returns the history entry of the action that led to the current ply, e.g. for the remaining time of the player
+        pub fn get_entry(&self) -> Option<&HistoryEntry> {
+            self.ply.checked_sub(1).map(|n| &self.log[n])
+        }
+
+        ///Moves the cursor to a ply, returns false if the game has less actions
+        /// # Arguments
+        /// * `ply` - the ply, 0 for the start position
+        pub fn jump(&mut self, ply: usize) -> bool {
+            if ply > self.log.len() {
+                return false;
+            }
+            let s = match ply {
+                0 => self.start,
+                n => *self.log[n - 1].get_after(),
+            };
+            self.current = Replay::at(&s, self.rules);
+            self.ply = ply;
+            true
+        }
+
+        ///Moves the cursor one action forward, returns false at the end of the game
+        pub fn forward(&mut self) -> bool {
+            self.jump(self.ply + 1)
+        }
+
+        ///Moves the cursor one action back, returns false at the start position
+        pub fn back(&mut self) -> bool {
+            match self.ply {
+                0 => false,
+                n => self.jump(n - 1),
+            }
+        }
+
+        ///Returns the ply after a move of a board, None if the move was not played
+        /// # Arguments
+        /// * `board` - the board of the move
+        /// * `number` - the move number on the board, e.g. 23 for *23A.*
+        /// * `color` - the player that moved
+        pub fn find_move(&self, board: BoardId, number: usize, color: Color) -> Option<usize> {
+            let board1 = board.is_board1();
+            self.log
+                .iter()
+                .position(|e| {
+                    let before = e.get_before();
+                    let white = if board1 {
+                        before.white_active_1
+                    } else {
+                        before.white_active_2
+                    };
+                    let movectr = if board1 { before.movectr1 } else { before.movectr2 };
+                    e.action.get_board() == board && movectr == number && white == color.is_white()
+                })
+                .map(|n| n + 1)
+        }
+
+        ///Moves the cursor to the state after a move of a board, e.g. board A move 23 of black, returns false if the move was not played
+        /// # Arguments
+        /// * `board` - the board of the move
+        /// * `number` - the move number on the board
+        /// * `color` - the player that moved
+        pub fn jump_to_move(&mut self, board: BoardId, number: usize, color: Color) -> bool {
+            match self.find_move(board, number, color) {
+                Some(ply) => self.jump(ply),
+                None => false,
+            }
+        }
+
+        ///Returns the FEN strings of both boards at the current ply
+        pub fn fen(&self) -> (String, String) {
+            gen_fen(&self.current)
+        }
+
+        ///Returns the BFEN strings of both boards at the current ply, they include the pools
+        pub fn bfen(&self) -> (String, String) {
+            gen_bfen(&self.current)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::logic::types::{Drop, Move, Seat};
+        use crate::logic::Winner;
+
+        #[test]
+        fn replay_cursor() {
+            let (b1, b2) = (BoardId::Board1, BoardId::Board2);
+            let mut cl = ChessLogic::new();
+            let start = gen_bfen(&cl);
+            let mut seen = Vec::new();
+            let actions = [
+                (b1, "e4"),
+                (b2, "d4"),
+                (b1, "d5"),
+                (b1, "exd5"),
+                (b2, "P@e5"),
+                (b2, "dxe5"),
+                (b1, "Qxd5"),
+                (b2, "Nc6"),
+            ];
+            for (board, san) in actions.iter() {
+                assert!(cl.play_san(*board, san) == Ok(true));
+                seen.push((gen_fen(&cl), gen_bfen(&cl)));
+            }
+            cl.resign_seat(Seat::new(b1, Color::White));
+
+            let mut r = Replay::new(&cl);
+            assert_eq!(r.len(), actions.len());
+            assert_eq!(r.get_ply(), 0);
+            assert_eq!(r.bfen(), start);
+            assert!(r.get_action().is_none());
+            assert!(!r.back());
+            for (fen, bfen) in seen.iter() {
+                assert!(r.forward());
+                assert_eq!(r.fen(), *fen);
+                assert_eq!(r.bfen(), *bfen);
+            }
+            assert!(!r.forward());
+            assert!(r.get_logic().get_winner(true) == Winner::N);
+
+            //both boards and all pools as they were
+            assert!(r.jump(4));
+            assert_eq!(r.get_logic().get_pool(Seat::new(b2, Color::Black)), [1, 0, 0, 0, 0]);
+            assert!(r.back());
+            assert_eq!(r.get_logic().get_pool(Seat::new(b2, Color::Black)), [0, 0, 0, 0, 0]);
+            assert!(
+                r.get_action()
+                    == Some(Action::Move {
+                        board: b1,
+                        mv: Move::from_name("d7-d5").unwrap()
+                    })
+            );
+
+            //board A move 2 of black is Qxd5, board B move 1 of black is the deploy
+            assert_eq!(r.find_move(b1, 2, Color::Black), Some(7));
+            assert!(r.jump_to_move(b2, 1, Color::Black));
+            assert_eq!(r.get_ply(), 5);
+            assert!(
+                r.get_action()
+                    == Some(Action::Drop {
+                        board: b2,
+                        drop: Drop::from_name("P@e5", false).unwrap()
+                    })
+            );
+            assert!(!r.jump_to_move(b2, 3, Color::White));
+            assert_eq!(r.get_ply(), 5);
+            assert!(!r.jump(actions.len() + 1));
+
+            //a black promotion counts as a move of black
+            let mut cl = ChessLogic::new();
+            for m in [
+                "h3", "a5", "h4", "a4", "h5", "a3", "h6", "axb2", "hxg7", "bxa1=Q", "Nf3", "Nc6",
+            ]
+            .iter()
+            {
+                assert!(cl.play_san(b1, m) == Ok(true), "{}", m);
+            }
+            let r = Replay::new(&cl);
+            assert_eq!(r.find_move(b1, 5, Color::Black), Some(10));
+            assert_eq!(r.find_move(b1, 6, Color::White), Some(11));
+            assert_eq!(r.find_move(b1, 6, Color::Black), Some(12));
+        }
+    }
+}